@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::tiles;
+use crate::{notifications::plural, panes::Markers, project_file::ProjectFile, tiles};
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(Serialize, Deserialize)]
@@ -8,8 +8,10 @@ use crate::tiles;
 pub struct App {
     pub tree: egui_tiles::Tree<tiles::Pane>,
 
-    #[serde(skip)]
     pub behavior: tiles::TreeBehavior,
+
+    #[serde(skip)]
+    pub project_file: ProjectFile,
 }
 
 impl Default for App {
@@ -19,6 +21,7 @@ impl Default for App {
         Self {
             tree,
             behavior: tiles::TreeBehavior::default(),
+            project_file: ProjectFile::default(),
         }
     }
 }
@@ -32,7 +35,24 @@ impl App {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            tiles::migrate_tree(&mut app.tree);
+            let state = &mut app.behavior.state;
+            state.restore_unsaved_changes();
+            state.project.load_files(state.project_path.as_deref());
+            let offline = state
+                .project
+                .offline_files()
+                .filter(|file| file.path.is_none())
+                .count();
+            if offline > 0 {
+                state.notifications.warning(format!(
+                    "{offline} media {} only kept in saved projects, open the project to restore {}",
+                    plural(offline, "file is", "files are"),
+                    plural(offline, "it", "them"),
+                ));
+            }
+            return app;
         }
 
         Default::default()
@@ -42,11 +62,43 @@ impl App {
 impl eframe::App for App {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let state = &mut self.behavior.state;
+        state.store_unsaved_changes();
+
+        // Media held in memory is too big for the storage, it's only saved with the project
+        let contents: Vec<_> = state
+            .project
+            .files
+            .iter_mut()
+            .map(|file| {
+                let frames = file
+                    .image_sequence
+                    .as_mut()
+                    .map(|image_sequence| std::mem::take(&mut image_sequence.content));
+                (std::mem::take(&mut file.content), frames)
+            })
+            .collect();
         eframe::set_value(storage, eframe::APP_KEY, self);
+        for (file, (content, frames)) in self.behavior.state.project.files.iter_mut().zip(contents)
+        {
+            file.content = content;
+            if let Some((image_sequence, frames)) = file.image_sequence.as_mut().zip(frames) {
+                image_sequence.content = frames;
+            }
+        }
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.project_file_ui(ctx);
+
+        let state = &mut self.behavior.state;
+        state.history.shortcuts(ctx, &mut state.project);
+        state.playback.shortcuts(ctx, &state.project.sequence);
+        Markers::shortcuts(ctx, state);
+        state.playback.update(ctx, &state.project.sequence);
+        state.thumbnails.update(ctx);
+
         self.menu_bar(ctx);
 
         self.footer(ctx);
@@ -60,5 +112,7 @@ impl eframe::App for App {
             .show(ctx, |ui| {
                 self.tree.ui(&mut self.behavior, ui);
             });
+
+        self.behavior.state.notifications.ui(ctx);
     }
 }
@@ -10,6 +10,13 @@ impl App {
                 }
                 ui.separator();
                 powered_by_egui_and_eframe(ui);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let notifications = &mut self.behavior.state.notifications;
+                    let count = notifications.history().len();
+                    ui.toggle_value(&mut notifications.show_history, format!("🔔 {count}"))
+                        .on_hover_text("Notifications");
+                });
             });
         });
     }
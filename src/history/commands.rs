@@ -0,0 +1,450 @@
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use super::Command;
+use crate::{
+    project::{AssetId, Bin, File, Organization, Project, Settings},
+    sequence::{FrameRate, Sequence},
+};
+
+/// Adds imported files to the end of `Project::files`, with bins made for them.
+///
+/// Files of one import are read one by one, consecutive commands of the same import are
+/// merged into a single undo step.
+pub struct ImportFiles {
+    name: String,
+    /// Id of the import.
+    import: u64,
+    /// Files and bins while they aren't in the project.
+    files: Vec<File>,
+    bins: Vec<Bin>,
+    file_ids: HashSet<AssetId>,
+    bin_ids: HashSet<AssetId>,
+}
+impl ImportFiles {
+    pub fn new(import: u64, file: File) -> Self {
+        Self {
+            name: format!("Import {}", file.name),
+            import,
+            file_ids: HashSet::from([file.id]),
+            bin_ids: HashSet::new(),
+            files: vec![file],
+            bins: Vec::new(),
+        }
+    }
+
+    /// Bins of an imported folder, its files follow.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn bins(import: u64, name: impl Into<String>, bins: Vec<Bin>) -> Self {
+        Self {
+            name: name.into(),
+            import,
+            file_ids: HashSet::new(),
+            bin_ids: bins.iter().map(|bin| bin.id).collect(),
+            files: Vec::new(),
+            bins,
+        }
+    }
+}
+impl Command for ImportFiles {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        project.bins.append(&mut self.bins);
+        project.files.append(&mut self.files);
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        self.bins = remove_where(&mut project.bins, |bin| self.bin_ids.contains(&bin.id));
+        self.files = remove_where(&mut project.files, |file| self.file_ids.contains(&file.id));
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = next.as_any().downcast_ref::<Self>() else {
+            return false;
+        };
+        if self.import != next.import {
+            return false;
+        }
+
+        self.file_ids.extend(&next.file_ids);
+        self.bin_ids.extend(&next.bin_ids);
+        // Folders keep their name
+        if self.bin_ids.is_empty() {
+            self.name = format!("Import {} files", self.file_ids.len());
+        }
+        true
+    }
+}
+
+/// Takes items accepted by the filter out of the list, keeping the order of both.
+fn remove_where<T>(items: &mut Vec<T>, filter: impl Fn(&T) -> bool) -> Vec<T> {
+    let (removed, kept) = std::mem::take(items).into_iter().partition(filter);
+    *items = kept;
+    removed
+}
+
+/// Renaming, reordering or moving files and bins, stored as snapshots of the organization.
+pub struct OrganizeFiles {
+    name: String,
+    before: Organization,
+    after: Organization,
+}
+impl OrganizeFiles {
+    pub fn new(name: impl Into<String>, before: Organization, after: Organization) -> Self {
+        Self {
+            name: name.into(),
+            before,
+            after,
+        }
+    }
+}
+impl Command for OrganizeFiles {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        project.set_organization(&self.after);
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        project.set_organization(&self.before);
+    }
+}
+
+/// Removes files and bins with everything in them, along with clips using the files.
+pub struct DeleteItems {
+    name: String,
+    files: Vec<AssetId>,
+    bins: Vec<AssetId>,
+    /// Removed files and bins with their indices, while they aren't in the project.
+    removed_files: Vec<(usize, File)>,
+    removed_bins: Vec<(usize, Bin)>,
+    /// Sequence before the clips were removed.
+    sequence: Option<Sequence>,
+}
+impl DeleteItems {
+    pub fn new(name: impl Into<String>, files: Vec<AssetId>, bins: Vec<AssetId>) -> Self {
+        Self {
+            name: name.into(),
+            files,
+            bins,
+            removed_files: Vec::new(),
+            removed_bins: Vec::new(),
+            sequence: None,
+        }
+    }
+}
+impl Command for DeleteItems {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        // Bins inside deleted bins, repeated until no more are found
+        let mut bins: HashSet<_> = self.bins.iter().copied().collect();
+        while let Some(bin) = project.bins.iter().find(|bin| {
+            !bins.contains(&bin.id) && bin.parent.is_some_and(|parent| bins.contains(&parent))
+        }) {
+            bins.insert(bin.id);
+        }
+        let files: HashSet<_> = project
+            .files
+            .iter()
+            .filter(|file| {
+                self.files.contains(&file.id) || file.bin.is_some_and(|bin| bins.contains(&bin))
+            })
+            .map(|file| file.id)
+            .collect();
+
+        let sequence = project.sequence.clone();
+        for track in &mut project.sequence.tracks {
+            track.clips.retain(|clip| !files.contains(&clip.file));
+        }
+        self.sequence = Some(sequence);
+
+        // From the end, so earlier indices stay valid
+        for index in (0..project.files.len()).rev() {
+            if files.contains(&project.files[index].id) {
+                self.removed_files
+                    .push((index, project.files.remove(index)));
+            }
+        }
+        for index in (0..project.bins.len()).rev() {
+            if bins.contains(&project.bins[index].id) {
+                self.removed_bins.push((index, project.bins.remove(index)));
+            }
+        }
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        for (index, file) in self.removed_files.drain(..).rev() {
+            project.files.insert(index, file);
+        }
+        for (index, bin) in self.removed_bins.drain(..).rev() {
+            project.bins.insert(index, bin);
+        }
+        if let Some(sequence) = self.sequence.take() {
+            project.sequence = sequence;
+        }
+    }
+}
+
+/// Any change of the sequence, stored as snapshots before and after the edit.
+pub struct EditSequence {
+    name: String,
+    before: Sequence,
+    after: Sequence,
+    /// Consecutive edits with the same id are merged, e.g. all steps of one drag.
+    merge_id: Option<egui::Id>,
+}
+impl EditSequence {
+    pub fn new(name: impl Into<String>, before: Sequence, after: Sequence) -> Self {
+        Self {
+            name: name.into(),
+            before,
+            after,
+            merge_id: None,
+        }
+    }
+
+    pub fn merge_id(mut self, id: egui::Id) -> Self {
+        self.merge_id = Some(id);
+        self
+    }
+}
+impl Command for EditSequence {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        project.sequence = self.after.clone();
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        project.sequence = self.before.clone();
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = next.as_any().downcast_ref::<Self>() else {
+            return false;
+        };
+        if self.merge_id.is_none() || self.merge_id != next.merge_id {
+            return false;
+        }
+
+        self.after = next.after.clone();
+        true
+    }
+}
+
+pub struct EditSettings {
+    before: Settings,
+    after: Settings,
+    /// Id of the widget, consecutive edits with it are merged.
+    merge_id: egui::Id,
+}
+impl EditSettings {
+    pub fn new(before: Settings, after: Settings, merge_id: egui::Id) -> Self {
+        Self {
+            before,
+            after,
+            merge_id,
+        }
+    }
+}
+impl Command for EditSettings {
+    fn name(&self) -> String {
+        "Change project settings".to_owned()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        project.settings = self.after.clone();
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        project.settings = self.before.clone();
+    }
+
+    fn merge(&mut self, next: &dyn Command) -> bool {
+        let Some(next) = next.as_any().downcast_ref::<Self>() else {
+            return false;
+        };
+        if self.merge_id != next.merge_id {
+            return false;
+        }
+
+        self.after = next.after.clone();
+        true
+    }
+}
+
+/// Changes the frame rate of an image sequence, which changes its duration too.
+///
+/// Clips already in the sequence keep their length.
+pub struct SetFrameRate {
+    file: AssetId,
+    before: FrameRate,
+    after: FrameRate,
+}
+impl SetFrameRate {
+    pub fn new(file: AssetId, before: FrameRate, after: FrameRate) -> Self {
+        Self {
+            file,
+            before,
+            after,
+        }
+    }
+
+    fn set(project: &mut Project, id: AssetId, frame_rate: FrameRate) {
+        let Some(file) = project.files.iter_mut().find(|file| file.id == id) else {
+            return;
+        };
+        if let Some(image_sequence) = &mut file.image_sequence {
+            image_sequence.frame_rate = frame_rate;
+            file.info.duration = Some(image_sequence.duration());
+            file.info.frame_rate = Some(frame_rate.fps());
+        }
+    }
+}
+impl Command for SetFrameRate {
+    fn name(&self) -> String {
+        format!("Set frame rate to {}", self.after.name())
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        Self::set(project, self.file, self.after);
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        Self::set(project, self.file, self.before);
+    }
+}
+
+/// Points offline files to new paths and reads their content from there.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct RelinkFiles {
+    name: String,
+    /// Files with their paths before and after relinking.
+    paths: Vec<(AssetId, Option<PathBuf>, PathBuf)>,
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl RelinkFiles {
+    pub fn new(name: impl Into<String>, paths: Vec<(AssetId, Option<PathBuf>, PathBuf)>) -> Self {
+        Self {
+            name: name.into(),
+            paths,
+        }
+    }
+
+    fn set_paths(project: &mut Project, paths: impl Iterator<Item = (AssetId, Option<PathBuf>)>) {
+        for (id, path) in paths {
+            if let Some(file) = project.files.iter_mut().find(|file| file.id == id) {
+                file.path = path;
+                if let Err(err) = file.load() {
+                    log::warn!("{} is offline: {err}", file.name);
+                }
+            }
+        }
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+impl Command for RelinkFiles {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn apply(&mut self, project: &mut Project) {
+        let paths = self.paths.iter();
+        Self::set_paths(
+            project,
+            paths.map(|(id, _, after)| (*id, Some(after.clone()))),
+        );
+    }
+
+    fn revert(&mut self, project: &mut Project) {
+        let paths = self.paths.iter();
+        Self::set_paths(project, paths.map(|(id, before, _)| (*id, before.clone())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{history::History, project::FileType};
+
+    fn file(name: &str) -> File {
+        File {
+            id: AssetId::new(),
+            name: name.to_owned(),
+            path: None,
+            relative_path: None,
+            content: Default::default(),
+            r#type: FileType::Sound,
+            video_thumbnail: None,
+            info: Default::default(),
+            bin: None,
+            size: 0,
+            imported: None,
+            hash: None,
+            image_sequence: None,
+        }
+    }
+
+    fn names(project: &Project) -> Vec<&str> {
+        project
+            .files
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn import_is_one_undo_step() {
+        let mut project = Project::default();
+        let mut history = History::default();
+        let bin = Bin {
+            id: AssetId::new(),
+            name: "Card".to_owned(),
+            parent: None,
+        };
+        history.execute(&mut project, ImportFiles::bins(1, "Import Card", vec![bin]));
+        for index in 0..300 {
+            history.execute(&mut project, ImportFiles::new(1, file(&index.to_string())));
+        }
+        history.execute(&mut project, ImportFiles::new(2, file("other.wav")));
+        history.execute(&mut project, ImportFiles::new(2, file("more.wav")));
+        assert_eq!(history.undo_name().as_deref(), Some("Import 2 files"));
+
+        history.undo(&mut project);
+        assert_eq!(project.files.len(), 300);
+        history.undo(&mut project);
+        assert!(project.files.is_empty());
+        assert!(project.bins.is_empty());
+        assert_eq!(history.undo_name(), None);
+
+        history.redo(&mut project);
+        assert_eq!(project.files.len(), 300);
+        assert_eq!(project.bins.len(), 1);
+    }
+
+    #[test]
+    fn undo_import_removes_its_files() {
+        let mut project = Project::default();
+        let mut import = ImportFiles::new(1, file("imported.wav"));
+        import.apply(&mut project);
+        // Moved before a file added later
+        project.files.push(file("later.wav"));
+        project.files.swap(0, 1);
+
+        import.revert(&mut project);
+        assert_eq!(names(&project), ["later.wav"]);
+        import.apply(&mut project);
+        assert_eq!(names(&project), ["later.wav", "imported.wav"]);
+    }
+}
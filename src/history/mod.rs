@@ -0,0 +1,138 @@
+mod commands;
+#[cfg(not(target_arch = "wasm32"))]
+pub use commands::RelinkFiles;
+pub use commands::{
+    DeleteItems, EditSequence, EditSettings, ImportFiles, OrganizeFiles, SetFrameRate,
+};
+
+use std::any::Any;
+
+use crate::project::Project;
+
+/// Maximal number of steps that can be undone.
+const MAX_UNDO_STEPS: usize = 200;
+
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+}
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Reversible edit of the project.
+pub trait Command: AsAny {
+    /// Name of the action shown in the Edit menu.
+    fn name(&self) -> String;
+    fn apply(&mut self, project: &mut Project);
+    fn revert(&mut self, project: &mut Project);
+
+    /// Folds `next`, already applied after this command, into this one.
+    ///
+    /// Used to turn continuous edits, like dragging, into a single undo step.
+    fn merge(&mut self, _next: &dyn Command) -> bool {
+        false
+    }
+}
+
+/// Command with unique revision, used to tell if the project changed since it was saved.
+struct Entry {
+    revision: u64,
+    command: Box<dyn Command>,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+    last_revision: u64,
+    /// Revision of the project before the oldest command that can be undone.
+    base_revision: Option<u64>,
+}
+impl History {
+    pub const UNDO_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+    pub const REDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+        egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+        egui::Key::Z,
+    );
+
+    /// Applies command and records it.
+    pub fn execute(&mut self, project: &mut Project, mut command: impl Command + 'static) {
+        command.apply(project);
+        self.redo.clear();
+
+        self.last_revision += 1;
+        if let Some(last) = self.undo.last_mut() {
+            if last.command.merge(&command) {
+                last.revision = self.last_revision;
+                return;
+            }
+        }
+
+        self.undo.push(Entry {
+            revision: self.last_revision,
+            command: Box::new(command),
+        });
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.base_revision = Some(self.undo.remove(0).revision);
+        }
+    }
+
+    pub fn undo(&mut self, project: &mut Project) {
+        if let Some(mut entry) = self.undo.pop() {
+            entry.command.revert(project);
+            self.redo.push(entry);
+        }
+    }
+
+    pub fn redo(&mut self, project: &mut Project) {
+        if let Some(mut entry) = self.redo.pop() {
+            entry.command.apply(project);
+            self.undo.push(entry);
+        }
+    }
+
+    pub fn undo_name(&self) -> Option<String> {
+        self.undo.last().map(|entry| entry.command.name())
+    }
+
+    pub fn redo_name(&self) -> Option<String> {
+        self.redo.last().map(|entry| entry.command.name())
+    }
+
+    /// Identifies current state of the project, `None` before any edit.
+    pub fn revision(&self) -> Option<u64> {
+        self.undo
+            .last()
+            .map(|entry| entry.revision)
+            .or(self.base_revision)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.base_revision = None;
+    }
+
+    pub fn shortcuts(&mut self, ctx: &egui::Context, project: &mut Project) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        // Check redo first, undo shortcut matches it too
+        let (redo, undo) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&Self::REDO_SHORTCUT),
+                i.consume_shortcut(&Self::UNDO_SHORTCUT),
+            )
+        });
+
+        if redo {
+            self.redo(project);
+        } else if undo {
+            self.undo(project);
+        }
+    }
+}
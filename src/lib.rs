@@ -4,6 +4,16 @@ mod app;
 pub use app::App;
 
 mod footer;
+mod history;
+mod media;
 mod menu_bar;
+mod notifications;
 mod panes;
+mod playback;
+mod project;
+mod project_file;
+mod sequence;
+mod state;
+mod task;
+mod thumbnails;
 mod tiles;
@@ -0,0 +1,56 @@
+//! Walks AAC frames in ADTS streams.
+
+use super::read::id3_len;
+
+const SAMPLE_RATES: [u32; 13] = [
+    96_000, 88_200, 64_000, 48_000, 44_100, 32_000, 24_000, 22_050, 16_000, 12_000, 11_025, 8_000,
+    7_350,
+];
+/// Samples per channel in each frame.
+pub const FRAME_SAMPLES: u32 = 1024;
+
+pub struct Frame {
+    pub sample_rate: u32,
+    /// 0 if the channel configuration is in the stream.
+    pub channels: u8,
+}
+
+/// Whether the bytes start with an ADTS frame header, after an optional ID3v2 tag.
+pub fn is_adts(bytes: &[u8]) -> bool {
+    let start = id3_len(bytes);
+    // Sync word and layer 0, which tells ADTS from MPEG audio
+    bytes
+        .get(start..start + 2)
+        .is_some_and(|header| header[0] == 0xff && header[1] & 0xf6 == 0xf0)
+}
+
+/// ADTS frames after an optional ID3v2 tag, stops at the first data which isn't a frame.
+pub fn frames(bytes: &[u8]) -> impl Iterator<Item = Frame> + '_ {
+    let mut offset = id3_len(bytes);
+    std::iter::from_fn(move || {
+        let header = bytes.get(offset..offset + 7)?;
+        if header[0] != 0xff || header[1] & 0xf6 != 0xf0 {
+            return None;
+        }
+
+        let protected = header[1] & 1 == 0;
+        let sample_rate = *SAMPLE_RATES.get(((header[2] >> 2) & 0b1111) as usize)?;
+        let channels = ((header[2] & 1) << 2) | (header[3] >> 6);
+        let length = (((header[3] & 0b11) as usize) << 11)
+            | ((header[4] as usize) << 3)
+            | (header[5] >> 5) as usize;
+        let header_length = if protected { 9 } else { 7 };
+        if length <= header_length {
+            return None;
+        }
+
+        let start = offset;
+        offset = offset.checked_add(length)?;
+        // Data block without the header, a truncated last frame still counts
+        bytes.get(start + header_length..offset.min(bytes.len()))?;
+        Some(Frame {
+            sample_rate,
+            channels,
+        })
+    })
+}
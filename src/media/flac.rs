@@ -0,0 +1,430 @@
+//! Reads FLAC stream info and decodes frames into samples.
+
+use super::read::{id3_len, u16_be, BitReader};
+
+/// Properties from the `STREAMINFO` metadata block.
+pub struct StreamInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u32,
+    /// Samples per channel, 0 if unknown.
+    pub total_samples: u64,
+}
+
+/// Decoded frame, with samples of each channel.
+pub struct Block {
+    pub channels: Vec<Vec<i32>>,
+    pub bits_per_sample: u32,
+}
+
+/// Stream info and offset of the first frame, `None` if this isn't a FLAC file.
+pub fn stream_info(bytes: &[u8]) -> Option<(StreamInfo, usize)> {
+    let start = id3_len(bytes);
+    if bytes.get(start..start + 4)? != b"fLaC" {
+        return None;
+    }
+
+    // STREAMINFO is always the first metadata block
+    let info = bytes.get(start + 8..start + 8 + 34)?;
+    let mut reader = BitReader::new(&info[10..]);
+    let sample_rate = reader.read(20)?;
+    let channels = reader.read(3)? as u16 + 1;
+    let bits_per_sample = reader.read(5)? + 1;
+    let total_samples = ((reader.read(4)? as u64) << 32) | reader.read(32)? as u64;
+
+    // Skip all metadata blocks, the last one has the top bit set
+    let mut offset = start + 4;
+    loop {
+        let header = *bytes.get(offset)?;
+        let size = bytes
+            .get(offset + 1..offset + 4)?
+            .iter()
+            .fold(0usize, |size, b| (size << 8) | *b as usize);
+        offset = offset.checked_add(4 + size)?;
+        if header & 0x80 != 0 {
+            break;
+        }
+    }
+
+    Some((
+        StreamInfo {
+            sample_rate,
+            channels,
+            bits_per_sample,
+            total_samples,
+        },
+        offset,
+    ))
+}
+
+/// Decoded frames, damaged ones are skipped.
+pub fn blocks(bytes: &[u8]) -> impl Iterator<Item = Block> + '_ {
+    let (info, mut offset) = match stream_info(bytes) {
+        Some((info, offset)) => (Some(info), offset),
+        None => (None, bytes.len()),
+    };
+
+    std::iter::from_fn(move || {
+        let info = info.as_ref()?;
+        while offset + 2 <= bytes.len() {
+            // Sync code followed by a reserved zero bit
+            if u16_be(bytes, offset)? & 0xfffe != 0xfff8 {
+                offset += 1;
+                continue;
+            }
+
+            let mut reader = BitReader::new(&bytes[offset..]);
+            match decode_frame(&mut reader, info) {
+                Some(block) => {
+                    reader.align();
+                    // Skip CRC-16
+                    offset += reader.byte_position() + 2;
+                    return Some(block);
+                }
+                None => offset += 1,
+            }
+        }
+        None
+    })
+}
+
+fn decode_frame(reader: &mut BitReader<'_>, info: &StreamInfo) -> Option<Block> {
+    reader.skip(16)?;
+    let block_size_code = reader.read(4)?;
+    let sample_rate_code = reader.read(4)?;
+    let channel_assignment = reader.read(4)?;
+    let bits_per_sample = match reader.read(3)? {
+        0 => info.bits_per_sample,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        7 => 32,
+        _ => return None,
+    };
+    if reader.read(1)? != 0 {
+        return None;
+    }
+
+    // Frame or sample number coded like UTF-8
+    let first = reader.read(8)?;
+    let continuation_bytes = match (first as u8).leading_ones() {
+        0 => 0,
+        ones @ 2..=7 => ones - 1,
+        _ => return None,
+    };
+    for _ in 0..continuation_bytes {
+        if reader.read(8)? >> 6 != 0b10 {
+            return None;
+        }
+    }
+
+    let block_size = match block_size_code {
+        1 => 192,
+        2..=5 => 576 << (block_size_code - 2),
+        6 => reader.read(8)? + 1,
+        7 => reader.read(16)? + 1,
+        8..=15 => 256 << (block_size_code - 8),
+        _ => return None,
+    } as usize;
+    match sample_rate_code {
+        12 => reader.skip(8)?,
+        13 | 14 => reader.skip(16)?,
+        15 => return None,
+        _ => {}
+    }
+    // CRC-8
+    reader.skip(8)?;
+
+    let channel_count = match channel_assignment {
+        0..=7 => channel_assignment as usize + 1,
+        8..=10 => 2,
+        _ => return None,
+    };
+    let mut channels = Vec::with_capacity(channel_count);
+    for channel in 0..channel_count {
+        // Side channel has one more bit
+        let side = matches!((channel_assignment, channel), (8, 1) | (9, 0) | (10, 1));
+        channels.push(decode_subframe(
+            reader,
+            block_size,
+            bits_per_sample + side as u32,
+        )?);
+    }
+
+    // Restore stereo channels from their difference
+    if let [first, second] = &mut channels[..] {
+        for (a, b) in first.iter_mut().zip(second.iter_mut()) {
+            match channel_assignment {
+                // Left and side
+                8 => *b = a.wrapping_sub(*b),
+                // Side and right
+                9 => *a = a.wrapping_add(*b),
+                // Mid and side
+                10 => {
+                    let mid = (*a << 1) | (*b & 1);
+                    let side = *b;
+                    *a = mid.wrapping_add(side) >> 1;
+                    *b = mid.wrapping_sub(side) >> 1;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Some(Block {
+        channels,
+        bits_per_sample,
+    })
+}
+
+fn decode_subframe(reader: &mut BitReader<'_>, block_size: usize, bits: u32) -> Option<Vec<i32>> {
+    if reader.read(1)? != 0 {
+        return None;
+    }
+    let kind = reader.read(6)?;
+    let wasted_bits = if reader.read(1)? == 1 {
+        reader.read_unary()? + 1
+    } else {
+        0
+    };
+    let bits = bits
+        .checked_sub(wasted_bits)
+        .filter(|bits| (1..=32).contains(bits))? as usize;
+
+    let mut samples = match kind {
+        // Constant
+        0 => vec![reader.read_signed(bits)?; block_size],
+        // Verbatim
+        1 => (0..block_size)
+            .map(|_| reader.read_signed(bits))
+            .collect::<Option<_>>()?,
+        // Fixed predictor
+        8..=12 => {
+            let order = kind as usize - 8;
+            let mut samples = warm_up(reader, order, bits)?;
+            residual(reader, block_size, order, &mut samples)?;
+            let coefficients: &[i64] = match order {
+                0 => &[],
+                1 => &[1],
+                2 => &[2, -1],
+                3 => &[3, -3, 1],
+                _ => &[4, -6, 4, -1],
+            };
+            predict(&mut samples, coefficients, 0);
+            samples
+        }
+        // Linear predictor
+        32..=63 => {
+            let order = kind as usize - 31;
+            let mut samples = warm_up(reader, order, bits)?;
+            let precision = reader.read(4)? as usize + 1;
+            if precision == 16 {
+                return None;
+            }
+            let shift = reader.read_signed(5)?.max(0) as u32;
+            let coefficients = (0..order)
+                .map(|_| reader.read_signed(precision).map(i64::from))
+                .collect::<Option<Vec<_>>>()?;
+            residual(reader, block_size, order, &mut samples)?;
+            predict(&mut samples, &coefficients, shift);
+            samples
+        }
+        _ => return None,
+    };
+
+    if wasted_bits > 0 {
+        for sample in &mut samples {
+            *sample <<= wasted_bits;
+        }
+    }
+    Some(samples)
+}
+
+fn warm_up(reader: &mut BitReader<'_>, order: usize, bits: usize) -> Option<Vec<i32>> {
+    (0..order).map(|_| reader.read_signed(bits)).collect()
+}
+
+/// Rice coded residual, appended after the warm up samples.
+fn residual(
+    reader: &mut BitReader<'_>,
+    block_size: usize,
+    order: usize,
+    samples: &mut Vec<i32>,
+) -> Option<()> {
+    let (parameter_bits, escape) = match reader.read(2)? {
+        0 => (4, 0b1111),
+        1 => (5, 0b11111),
+        _ => return None,
+    };
+    let partition_order = reader.read(4)?;
+    let partitions = 1usize << partition_order;
+    if block_size % partitions != 0 || block_size >> partition_order < order {
+        return None;
+    }
+
+    samples.reserve(block_size - order);
+    for partition in 0..partitions {
+        let count = (block_size >> partition_order) - if partition == 0 { order } else { 0 };
+        let parameter = reader.read(parameter_bits)?;
+        if parameter == escape {
+            let bits = reader.read(5)? as usize;
+            for _ in 0..count {
+                samples.push(reader.read_signed(bits)?);
+            }
+        } else {
+            for _ in 0..count {
+                let quotient = reader.read_unary()?;
+                let remainder = reader.read(parameter as usize)?;
+                let value = (quotient << parameter) | remainder;
+                // Zigzag encoding of signed values
+                samples.push((value >> 1) as i32 ^ -((value & 1) as i32));
+            }
+        }
+    }
+    Some(())
+}
+
+/// Adds prediction from previous samples to residuals after the warm up samples.
+fn predict(samples: &mut [i32], coefficients: &[i64], shift: u32) {
+    let order = coefficients.len();
+    for index in order..samples.len() {
+        let prediction: i64 = coefficients
+            .iter()
+            .zip(samples[index - order..index].iter().rev())
+            .map(|(coefficient, sample)| coefficient * *sample as i64)
+            .sum();
+        samples[index] = samples[index].wrapping_add((prediction >> shift) as i32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes big endian bit fields, the counterpart of `BitReader`.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bits: usize,
+    }
+    impl BitWriter {
+        fn put(&mut self, value: i64, bits: usize) -> &mut Self {
+            for bit in (0..bits).rev() {
+                if self.bits % 8 == 0 {
+                    self.bytes.push(0);
+                }
+                if (value >> bit) & 1 == 1 {
+                    *self.bytes.last_mut().unwrap() |= 0x80 >> (self.bits % 8);
+                }
+                self.bits += 1;
+            }
+            self
+        }
+
+        /// Rice code of a zigzag encoded residual.
+        fn rice(&mut self, residual: i64, parameter: usize) -> &mut Self {
+            let value = if residual < 0 {
+                -2 * residual - 1
+            } else {
+                2 * residual
+            };
+            for _ in 0..value >> parameter {
+                self.put(0, 1);
+            }
+            self.put(1, 1).put(value, parameter)
+        }
+    }
+
+    fn header(last: bool, kind: u8, size: usize) -> Vec<u8> {
+        let mut header = vec![kind | if last { 0x80 } else { 0 }];
+        header.extend_from_slice(&(size as u32).to_be_bytes()[1..]);
+        header
+    }
+
+    fn stream_info_block(sample_rate: i64, channels: i64, bits: i64, samples: i64) -> Vec<u8> {
+        let mut block = BitWriter::default();
+        block.put(4096, 16).put(4096, 16).put(0, 24).put(0, 24);
+        block
+            .put(sample_rate, 20)
+            .put(channels - 1, 3)
+            .put(bits - 1, 5)
+            .put(samples, 36);
+        block.bytes.extend_from_slice(&[0; 16]);
+        block.bytes
+    }
+
+    #[test]
+    fn stream_info_skips_metadata() {
+        let mut bytes = b"fLaC".to_vec();
+        bytes.extend(header(false, 0, 34));
+        bytes.extend(stream_info_block(44_100, 2, 16, 88_200));
+        // Padding
+        bytes.extend(header(true, 1, 10));
+        bytes.extend_from_slice(&[0; 10]);
+
+        let (info, offset) = stream_info(&bytes).unwrap();
+        assert_eq!(info.sample_rate, 44_100);
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.bits_per_sample, 16);
+        assert_eq!(info.total_samples, 88_200);
+        assert_eq!(offset, bytes.len());
+    }
+
+    #[test]
+    fn stream_info_needs_last_block() {
+        let mut bytes = b"fLaC".to_vec();
+        bytes.extend(header(false, 0, 34));
+        bytes.extend(stream_info_block(48_000, 1, 24, 0));
+        // Block claims to go far past the end
+        bytes.extend(header(false, 1, 0xff_ffff));
+
+        assert!(stream_info(&bytes).is_none());
+        assert!(stream_info(b"RIFF").is_none());
+    }
+
+    #[test]
+    fn fixed_subframe() {
+        let mut bits = BitWriter::default();
+        // Fixed predictor of order 2 without wasted bits
+        bits.put(0, 1).put(8 + 2, 6).put(0, 1);
+        bits.put(10, 16).put(20, 16);
+        // Rice coding with one partition and parameter 2
+        bits.put(0, 2).put(0, 4).put(2, 4);
+        for residual in [0, 1, -1] {
+            bits.rice(residual, 2);
+        }
+
+        let samples = decode_subframe(&mut BitReader::new(&bits.bytes), 5, 16).unwrap();
+        assert_eq!(samples, [10, 20, 30, 41, 51]);
+    }
+
+    #[test]
+    fn lpc_subframe() {
+        let mut bits = BitWriter::default();
+        // Linear predictor of order 1
+        bits.put(0, 1).put(32, 6).put(0, 1);
+        bits.put(-100, 16);
+        // Precision of 4 bits, shift by 1 and coefficient 2, predicting the previous sample
+        bits.put(3, 4).put(1, 5).put(2, 4);
+        bits.put(0, 2).put(0, 4).put(1, 4);
+        for residual in [1, 2, -3] {
+            bits.rice(residual, 1);
+        }
+
+        let samples = decode_subframe(&mut BitReader::new(&bits.bytes), 4, 16).unwrap();
+        assert_eq!(samples, [-100, -99, -97, -100]);
+    }
+
+    #[test]
+    fn constant_subframe_with_wasted_bits() {
+        let mut bits = BitWriter::default();
+        // One wasted bit, unary coded as its count minus one
+        bits.put(0, 1).put(0, 6).put(1, 1).put(1, 1);
+        bits.put(-3, 15);
+
+        let samples = decode_subframe(&mut BitReader::new(&bits.bytes), 3, 16).unwrap();
+        assert_eq!(samples, [-6, -6, -6]);
+    }
+}
@@ -0,0 +1,117 @@
+//! Walks Matroska and WebM files, which share the same EBML structure.
+
+use super::read::{ebml_uint, elements, find_element, u16_be, vint, walk_elements};
+
+const EBML: u32 = 0x1a45_dfa3;
+const DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x1853_8067;
+pub const INFO: u32 = 0x1549_a966;
+pub const TIMECODE_SCALE: u32 = 0x2a_d7b1;
+pub const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654_ae6b;
+const TRACK_ENTRY: u32 = 0xae;
+const TRACK_NUMBER: u32 = 0xd7;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+pub const DEFAULT_DURATION: u32 = 0x23_e383;
+pub const VIDEO: u32 = 0xe0;
+pub const PIXEL_WIDTH: u32 = 0xb0;
+pub const PIXEL_HEIGHT: u32 = 0xba;
+pub const AUDIO: u32 = 0xe1;
+pub const SAMPLING_FREQUENCY: u32 = 0xb5;
+pub const CHANNELS: u32 = 0x9f;
+const CLUSTER: u32 = 0x1f43_b675;
+const TIMECODE: u32 = 0xe7;
+const SIMPLE_BLOCK: u32 = 0xa3;
+const BLOCK_GROUP: u32 = 0xa0;
+const BLOCK: u32 = 0xa1;
+const ATTACHMENTS: u32 = 0x1941_a469;
+const ATTACHED_FILE: u32 = 0x61a7;
+const FILE_MIME_TYPE: u32 = 0x4660;
+const FILE_DATA: u32 = 0x465c;
+
+/// Default timecode scale, 1 ms in nanoseconds.
+pub const DEFAULT_TIMECODE_SCALE: u64 = 1_000_000;
+
+pub struct Track<'a> {
+    pub number: u64,
+    /// 1 for video, 2 for audio.
+    pub kind: u64,
+    pub codec: &'a str,
+    /// Body of the whole `TrackEntry`.
+    pub entry: &'a [u8],
+}
+
+pub struct Block<'a> {
+    pub track: u64,
+    /// Time in timecode scale units.
+    pub time: i64,
+    /// Laced blocks hold several frames with sizes in front of them.
+    pub laced: bool,
+    pub data: &'a [u8],
+}
+
+/// `webm` or `matroska`, `None` if the file doesn't start with an EBML header.
+pub fn doc_type(bytes: &[u8]) -> Option<&str> {
+    let (id, header) = elements(bytes).next()?;
+    if id != EBML {
+        return None;
+    }
+    std::str::from_utf8(find_element(header, DOC_TYPE)?).ok()
+}
+
+/// Body of the segment, which holds everything except the EBML header.
+pub fn segment(bytes: &[u8]) -> Option<&[u8]> {
+    doc_type(bytes)?;
+    find_element(bytes, SEGMENT)
+}
+
+pub fn tracks(segment: &[u8]) -> impl Iterator<Item = Track<'_>> {
+    elements(find_element(segment, TRACKS).unwrap_or_default())
+        .filter(|(id, _)| *id == TRACK_ENTRY)
+        .filter_map(|(_, entry)| {
+            Some(Track {
+                number: ebml_uint(find_element(entry, TRACK_NUMBER)?)?,
+                kind: ebml_uint(find_element(entry, TRACK_TYPE)?)?,
+                codec: std::str::from_utf8(find_element(entry, CODEC_ID)?)
+                    .ok()?
+                    .trim_end_matches('\0'),
+                entry,
+            })
+        })
+}
+
+/// Blocks of all clusters in file order, clusters may have unknown size.
+pub fn blocks(segment: &[u8]) -> impl Iterator<Item = Block<'_>> {
+    let mut cluster_time = 0;
+    walk_elements(segment, &[CLUSTER, BLOCK_GROUP]).filter_map(move |(id, body)| match id {
+        TIMECODE => {
+            cluster_time = ebml_uint(body)? as i64;
+            None
+        }
+        SIMPLE_BLOCK | BLOCK => {
+            let (track, len) = vint(body, 0, false)?;
+            let relative = u16_be(body, len)? as i16;
+            let flags = *body.get(len + 2)?;
+            Some(Block {
+                track,
+                time: cluster_time + relative as i64,
+                laced: flags & 0x06 != 0,
+                data: &body[len + 3..],
+            })
+        }
+        _ => None,
+    })
+}
+
+/// First attached image, usually the cover art.
+pub fn cover(segment: &[u8]) -> Option<&[u8]> {
+    elements(find_element(segment, ATTACHMENTS)?)
+        .filter(|(id, _)| *id == ATTACHED_FILE)
+        .find_map(|(_, file)| {
+            find_element(file, FILE_MIME_TYPE)?
+                .starts_with(b"image/")
+                .then(|| find_element(file, FILE_DATA))
+                .flatten()
+        })
+}
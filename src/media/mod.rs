@@ -0,0 +1,10 @@
+mod aac;
+mod flac;
+mod matroska;
+mod mp3;
+mod mp4;
+pub mod probe;
+pub use probe::MediaInfo;
+mod read;
+pub mod thumbnail;
+pub mod waveform;
@@ -0,0 +1,81 @@
+//! Walks MPEG audio Layer III frames.
+
+use super::read::{id3_len, u32_be};
+
+const BITRATES_V1_L3: [u32; 16] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 0,
+];
+const BITRATES_V2_L3: [u32; 16] = [
+    0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160, 0,
+];
+const SAMPLE_RATES: [u32; 3] = [44_100, 48_000, 32_000];
+
+pub struct Frame {
+    pub sample_rate: u32,
+    /// Samples per channel.
+    pub samples: u32,
+    pub channels: u8,
+}
+
+/// Frames after an optional ID3v2 tag, stops at the first data which isn't a frame.
+pub fn frames(bytes: &[u8]) -> impl Iterator<Item = Frame> + '_ {
+    let mut offset = id3_len(bytes);
+
+    let mut found = false;
+    std::iter::from_fn(move || {
+        while offset + 4 <= bytes.len() {
+            let header = u32_be(bytes, offset)?;
+            if header >> 21 != 0x7ff {
+                if found {
+                    return None;
+                }
+                offset += 1;
+                continue;
+            }
+
+            let version = (header >> 19) & 0b11; // 3: MPEG 1, 2: MPEG 2, 0: MPEG 2.5
+            let layer = (header >> 17) & 0b11; // 1: Layer III
+            let bitrate_index = ((header >> 12) & 0b1111) as usize;
+            let sample_rate_index = ((header >> 10) & 0b11) as usize;
+            let padding = (header >> 9) & 1;
+            let channel_mode = (header >> 6) & 0b11; // 3: mono
+            if version == 1 || layer != 1 || sample_rate_index == 3 {
+                offset += 1;
+                continue;
+            }
+
+            let (bitrate, sample_rate, samples) = match version {
+                3 => (
+                    BITRATES_V1_L3[bitrate_index],
+                    SAMPLE_RATES[sample_rate_index],
+                    1152,
+                ),
+                2 => (
+                    BITRATES_V2_L3[bitrate_index],
+                    SAMPLE_RATES[sample_rate_index] / 2,
+                    576,
+                ),
+                _ => (
+                    BITRATES_V2_L3[bitrate_index],
+                    SAMPLE_RATES[sample_rate_index] / 4,
+                    576,
+                ),
+            };
+            if bitrate == 0 {
+                offset += 1;
+                continue;
+            }
+
+            let length = (samples / 8 * bitrate * 1000 / sample_rate + padding) as usize;
+            offset += length.max(1);
+            found = true;
+            return Some(Frame {
+                sample_rate,
+                samples,
+                channels: if channel_mode == 3 { 1 } else { 2 },
+            });
+        }
+
+        None
+    })
+}
@@ -0,0 +1,107 @@
+//! Tracks and sample tables of MP4, MOV and M4A files.
+
+use super::read::{boxes, find_box, find_path, u32_be, u64_be};
+
+pub struct Track<'a> {
+    /// Handler type, e.g. `vide` or `soun`.
+    pub handler: &'a [u8],
+    pub mdia: &'a [u8],
+    pub stbl: &'a [u8],
+    /// Format of the first sample description, e.g. `avc1`.
+    pub format: &'a [u8],
+    /// First sample description, its layout depends on the handler.
+    pub description: &'a [u8],
+}
+
+/// Whether the bytes start with a box which only MP4 or MOV files begin with.
+pub fn is_mp4(bytes: &[u8]) -> bool {
+    // Old QuickTime files may not start with `ftyp`
+    matches!(
+        bytes.get(4..8),
+        Some(b"ftyp" | b"moov" | b"mdat" | b"wide" | b"free")
+    )
+}
+
+pub fn tracks(moov: &[u8]) -> impl Iterator<Item = Track<'_>> {
+    boxes(moov)
+        .filter(|(kind, _)| *kind == b"trak")
+        .filter_map(|(_, trak)| {
+            let mdia = find_box(trak, b"mdia")?;
+            let stbl = find_path(mdia, &[b"minf", b"stbl"])?;
+            let description = find_box(stbl, b"stsd")?.get(8..)?;
+            Some(Track {
+                handler: find_box(mdia, b"hdlr")?.get(8..12)?,
+                mdia,
+                stbl,
+                format: description.get(4..8)?,
+                description,
+            })
+        })
+}
+
+/// Time scale and duration in it, from a `mvhd` or `mdhd` body.
+pub fn header_duration(header: &[u8]) -> Option<(u32, u64)> {
+    match header.first()? {
+        0 => Some((u32_be(header, 12)?, u32_be(header, 16)? as u64)),
+        _ => Some((u32_be(header, 20)?, u64_be(header, 24)?)),
+    }
+}
+
+pub fn sample_count(stbl: &[u8]) -> Option<u32> {
+    u32_be(find_box(stbl, b"stsz")?, 8)
+}
+
+fn sample_size(stsz: &[u8], sample: u32) -> Option<u32> {
+    match u32_be(stsz, 4)? {
+        0 => u32_be(stsz, 12 + sample as usize * 4),
+        size => Some(size),
+    }
+}
+
+fn chunk_offset(stbl: &[u8], chunk: usize) -> Option<u64> {
+    if let Some(stco) = find_box(stbl, b"stco") {
+        u32_be(stco, 8 + chunk * 4).map(u64::from)
+    } else {
+        u64_be(find_box(stbl, b"co64")?, 8 + chunk * 8)
+    }
+}
+
+/// Data of one sample, found through its chunk.
+pub fn sample<'a>(bytes: &'a [u8], stbl: &[u8], sample: u32) -> Option<&'a [u8]> {
+    let stsz = find_box(stbl, b"stsz")?;
+    let stsc = find_box(stbl, b"stsc")?;
+    let entries = u32_be(stsc, 4)? as usize;
+
+    // Runs of chunks with the same number of samples
+    let mut run_first_sample = 0u64;
+    for entry in 0..entries {
+        let first_chunk = u32_be(stsc, 8 + entry * 12)? as u64;
+        let samples_per_chunk = u32_be(stsc, 12 + entry * 12)? as u64;
+        let next_first_chunk = if entry + 1 < entries {
+            u32_be(stsc, 8 + (entry + 1) * 12)? as u64
+        } else {
+            u32::MAX as u64
+        };
+        if samples_per_chunk == 0 || next_first_chunk < first_chunk {
+            return None;
+        }
+
+        let run_samples = (next_first_chunk - first_chunk) * samples_per_chunk;
+        if (sample as u64) < run_first_sample + run_samples {
+            let chunk_in_run = (sample as u64 - run_first_sample) / samples_per_chunk;
+            let chunk = first_chunk - 1 + chunk_in_run;
+            let chunk_first_sample = run_first_sample + chunk_in_run * samples_per_chunk;
+
+            let mut offset = chunk_offset(stbl, chunk as usize)?;
+            for previous in chunk_first_sample..sample as u64 {
+                offset += sample_size(stsz, previous as u32)? as u64;
+            }
+            let offset = usize::try_from(offset).ok()?;
+            let size = sample_size(stsz, sample)? as usize;
+            return bytes.get(offset..offset.checked_add(size)?);
+        }
+        run_first_sample += run_samples;
+    }
+
+    None
+}
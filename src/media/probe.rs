@@ -0,0 +1,608 @@
+//! Reads media properties straight from container headers.
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    aac, flac, matroska, mp3, mp4,
+    read::{
+        chunks, ebml_float, ebml_uint, find_box, find_chunk, find_element, u16_be, u16_le, u32_be,
+        u32_le, u64_le,
+    },
+};
+use crate::project::FileType;
+
+/// Properties of imported media, fields are `None` when not present or not known.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MediaInfo {
+    /// Container or image format, e.g. `MP4` or `PNG`.
+    pub format: String,
+    /// Length in seconds.
+    pub duration: Option<f64>,
+    /// Width and height in pixels.
+    pub resolution: Option<[u32; 2]>,
+    pub frame_rate: Option<f64>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+impl MediaInfo {
+    /// Kind of media according to the content.
+    pub fn file_type(&self) -> Option<FileType> {
+        if self.video_codec.is_some() || (self.resolution.is_some() && self.duration.is_some()) {
+            Some(FileType::Video)
+        } else if self.resolution.is_some() {
+            Some(FileType::Image)
+        } else if self.audio_codec.is_some() || self.sample_rate.is_some() {
+            Some(FileType::Sound)
+        } else {
+            None
+        }
+    }
+}
+
+/// `None` if the format isn't recognized or headers are damaged.
+pub fn probe(bytes: &[u8]) -> Option<MediaInfo> {
+    if bytes.len() < 12 {
+        return None;
+    }
+
+    if mp4::is_mp4(bytes) {
+        probe_mp4(bytes)
+    } else if let Some(doc_type) = matroska::doc_type(bytes) {
+        probe_matroska(bytes, doc_type)
+    } else if &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"AVI " {
+        probe_avi(bytes)
+    } else if &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+        probe_wav(bytes)
+    } else if &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        probe_webp(bytes)
+    } else if &bytes[0..4] == b"OggS" {
+        probe_ogg(bytes)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        probe_png(bytes)
+    } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        probe_jpeg(bytes)
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        probe_gif(bytes)
+    } else if bytes.starts_with(b"BM") {
+        probe_bmp(bytes)
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        probe_tiff(bytes)
+    } else if let Some((info, _)) = flac::stream_info(bytes) {
+        Some(probe_flac(info))
+    } else if aac::is_adts(bytes) {
+        probe_adts(bytes)
+    } else {
+        probe_mp3(bytes)
+    }
+}
+
+/// Readable name of a four character code.
+fn codec_name(fourcc: &[u8]) -> String {
+    match fourcc {
+        b"avc1" | b"avc3" | b"H264" | b"h264" | b"X264" | b"x264" => "H.264".to_owned(),
+        b"hvc1" | b"hev1" => "H.265".to_owned(),
+        b"mp4v" | b"XVID" | b"xvid" | b"DIVX" | b"divx" | b"DX50" | b"FMP4" => {
+            "MPEG-4 Visual".to_owned()
+        }
+        b"jpeg" | b"mjpa" | b"AVDJ" | b"dmb1" | b"MJPG" | b"mjpg" => "Motion JPEG".to_owned(),
+        b"apch" | b"apcn" | b"apcs" | b"apco" | b"ap4h" | b"ap4x" => "ProRes".to_owned(),
+        b"av01" => "AV1".to_owned(),
+        b"vp09" => "VP9".to_owned(),
+        b"mp4a" => "AAC".to_owned(),
+        b"fLaC" => "FLAC".to_owned(),
+        b"alac" => "ALAC".to_owned(),
+        b"Opus" => "Opus".to_owned(),
+        b"ac-3" => "AC-3".to_owned(),
+        b"sowt" | b"twos" | b"lpcm" | b"in24" | b"in32" | b"fl32" => "PCM".to_owned(),
+        _ => String::from_utf8_lossy(fourcc).trim().to_owned(),
+    }
+}
+
+fn probe_mp4(bytes: &[u8]) -> Option<MediaInfo> {
+    let moov = find_box(bytes, b"moov")?;
+    let (timescale, duration) = mp4::header_duration(find_box(moov, b"mvhd")?)?;
+
+    let mut info = MediaInfo {
+        format: match bytes.get(8..12) {
+            Some(b"qt  ") => "MOV".to_owned(),
+            Some(b"M4A " | b"M4B ") => "M4A".to_owned(),
+            _ => "MP4".to_owned(),
+        },
+        duration: (timescale > 0).then(|| duration as f64 / timescale as f64),
+        ..Default::default()
+    };
+
+    for track in mp4::tracks(moov) {
+        let description = track.description;
+        match track.handler {
+            b"vide" if info.video_codec.is_none() => {
+                info.video_codec = Some(codec_name(track.format));
+                info.resolution = u16_be(description, 32)
+                    .zip(u16_be(description, 34))
+                    .map(|(width, height)| [width as u32, height as u32]);
+                info.frame_rate = mp4_frame_rate(&track);
+            }
+            b"soun" if info.audio_codec.is_none() => {
+                info.audio_codec = Some(codec_name(track.format));
+                info.channels = u16_be(description, 24);
+                info.sample_rate = u32_be(description, 32).map(|rate| rate >> 16);
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// Average rate from number of samples and track duration.
+fn mp4_frame_rate(track: &mp4::Track<'_>) -> Option<f64> {
+    let (timescale, duration) = mp4::header_duration(find_box(track.mdia, b"mdhd")?)?;
+    let samples = mp4::sample_count(track.stbl)?;
+    if duration == 0 || timescale == 0 {
+        return None;
+    }
+
+    Some(round_rate(
+        samples as f64 * timescale as f64 / duration as f64,
+    ))
+}
+
+/// Rounds frame rate to three decimals, e.g. 29.97.
+fn round_rate(frame_rate: f64) -> f64 {
+    (frame_rate * 1000.).round() / 1000.
+}
+
+/// Readable name of a Matroska codec id.
+fn matroska_codec_name(codec: &str) -> String {
+    match codec {
+        "V_MPEG4/ISO/AVC" => "H.264",
+        "V_MPEGH/ISO/HEVC" => "H.265",
+        "V_MPEG4/ISO/SP" | "V_MPEG4/ISO/ASP" | "V_MPEG4/ISO/AP" => "MPEG-4 Visual",
+        "V_MJPEG" => "Motion JPEG",
+        "V_VP8" => "VP8",
+        "V_VP9" => "VP9",
+        "V_AV1" => "AV1",
+        "V_PRORES" => "ProRes",
+        "A_OPUS" => "Opus",
+        "A_VORBIS" => "Vorbis",
+        "A_FLAC" => "FLAC",
+        "A_MPEG/L3" => "MP3",
+        "A_AC3" => "AC-3",
+        "A_EAC3" => "E-AC-3",
+        codec if codec.starts_with("A_AAC") => "AAC",
+        codec if codec.starts_with("A_PCM") => "PCM",
+        codec => codec,
+    }
+    .to_owned()
+}
+
+fn probe_matroska(bytes: &[u8], doc_type: &str) -> Option<MediaInfo> {
+    let segment = matroska::segment(bytes)?;
+    let segment_info = find_element(segment, matroska::INFO)?;
+    let timecode_scale = find_element(segment_info, matroska::TIMECODE_SCALE)
+        .and_then(ebml_uint)
+        .unwrap_or(matroska::DEFAULT_TIMECODE_SCALE);
+
+    // Live recordings don't know the duration, take time of the last block then
+    let duration = find_element(segment_info, matroska::DURATION)
+        .and_then(ebml_float)
+        .or_else(|| {
+            matroska::blocks(segment)
+                .map(|block| block.time)
+                .max()
+                .map(|time| time as f64)
+        })
+        .map(|duration| duration * timecode_scale as f64 / 1_000_000_000.);
+
+    let mut info = MediaInfo {
+        format: if doc_type == "webm" {
+            "WebM".to_owned()
+        } else {
+            "Matroska".to_owned()
+        },
+        duration,
+        ..Default::default()
+    };
+
+    for track in matroska::tracks(segment) {
+        let setting = |settings, id| {
+            find_element(track.entry, settings).and_then(|settings| find_element(settings, id))
+        };
+        match track.kind {
+            1 if info.video_codec.is_none() => {
+                info.video_codec = Some(matroska_codec_name(track.codec));
+                info.resolution = setting(matroska::VIDEO, matroska::PIXEL_WIDTH)
+                    .and_then(ebml_uint)
+                    .zip(setting(matroska::VIDEO, matroska::PIXEL_HEIGHT).and_then(ebml_uint))
+                    .map(|(width, height)| [width as u32, height as u32]);
+                info.frame_rate = find_element(track.entry, matroska::DEFAULT_DURATION)
+                    .and_then(ebml_uint)
+                    .filter(|frame_duration| *frame_duration > 0)
+                    .map(|frame_duration| round_rate(1_000_000_000. / frame_duration as f64));
+            }
+            2 if info.audio_codec.is_none() => {
+                info.audio_codec = Some(matroska_codec_name(track.codec));
+                info.sample_rate = setting(matroska::AUDIO, matroska::SAMPLING_FREQUENCY)
+                    .and_then(ebml_float)
+                    .map(|rate| rate as u32)
+                    // Default of the format
+                    .or(Some(8000));
+                info.channels = setting(matroska::AUDIO, matroska::CHANNELS)
+                    .and_then(ebml_uint)
+                    .map(|channels| channels as u16)
+                    .or(Some(1));
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+fn probe_avi(bytes: &[u8]) -> Option<MediaInfo> {
+    let riff = &bytes[12..];
+    let avih = find_chunk(riff, b"avih")?;
+    let micro_sec_per_frame = u32_le(avih, 0)?;
+    let total_frames = u32_le(avih, 16)?;
+
+    let mut info = MediaInfo {
+        format: "AVI".to_owned(),
+        duration: Some(micro_sec_per_frame as f64 * total_frames as f64 / 1_000_000.),
+        resolution: Some([u32_le(avih, 32)?, u32_le(avih, 36)?]),
+        frame_rate: (micro_sec_per_frame > 0)
+            .then(|| round_rate(1_000_000. / micro_sec_per_frame as f64)),
+        ..Default::default()
+    };
+
+    // Stream headers, each in a `strl` list
+    let hdrl = chunks(riff).find_map(|(id, body)| {
+        (id == b"LIST" && body.get(0..4) == Some(b"hdrl")).then(|| &body[4..])
+    })?;
+    for (_, strl) in
+        chunks(hdrl).filter(|(id, body)| *id == b"LIST" && body.get(0..4) == Some(b"strl"))
+    {
+        let strl = &strl[4..];
+        let (Some(strh), Some(strf)) = (find_chunk(strl, b"strh"), find_chunk(strl, b"strf"))
+        else {
+            continue;
+        };
+        match strh.get(0..4) {
+            Some(b"vids") if info.video_codec.is_none() => {
+                info.video_codec = strf.get(16..20).map(codec_name);
+            }
+            Some(b"auds") if info.audio_codec.is_none() => {
+                info.audio_codec = u16_le(strf, 0).map(wave_format_name);
+                info.channels = u16_le(strf, 2);
+                info.sample_rate = u32_le(strf, 4);
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// Name of a `WAVEFORMATEX` format tag.
+fn wave_format_name(tag: u16) -> String {
+    match tag {
+        0x0001 => "PCM".to_owned(),
+        0x0003 => "PCM float".to_owned(),
+        0x0055 => "MP3".to_owned(),
+        0x00ff | 0x1610 => "AAC".to_owned(),
+        0x2000 => "AC-3".to_owned(),
+        _ => format!("0x{tag:04x}"),
+    }
+}
+
+fn probe_wav(bytes: &[u8]) -> Option<MediaInfo> {
+    let chunks = &bytes[12..];
+    let fmt = find_chunk(chunks, b"fmt ")?;
+    let byte_rate = u32_le(fmt, 8)?;
+
+    let mut tag = u16_le(fmt, 0)?;
+    // WAVE_FORMAT_EXTENSIBLE keeps the real format in the sub format GUID
+    if tag == 0xfffe {
+        tag = u16_le(fmt, 24)?;
+    }
+
+    // Size from header, body may be cut
+    let mut duration = None;
+    let mut offset = 0;
+    while offset + 8 <= chunks.len() {
+        let size = u32_le(chunks, offset + 4)? as usize;
+        if &chunks[offset..offset + 4] == b"data" && byte_rate > 0 {
+            duration = Some(size as f64 / byte_rate as f64);
+            break;
+        }
+        // Chunks are word aligned, a size past what can be addressed ends the list
+        let Some(next) = (offset + 8)
+            .checked_add(size)
+            .and_then(|end| end.checked_add(size & 1))
+        else {
+            break;
+        };
+        offset = next;
+    }
+
+    Some(MediaInfo {
+        format: "WAV".to_owned(),
+        duration,
+        audio_codec: Some(wave_format_name(tag)),
+        channels: u16_le(fmt, 2),
+        sample_rate: u32_le(fmt, 4),
+        ..Default::default()
+    })
+}
+
+fn probe_ogg(bytes: &[u8]) -> Option<MediaInfo> {
+    // First page carries codec identification header
+    let segments = *bytes.get(26)? as usize;
+    let packet = bytes.get(27 + segments..)?;
+    let (codec, channels, sample_rate, granule_rate, pre_skip) =
+        if packet.starts_with(b"\x01vorbis") {
+            let sample_rate = u32_le(packet, 12)?;
+            ("Vorbis", *packet.get(11)?, sample_rate, sample_rate, 0)
+        } else if packet.starts_with(b"OpusHead") {
+            // Opus granule position is always at 48 kHz
+            (
+                "Opus",
+                *packet.get(9)?,
+                u32_le(packet, 12)?,
+                48_000,
+                u16_le(packet, 10)?,
+            )
+        } else {
+            return None;
+        };
+
+    // Granule position of the last page is the total sample count
+    let duration = (granule_rate > 0)
+        .then(|| {
+            let last_page = bytes.windows(4).rposition(|w| w == b"OggS")?;
+            let granule = u64_le(bytes, last_page + 6)?;
+            Some(granule.saturating_sub(pre_skip as u64) as f64 / granule_rate as f64)
+        })
+        .flatten();
+
+    Some(MediaInfo {
+        format: "Ogg".to_owned(),
+        duration,
+        audio_codec: Some(codec.to_owned()),
+        sample_rate: Some(sample_rate),
+        channels: Some(channels as u16),
+        ..Default::default()
+    })
+}
+
+/// Sums duration of all MPEG audio frames.
+fn probe_mp3(bytes: &[u8]) -> Option<MediaInfo> {
+    let mut frames = mp3::frames(bytes).peekable();
+    let first = frames.peek()?;
+    let (sample_rate, channels) = (first.sample_rate, first.channels);
+    let duration = frames
+        .map(|frame| frame.samples as f64 / frame.sample_rate as f64)
+        .sum();
+
+    Some(MediaInfo {
+        format: "MP3".to_owned(),
+        duration: Some(duration),
+        audio_codec: Some("MP3".to_owned()),
+        sample_rate: Some(sample_rate),
+        channels: Some(channels as u16),
+        ..Default::default()
+    })
+}
+
+fn probe_flac(stream_info: flac::StreamInfo) -> MediaInfo {
+    MediaInfo {
+        format: "FLAC".to_owned(),
+        duration: (stream_info.total_samples > 0 && stream_info.sample_rate > 0)
+            .then(|| stream_info.total_samples as f64 / stream_info.sample_rate as f64),
+        audio_codec: Some("FLAC".to_owned()),
+        sample_rate: Some(stream_info.sample_rate),
+        channels: Some(stream_info.channels),
+        ..Default::default()
+    }
+}
+
+/// Counts AAC frames, each has the same number of samples.
+fn probe_adts(bytes: &[u8]) -> Option<MediaInfo> {
+    let mut frames = aac::frames(bytes).peekable();
+    let first = frames.peek()?;
+    let (sample_rate, channels) = (first.sample_rate, first.channels);
+    let duration = frames.count() as f64 * aac::FRAME_SAMPLES as f64 / sample_rate as f64;
+
+    Some(MediaInfo {
+        format: "AAC".to_owned(),
+        duration: Some(duration),
+        audio_codec: Some("AAC".to_owned()),
+        sample_rate: Some(sample_rate),
+        channels: (channels > 0).then_some(channels as u16),
+        ..Default::default()
+    })
+}
+
+fn probe_png(bytes: &[u8]) -> Option<MediaInfo> {
+    // IHDR is always the first chunk
+    if bytes.get(12..16)? != b"IHDR" {
+        return None;
+    }
+
+    Some(MediaInfo {
+        format: "PNG".to_owned(),
+        resolution: Some([u32_be(bytes, 16)?, u32_be(bytes, 20)?]),
+        ..Default::default()
+    })
+}
+
+fn probe_jpeg(bytes: &[u8]) -> Option<MediaInfo> {
+    // Walk segments until a start of frame marker
+    let mut offset = 2;
+    loop {
+        while *bytes.get(offset)? != 0xff {
+            offset += 1;
+        }
+        let marker = *bytes.get(offset + 1)?;
+        match marker {
+            // Fill bytes and markers without length
+            0xff => offset += 1,
+            0x01 | 0xd0..=0xd7 => offset += 2,
+            // Start of frame, except DHT, JPG and DAC which share the range
+            0xc0..=0xcf if ![0xc4, 0xc8, 0xcc].contains(&marker) => {
+                return Some(MediaInfo {
+                    format: "JPEG".to_owned(),
+                    resolution: Some([
+                        u16_be(bytes, offset + 7)? as u32,
+                        u16_be(bytes, offset + 5)? as u32,
+                    ]),
+                    ..Default::default()
+                });
+            }
+            _ => offset += 2 + u16_be(bytes, offset + 2)? as usize,
+        }
+    }
+}
+
+fn probe_gif(bytes: &[u8]) -> Option<MediaInfo> {
+    Some(MediaInfo {
+        format: "GIF".to_owned(),
+        resolution: Some([u16_le(bytes, 6)? as u32, u16_le(bytes, 8)? as u32]),
+        ..Default::default()
+    })
+}
+
+fn probe_webp(bytes: &[u8]) -> Option<MediaInfo> {
+    let (id, body) = chunks(&bytes[12..]).next()?;
+    let resolution = match id {
+        // Lossy, frame header after a 3 byte tag and start code
+        b"VP8 " => [
+            (u16_le(body, 6)? & 0x3fff) as u32,
+            (u16_le(body, 8)? & 0x3fff) as u32,
+        ],
+        // Lossless, 14 bits per dimension after a signature byte
+        b"VP8L" => {
+            let bits = u32_le(body, 1)?;
+            [(bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1]
+        }
+        // Extended, canvas size in 24 bits after flags
+        b"VP8X" => {
+            let size = |offset: usize| {
+                let size = body.get(offset..offset + 3)?;
+                Some(u32::from_le_bytes([size[0], size[1], size[2], 0]))
+            };
+            [size(4)? + 1, size(7)? + 1]
+        }
+        _ => return None,
+    };
+
+    Some(MediaInfo {
+        format: "WebP".to_owned(),
+        resolution: Some(resolution),
+        ..Default::default()
+    })
+}
+
+fn probe_bmp(bytes: &[u8]) -> Option<MediaInfo> {
+    // Old OS/2 header has 16 bit dimensions, height is negative for top-down bitmaps
+    let resolution = if u32_le(bytes, 14)? == 12 {
+        [u16_le(bytes, 18)? as u32, u16_le(bytes, 20)? as u32]
+    } else {
+        [
+            (u32_le(bytes, 18)? as i32).unsigned_abs(),
+            (u32_le(bytes, 22)? as i32).unsigned_abs(),
+        ]
+    };
+
+    Some(MediaInfo {
+        format: "BMP".to_owned(),
+        resolution: Some(resolution),
+        ..Default::default()
+    })
+}
+
+fn probe_tiff(bytes: &[u8]) -> Option<MediaInfo> {
+    let little_endian = bytes.starts_with(b"II");
+    let u16_at = |offset| {
+        if little_endian {
+            u16_le(bytes, offset)
+        } else {
+            u16_be(bytes, offset)
+        }
+    };
+    let u32_at = |offset| {
+        if little_endian {
+            u32_le(bytes, offset)
+        } else {
+            u32_be(bytes, offset)
+        }
+    };
+
+    // Tags of the first image file directory, value is a short or a long
+    let directory = u32_at(4)? as usize;
+    let mut width = None;
+    let mut height = None;
+    for entry in 0..u16_at(directory)? as usize {
+        let offset = directory + 2 + entry * 12;
+        let value = match u16_at(offset + 2)? {
+            3 => u16_at(offset + 8)? as u32,
+            4 => u32_at(offset + 8)?,
+            _ => continue,
+        };
+        match u16_at(offset)? {
+            256 => width = Some(value),
+            257 => height = Some(value),
+            _ => {}
+        }
+    }
+
+    Some(MediaInfo {
+        format: "TIFF".to_owned(),
+        resolution: Some([width?, height?]),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// WAV header with 16-bit stereo at 48 kHz followed by the chunks.
+    fn wav(chunks: &[u8]) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&48_000u32.to_le_bytes());
+        bytes.extend_from_slice(&192_000u32.to_le_bytes());
+        bytes.extend_from_slice(&4u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(chunks);
+        bytes
+    }
+
+    #[test]
+    fn wav_duration_from_data_header() {
+        let mut chunks = b"data".to_vec();
+        chunks.extend_from_slice(&96_000u32.to_le_bytes());
+        // Body is cut, the header tells the length
+        chunks.extend_from_slice(&[0; 100]);
+
+        let info = probe(&wav(&chunks)).unwrap();
+        assert_eq!(info.duration, Some(0.5));
+        assert_eq!(info.sample_rate, Some(48_000));
+        assert_eq!(info.channels, Some(2));
+    }
+
+    #[test]
+    fn wav_with_huge_chunk_ends() {
+        let mut chunks = b"junk".to_vec();
+        chunks.extend_from_slice(&u32::MAX.to_le_bytes());
+        chunks.extend_from_slice(b"data\0\0\0\0");
+
+        let info = probe(&wav(&chunks)).unwrap();
+        assert_eq!(info.duration, None);
+    }
+}
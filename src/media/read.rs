@@ -0,0 +1,381 @@
+//! Helpers for reading binary container formats.
+
+pub fn u16_le(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub fn u16_be(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub fn u32_le(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub fn u32_be(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub fn u64_be(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+pub fn u64_le(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// Iterates over a list of ISO BMFF boxes, yielding their type and body.
+pub fn boxes(bytes: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        if offset + 8 > bytes.len() {
+            return None;
+        }
+
+        let size = u32_be(bytes, offset)? as u64;
+        let (header, size) = match size {
+            0 => (8, (bytes.len() - offset) as u64),
+            1 => (16, u64_be(bytes, offset + 8)?),
+            _ => (8, size),
+        };
+        if size < header {
+            return None;
+        }
+
+        let start = offset;
+        let end = offset
+            .checked_add(usize::try_from(size).ok()?)?
+            .min(bytes.len());
+        offset = end;
+        Some((
+            &bytes[start + 4..start + 8],
+            bytes.get(start + header as usize..end)?,
+        ))
+    })
+}
+
+/// Finds box with given type in a list of ISO BMFF boxes and returns its body.
+pub fn find_box<'a>(bytes: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(bytes)
+        .find(|(box_kind, _)| box_kind == kind)
+        .map(|(_, body)| body)
+}
+
+/// Follows path of nested boxes, e.g. `[b"moov", b"mvhd"]`.
+pub fn find_path<'a>(bytes: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    path.iter()
+        .try_fold(bytes, |bytes, kind| find_box(bytes, kind))
+}
+
+/// Iterates over a list of RIFF chunks, yielding their id and body.
+///
+/// Stops after a chunk whose size goes past what can be addressed, e.g. in damaged files.
+pub fn chunks(bytes: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut offset = Some(0);
+    std::iter::from_fn(move || {
+        let start = offset.filter(|start| bytes.len().saturating_sub(*start) >= 8)?;
+        let size = u32_le(bytes, start + 4)? as usize;
+        let body_end = (start + 8).saturating_add(size).min(bytes.len());
+        let body = bytes.get(start + 8..body_end)?;
+        // Chunks are word aligned
+        offset = (start + 8)
+            .checked_add(size)
+            .and_then(|end| end.checked_add(size & 1));
+        Some((&bytes[start..start + 4], body))
+    })
+}
+
+/// Finds RIFF chunk with given id among chunks, descending into `LIST`s.
+pub fn find_chunk<'a>(bytes: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+    chunks(bytes).find_map(|(chunk_id, body)| {
+        if chunk_id == id {
+            Some(body)
+        } else if chunk_id == b"LIST" && body.len() >= 4 {
+            find_chunk(&body[4..], id)
+        } else {
+            None
+        }
+    })
+}
+
+/// Length of the ID3v2 tag at the start, 0 if there is none.
+pub fn id3_len(bytes: &[u8]) -> usize {
+    if bytes.starts_with(b"ID3") && bytes.len() >= 10 {
+        let size = bytes[6..10]
+            .iter()
+            .fold(0usize, |size, b| (size << 7) | (*b & 0x7f) as usize);
+        10 + size
+    } else {
+        0
+    }
+}
+
+/// Reads EBML variable length integer, returning it with its length.
+///
+/// Element ids keep the length marker, sizes don't. Size with all bits set is unknown.
+pub fn vint(bytes: &[u8], offset: usize, keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *bytes.get(offset)?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 {
+        return None;
+    }
+
+    let mut value = if keep_marker {
+        first as u64
+    } else {
+        (first as u64) & (0xff >> len)
+    };
+    for byte in bytes.get(offset + 1..offset + len)? {
+        value = (value << 8) | *byte as u64;
+    }
+    Some((value, len))
+}
+
+/// Iterates over a list of EBML (Matroska, WebM) elements, yielding their id and body.
+///
+/// Element with unknown size extends to the end of the list.
+pub fn elements(bytes: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let (id, id_len) = vint(bytes, offset, true)?;
+        let (size, size_len) = vint(bytes, offset + id_len, false)?;
+        let start = offset + id_len + size_len;
+        let unknown = size == (1 << (7 * size_len)) - 1;
+        let end = if unknown {
+            bytes.len()
+        } else {
+            start
+                .checked_add(usize::try_from(size).ok()?)?
+                .min(bytes.len())
+        };
+
+        offset = end;
+        Some((u32::try_from(id).ok()?, bytes.get(start..end)?))
+    })
+}
+
+/// Walks EBML elements like [`elements`], but descends into elements with given ids.
+///
+/// Useful for clusters which may have unknown size and contain nothing but elements.
+pub fn walk_elements<'a>(
+    bytes: &'a [u8],
+    containers: &'a [u32],
+) -> impl Iterator<Item = (u32, &'a [u8])> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let (id, id_len) = vint(bytes, offset, true)?;
+        let (size, size_len) = vint(bytes, offset + id_len, false)?;
+        let start = offset + id_len + size_len;
+        let id = u32::try_from(id).ok()?;
+        if containers.contains(&id) {
+            offset = start;
+            return Some((id, &bytes[start..start]));
+        }
+
+        let end = start
+            .checked_add(usize::try_from(size).ok()?)?
+            .min(bytes.len());
+        offset = end;
+        Some((id, bytes.get(start..end)?))
+    })
+}
+
+/// Finds EBML element with given id in a list of elements and returns its body.
+pub fn find_element(bytes: &[u8], id: u32) -> Option<&[u8]> {
+    elements(bytes)
+        .find(|(element_id, _)| *element_id == id)
+        .map(|(_, body)| body)
+}
+
+/// Unsigned integer stored in an EBML element body.
+pub fn ebml_uint(body: &[u8]) -> Option<u64> {
+    (body.len() <= 8).then(|| body.iter().fold(0, |value, b| (value << 8) | *b as u64))
+}
+
+/// Float stored in an EBML element body.
+pub fn ebml_float(body: &[u8]) -> Option<f64> {
+    match body.len() {
+        4 => Some(f32::from_be_bytes(body.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(body.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+/// Reads big endian bit fields.
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Reads up to 32 bits.
+    pub fn read(&mut self, bits: usize) -> Option<u32> {
+        let mut value = 0u64;
+        let mut remaining = bits;
+        while remaining > 0 {
+            let byte = *self.bytes.get(self.position / 8)?;
+            let available = 8 - self.position % 8;
+            let take = available.min(remaining);
+            let chunk = (byte as u32 >> (available - take)) & ((1 << take) - 1);
+            value = (value << take) | chunk as u64;
+            self.position += take;
+            remaining -= take;
+        }
+        Some(value as u32)
+    }
+
+    /// Reads up to 32 bits of a two's complement number.
+    pub fn read_signed(&mut self, bits: usize) -> Option<i32> {
+        if bits == 0 {
+            return Some(0);
+        }
+        let value = self.read(bits)?;
+        let shift = 32 - bits as u32;
+        Some(((value << shift) as i32) >> shift)
+    }
+
+    /// Counts zero bits before the next one bit, which is consumed too.
+    pub fn read_unary(&mut self) -> Option<u32> {
+        let mut zeros = 0;
+        loop {
+            let byte = *self.bytes.get(self.position / 8)?;
+            let rest = byte << (self.position % 8);
+            if rest == 0 {
+                let skipped = 8 - self.position % 8;
+                zeros += skipped as u32;
+                self.position += skipped;
+            } else {
+                let leading = rest.leading_zeros();
+                zeros += leading;
+                self.position += leading as usize + 1;
+                return Some(zeros);
+            }
+        }
+    }
+
+    pub fn skip(&mut self, bits: usize) -> Option<()> {
+        self.position += bits;
+        (self.position <= self.bytes.len() * 8).then_some(())
+    }
+
+    /// Skips to the start of the next byte.
+    pub fn align(&mut self) {
+        self.position = self.position.div_ceil(8) * 8;
+    }
+
+    /// Position in bytes, rounded up.
+    pub fn byte_position(&self) -> usize {
+        self.position.div_ceil(8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8; 4], size: u32, body: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&size.to_le_bytes());
+        chunk.extend_from_slice(body);
+        chunk
+    }
+
+    #[test]
+    fn chunks_are_word_aligned() {
+        let mut bytes = chunk(b"odd ", 3, b"abc\0");
+        bytes.extend(chunk(b"next", 2, b"de"));
+
+        let found: Vec<_> = chunks(&bytes).collect();
+        assert_eq!(
+            found,
+            [(&b"odd "[..], &b"abc"[..]), (&b"next"[..], &b"de"[..])]
+        );
+    }
+
+    #[test]
+    fn truncated_chunks() {
+        // Body is cut
+        let bytes = chunk(b"data", 100, b"abc");
+        assert_eq!(
+            chunks(&bytes).collect::<Vec<_>>(),
+            [(&b"data"[..], &b"abc"[..])]
+        );
+
+        // Size which can't be addressed ends the list
+        let mut bytes = chunk(b"junk", u32::MAX, b"abc");
+        bytes.extend(chunk(b"data", 0, b""));
+        assert_eq!(chunks(&bytes).count(), 1);
+
+        // Header is cut
+        assert_eq!(chunks(b"data\x04\0").count(), 0);
+        assert_eq!(find_chunk(b"LIST\x04\0\0\0", b"data"), None);
+    }
+
+    #[test]
+    fn chunks_in_lists() {
+        let list = chunk(
+            b"LIST",
+            16,
+            &[b"INFO".as_slice(), &chunk(b"INAM", 4, b"name")].concat(),
+        );
+        assert_eq!(find_chunk(&list, b"INAM"), Some(&b"name"[..]));
+    }
+
+    #[test]
+    fn vint_lengths() {
+        // Element ids keep the marker
+        assert_eq!(
+            vint(&[0x1a, 0x45, 0xdf, 0xa3], 0, true),
+            Some((0x1a45_dfa3, 4))
+        );
+        assert_eq!(vint(&[0x81], 0, false), Some((1, 1)));
+        assert_eq!(vint(&[0x40, 0x02], 0, false), Some((2, 2)));
+        assert_eq!(vint(&[0x00, 0x01], 0, false), None);
+        // Cut after the length marker
+        assert_eq!(vint(&[0x20, 0x01], 0, false), None);
+        assert_eq!(vint(&[0x01; 8], 0, false), Some((0x01_0101_0101_0101, 8)));
+    }
+
+    #[test]
+    fn elements_with_unknown_size() {
+        // Known size, then unknown size which extends to the end
+        let bytes = [0x86, 0x82, b'V', b'P', 0xa3, 0xff, 1, 2, 3];
+        let found: Vec<_> = elements(&bytes).collect();
+        assert_eq!(found, [(0x86, &b"VP"[..]), (0xa3, &[1, 2, 3][..])]);
+
+        // Size past the end is cut
+        assert_eq!(
+            elements(&[0x86, 0x85, 1]).collect::<Vec<_>>(),
+            [(0x86, &[1][..])]
+        );
+    }
+
+    #[test]
+    fn box_sizes() {
+        let mut bytes = 12u32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(b"ftypisom");
+        // Size 0 extends to the end
+        bytes.extend_from_slice(&0u32.to_be_bytes());
+        bytes.extend_from_slice(b"mdat\x01\x02");
+
+        assert_eq!(find_box(&bytes, b"ftyp"), Some(&b"isom"[..]));
+        assert_eq!(find_box(&bytes, b"mdat"), Some(&[1, 2][..]));
+        // Smaller than its header
+        assert_eq!(boxes(b"\0\0\0\x04free").count(), 0);
+    }
+}
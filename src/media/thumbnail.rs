@@ -0,0 +1,169 @@
+//! Extracts a representative frame of a video without decoding the whole stream.
+//!
+//! Only codecs storing frames as plain images (Motion JPEG, PNG, uncompressed AVI)
+//! can be read, for other codecs the cover art of MP4/MOV/MKV files is used if present.
+//!
+//! Compressed codecs like H.264, HEVC, VP8/VP9 and AV1 are out of scope: there's no decoder
+//! which builds for both native and the web without system libraries. Such videos without
+//! cover art keep the generic video icon.
+
+use super::{
+    matroska, mp4,
+    read::{chunks, find_box, find_path, u32_be, u32_le},
+};
+
+/// MP4/MOV sample formats whose samples are complete JPEG or PNG images.
+const IMAGE_FORMATS: [&[u8; 4]; 5] = [b"jpeg", b"mjpa", b"AVDJ", b"dmb1", b"png "];
+/// AVI compressions whose frames are complete JPEG images.
+const MJPEG_COMPRESSIONS: [&[u8; 4]; 3] = [b"MJPG", b"mjpg", b"AVRn"];
+
+/// Position of the representative frame, as a fraction of all frames.
+const FRAME_POSITION: f64 = 0.1;
+
+/// Encoded image (JPEG, PNG or BMP) of a frame around 10% of the video.
+pub fn video_frame(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 {
+        return None;
+    }
+
+    if mp4::is_mp4(bytes) {
+        mp4_frame(bytes)
+    } else if &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"AVI " {
+        avi_frame(bytes)
+    } else if let Some(segment) = matroska::segment(bytes) {
+        matroska_frame(segment)
+    } else {
+        None
+    }
+}
+
+fn mp4_frame(bytes: &[u8]) -> Option<Vec<u8>> {
+    let moov = find_box(bytes, b"moov")?;
+    mp4::tracks(moov)
+        .find_map(|track| mp4_track_frame(bytes, &track))
+        .or_else(|| mp4_cover(moov))
+}
+
+fn mp4_track_frame(bytes: &[u8], track: &mp4::Track<'_>) -> Option<Vec<u8>> {
+    if track.handler != b"vide"
+        || !IMAGE_FORMATS
+            .iter()
+            .any(|image_format| *image_format == track.format)
+    {
+        return None;
+    }
+
+    let sample_count = mp4::sample_count(track.stbl)?;
+    if sample_count == 0 {
+        return None;
+    }
+    let target = (sample_count as f64 * FRAME_POSITION) as u32;
+
+    // Last keyframe before the target, all samples are keyframes without `stss`
+    let sample = match find_box(track.stbl, b"stss") {
+        Some(stss) => (0..u32_be(stss, 4)?)
+            .map_while(|index| u32_be(stss, 8 + index as usize * 4))
+            .map(|number| number.saturating_sub(1))
+            .take_while(|&sample| sample <= target)
+            .last()
+            .unwrap_or(0),
+        None => target,
+    };
+
+    mp4::sample(bytes, track.stbl, sample).map(<[u8]>::to_vec)
+}
+
+/// Frame of a Motion JPEG track or the attached cover art.
+fn matroska_frame(segment: &[u8]) -> Option<Vec<u8>> {
+    let frame = matroska::tracks(segment)
+        .find(|track| track.kind == 1 && track.codec == "V_MJPEG")
+        .and_then(|track| {
+            let frames: Vec<_> = matroska::blocks(segment)
+                .filter(|block| block.track == track.number && !block.laced)
+                .map(|block| block.data)
+                .collect();
+            frames
+                .get((frames.len() as f64 * FRAME_POSITION) as usize)
+                .copied()
+        });
+    frame
+        .or_else(|| matroska::cover(segment))
+        .map(<[u8]>::to_vec)
+}
+
+/// Cover art from iTunes style metadata.
+fn mp4_cover(moov: &[u8]) -> Option<Vec<u8>> {
+    let meta = find_path(moov, &[b"udta", b"meta"])?;
+    // MP4 `meta` is a full box with version and flags, QuickTime one isn't
+    let meta = if meta.get(8..12) == Some(b"hdlr") {
+        &meta[4..]
+    } else {
+        meta
+    };
+
+    let data = find_path(meta, &[b"ilst", b"covr", b"data"])?;
+    // Skip type indicator and locale
+    data.get(8..)
+        .filter(|image| !image.is_empty())
+        .map(<[u8]>::to_vec)
+}
+
+fn avi_frame(bytes: &[u8]) -> Option<Vec<u8>> {
+    let list = |kind: &[u8; 4]| {
+        chunks(&bytes[12..]).find_map(|(id, body)| {
+            (id == b"LIST" && body.get(0..4) == Some(kind)).then(|| &body[4..])
+        })
+    };
+    let hdrl = list(b"hdrl")?;
+    let movi = list(b"movi")?;
+
+    // First video stream and its format
+    let (stream, strf) = chunks(hdrl)
+        .filter(|(id, body)| *id == b"LIST" && body.get(0..4) == Some(b"strl"))
+        .enumerate()
+        .find_map(|(index, (_, strl))| {
+            let strl = &strl[4..];
+            let strh = chunks(strl).find(|(id, _)| *id == b"strh")?.1;
+            let strf = chunks(strl).find(|(id, _)| *id == b"strf")?.1;
+            (strh.get(0..4)? == b"vids").then_some((index, strf))
+        })?;
+
+    // Frames of the stream are `##dc` (compressed) or `##db` (uncompressed) chunks
+    let prefix = format!("{stream:02}");
+    let frames: Vec<_> = chunks(movi)
+        .filter(|(id, body)| {
+            id.starts_with(prefix.as_bytes())
+                && (id.ends_with(b"dc") || id.ends_with(b"db"))
+                && !body.is_empty()
+        })
+        .map(|(_, body)| body)
+        .collect();
+    let frame = frames.get((frames.len() as f64 * FRAME_POSITION) as usize)?;
+
+    let compression = strf.get(16..20)?;
+    if MJPEG_COMPRESSIONS
+        .iter()
+        .any(|mjpeg_compression| *mjpeg_compression == compression)
+    {
+        Some(frame.to_vec())
+    } else if u32_le(strf, 16)? == 0 {
+        bitmap(strf, frame)
+    } else {
+        None
+    }
+}
+
+/// Uncompressed frame with its `BITMAPINFOHEADER`, wrapped into a BMP file.
+fn bitmap(info: &[u8], pixels: &[u8]) -> Option<Vec<u8>> {
+    let info = info.get(..u32_le(info, 0)? as usize)?;
+    let pixels_offset = 14 + info.len() as u32;
+
+    let mut bmp = Vec::with_capacity(pixels_offset as usize + pixels.len());
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(pixels_offset + pixels.len() as u32).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&pixels_offset.to_le_bytes());
+    bmp.extend_from_slice(info);
+    bmp.extend_from_slice(pixels);
+    Some(bmp)
+}
@@ -0,0 +1,358 @@
+//! Min/max peaks of audio for drawing waveforms.
+//!
+//! WAV and FLAC are decoded by the parsers used for probing. MP3, Ogg Vorbis and AAC in ADTS,
+//! MP4 or M4A are decoded with Symphonia.
+//!
+//! Opus is out of scope: Symphonia has no Opus decoder and the available ones need libopus,
+//! which doesn't build for the web. Opus files keep the generic sound icon.
+
+use std::{
+    io::{Read, Seek, SeekFrom},
+    ops::Range,
+};
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    errors::Error,
+    io::{MediaSource, MediaSourceStream},
+    probe::Hint,
+};
+
+use super::{
+    flac,
+    read::{find_chunk, u16_le, u32_le},
+};
+use crate::project::Content;
+
+/// Samples summarized by one peak of the finest level.
+const SAMPLES_PER_PEAK: usize = 256;
+/// Coarser levels are built until they would have fewer peaks than this.
+const MIN_PEAKS: usize = 64;
+
+/// Range of sample values, from -1 to 1.
+#[derive(Clone, Copy, Debug)]
+pub struct Peak {
+    pub min: f32,
+    pub max: f32,
+}
+impl Peak {
+    fn merge(self, other: Self) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+}
+
+/// Collects peaks of interleaved samples, `SAMPLES_PER_PEAK` frames each.
+#[derive(Default)]
+struct PeakBuilder {
+    peaks: Vec<Peak>,
+    peak: Option<Peak>,
+    frames: usize,
+}
+impl PeakBuilder {
+    /// Adds samples of all channels of one frame.
+    fn push_frame(&mut self, samples: impl IntoIterator<Item = f32>) {
+        for value in samples {
+            let sample = Peak {
+                min: value.min(0.),
+                max: value.max(0.),
+            };
+            self.peak = Some(self.peak.map_or(sample, |peak| peak.merge(sample)));
+        }
+        self.frames += 1;
+        if self.frames == SAMPLES_PER_PEAK {
+            self.peaks.extend(self.peak.take());
+            self.frames = 0;
+        }
+    }
+
+    fn finish(mut self) -> Vec<Peak> {
+        self.peaks.extend(self.peak);
+        self.peaks
+    }
+}
+
+/// Content read by Symphonia, which needs an owned source.
+struct ContentSource {
+    content: Content,
+    position: u64,
+}
+impl Read for ContentSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let start = (self.position as usize).min(self.content.len());
+        let read = buf.len().min(self.content.len() - start);
+        buf[..read].copy_from_slice(&self.content[start..start + read]);
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+impl Seek for ContentSource {
+    fn seek(&mut self, position: SeekFrom) -> std::io::Result<u64> {
+        let position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.content.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "seek before start")
+        })?;
+        Ok(self.position)
+    }
+}
+impl MediaSource for ContentSource {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.content.len() as u64)
+    }
+}
+
+/// Peaks of all channels mixed together, at several zoom levels.
+pub struct Waveform {
+    /// Seconds covered by one peak of the finest level.
+    seconds_per_peak: f64,
+    /// Levels from the finest one, each with half the peaks of the previous one.
+    levels: Vec<Vec<Peak>>,
+}
+impl Waveform {
+    /// `None` if the format isn't supported or there is no audio.
+    pub fn decode(content: &Content) -> Option<Self> {
+        let bytes: &[u8] = content;
+        if bytes.len() < 12 {
+            None
+        } else if &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE" {
+            Self::from_wav(bytes)
+        } else if flac::stream_info(bytes).is_some() {
+            Self::from_flac(bytes)
+        } else {
+            Self::from_symphonia(content.clone())
+        }
+    }
+
+    fn new(peaks: Vec<Peak>, seconds_per_peak: f64) -> Option<Self> {
+        if peaks.is_empty() || seconds_per_peak <= 0. {
+            return None;
+        }
+
+        let mut levels = vec![peaks];
+        while let Some(finer) = levels.last().filter(|peaks| peaks.len() >= MIN_PEAKS * 2) {
+            let coarser = finer
+                .chunks(2)
+                .map(|pair| {
+                    pair[1..]
+                        .iter()
+                        .fold(pair[0], |peak, other| peak.merge(*other))
+                })
+                .collect();
+            levels.push(coarser);
+        }
+
+        Some(Self {
+            seconds_per_peak,
+            levels,
+        })
+    }
+
+    fn from_wav(bytes: &[u8]) -> Option<Self> {
+        let chunks = &bytes[12..];
+        let fmt = find_chunk(chunks, b"fmt ")?;
+        let data = find_chunk(chunks, b"data")?;
+
+        let mut format = u16_le(fmt, 0)?;
+        let channels = u16_le(fmt, 2)? as usize;
+        let sample_rate = u32_le(fmt, 4)?;
+        let bits = u16_le(fmt, 14)?;
+        // WAVE_FORMAT_EXTENSIBLE keeps the real format in the sub format GUID
+        if format == 0xfffe {
+            format = u16_le(fmt, 24)?;
+        }
+        if channels == 0 || sample_rate == 0 {
+            return None;
+        }
+
+        let sample: fn(&[u8]) -> f32 = match (format, bits) {
+            (1, 8) => |b| (b[0] as f32 - 128.) / 128.,
+            (1, 16) => |b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32_768.,
+            (1, 24) => |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.,
+            (1, 32) => |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.,
+            (3, 32) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            (3, 64) => |b| f64::from_le_bytes(b.try_into().unwrap_or_default()) as f32,
+            _ => return None,
+        };
+        let sample_size = bits as usize / 8;
+
+        let peaks = data
+            .chunks(SAMPLES_PER_PEAK * channels * sample_size)
+            .map(|block| {
+                block.chunks_exact(sample_size).map(sample).fold(
+                    Peak { min: 0., max: 0. },
+                    |peak, value| Peak {
+                        min: peak.min.min(value),
+                        max: peak.max.max(value),
+                    },
+                )
+            })
+            .collect();
+        Self::new(peaks, SAMPLES_PER_PEAK as f64 / sample_rate as f64)
+    }
+
+    fn from_flac(bytes: &[u8]) -> Option<Self> {
+        let (info, _) = flac::stream_info(bytes)?;
+        if info.sample_rate == 0 {
+            return None;
+        }
+
+        let mut peaks = PeakBuilder::default();
+        for block in flac::blocks(bytes) {
+            let scale = 2f32.powi(block.bits_per_sample as i32 - 1);
+            let length = block.channels.first().map_or(0, Vec::len);
+            for index in 0..length {
+                peaks.push_frame(
+                    block
+                        .channels
+                        .iter()
+                        .map(|channel| channel[index] as f32 / scale),
+                );
+            }
+        }
+        Self::new(
+            peaks.finish(),
+            SAMPLES_PER_PEAK as f64 / info.sample_rate as f64,
+        )
+    }
+
+    /// Decodes the first sound track of MP3, Ogg Vorbis, ADTS, MP4 or M4A.
+    ///
+    /// Damaged packets are skipped, like players do.
+    fn from_symphonia(content: Content) -> Option<Self> {
+        let source = ContentSource {
+            content,
+            position: 0,
+        };
+        let stream = MediaSourceStream::new(Box::new(source), Default::default());
+        let mut format = symphonia::default::get_probe()
+            .format(
+                &Hint::new(),
+                stream,
+                &Default::default(),
+                &Default::default(),
+            )
+            .ok()?
+            .format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)?;
+        let track_id = track.id;
+        let sample_rate = track.codec_params.sample_rate.filter(|rate| *rate > 0)?;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .ok()?;
+
+        let mut peaks = PeakBuilder::default();
+        let mut samples: Option<SampleBuffer<f32>> = None;
+        loop {
+            // Reading past the end is the usual way streams end
+            let Ok(packet) = format.next_packet() else {
+                break;
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(Error::DecodeError(err)) => {
+                    log::debug!("Skipping damaged packet: {err}");
+                    continue;
+                }
+                Err(_) => break,
+            };
+
+            let spec = *decoded.spec();
+            let channels = spec.channels.count();
+            if channels == 0 {
+                continue;
+            }
+            let buffer = match &mut samples {
+                Some(buffer) if buffer.capacity() >= decoded.capacity() * channels => buffer,
+                _ => samples.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+            };
+            buffer.copy_interleaved_ref(decoded);
+            for frame in buffer.samples().chunks_exact(channels) {
+                peaks.push_frame(frame.iter().copied());
+            }
+        }
+
+        Self::new(peaks.finish(), SAMPLES_PER_PEAK as f64 / sample_rate as f64)
+    }
+
+    /// Length in seconds.
+    pub fn duration(&self) -> f64 {
+        self.levels[0].len() as f64 * self.seconds_per_peak
+    }
+
+    /// Coarsest level still at least as detailed as requested, with seconds per its peak.
+    pub fn level(&self, seconds_per_peak: f64) -> (&[Peak], f64) {
+        let mut level = 0;
+        while level + 1 < self.levels.len()
+            && self.seconds_per_peak * 2f64.powi(level as i32 + 1) <= seconds_per_peak
+        {
+            level += 1;
+        }
+
+        (
+            &self.levels[level],
+            self.seconds_per_peak * 2f64.powi(level as i32),
+        )
+    }
+
+    /// Draws the waveform of the time range in seconds, one line per pixel column.
+    pub fn paint(
+        &self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        range: Range<f64>,
+        color: egui::Color32,
+    ) {
+        let columns = rect.width().floor() as usize;
+        if columns == 0 || range.end <= range.start {
+            return;
+        }
+
+        let seconds_per_column = (range.end - range.start) / columns as f64;
+        let (peaks, seconds_per_peak) = self.level(seconds_per_column);
+        let center = rect.center().y;
+        let half_height = rect.height() / 2.;
+
+        for column in 0..columns {
+            let start = range.start + column as f64 * seconds_per_column;
+            if start < 0. {
+                continue;
+            }
+            let first = (start / seconds_per_peak) as usize;
+            let last = (((start + seconds_per_column) / seconds_per_peak).ceil() as usize)
+                .clamp(first + 1, peaks.len().max(first + 1));
+            let Some(peak) = peaks
+                .get(first..last)
+                .and_then(|peaks| peaks.iter().copied().reduce(Peak::merge))
+            else {
+                break;
+            };
+
+            let x = rect.left() + column as f32 + 0.5;
+            let top = center - peak.max.clamp(-1., 1.) * half_height;
+            // At least one pixel, so silence is visible as a line
+            let bottom = (center - peak.min.clamp(-1., 1.) * half_height).max(top + 1.);
+            painter.line_segment(
+                [egui::pos2(x, top), egui::pos2(x, bottom)],
+                egui::Stroke::new(1., color),
+            );
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{panes::Files, tiles, App};
+use crate::{
+    history::{EditSettings, History},
+    panes::{Files, Markers, Timeline},
+    project_file::ProjectFile,
+    tiles, App,
+};
 
 impl App {
     pub fn menu_bar(&mut self, ctx: &egui::Context) {
@@ -6,6 +11,49 @@ impl App {
             egui::menu::bar(ui, |ui| {
                 let is_web = cfg!(target_arch = "wasm32");
                 ui.menu_button("File", |ui| {
+                    if ui
+                        .add(
+                            egui::Button::new("New project").shortcut_text(
+                                ui.ctx().format_shortcut(&ProjectFile::NEW_SHORTCUT),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        self.new_project();
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new("Open project…").shortcut_text(
+                                ui.ctx().format_shortcut(&ProjectFile::OPEN_SHORTCUT),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        self.open_project(ctx);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new("Save").shortcut_text(
+                                ui.ctx().format_shortcut(&ProjectFile::SAVE_SHORTCUT),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        self.save_project(ctx);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add(egui::Button::new("Save as…").shortcut_text(
+                            ui.ctx().format_shortcut(&ProjectFile::SAVE_AS_SHORTCUT),
+                        ))
+                        .clicked()
+                    {
+                        self.save_project_as(ctx);
+                        ui.close_menu();
+                    }
+                    ui.separator();
                     if ui
                         .add(
                             egui::Button::new("Import file").shortcut_text(
@@ -17,14 +65,126 @@ impl App {
                         Files::import_file_dialog(&mut self.behavior.files, ui);
                         ui.close_menu();
                     };
-                    if !is_web && ui.button("Quit").clicked() {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if ui.button("Import folder…").clicked() {
+                        self.behavior.files.import_folder_dialog(ui);
+                        ui.close_menu();
+                    }
+                    ui.menu_button("Project settings", |ui| {
+                        let state = &mut self.behavior.state;
+                        let mut settings = state.project.settings.clone();
+                        let response = ui
+                            .horizontal(|ui| {
+                                ui.label("Still duration");
+                                ui.add(
+                                    egui::DragValue::new(&mut settings.still_duration)
+                                        .range(0.1..=3600.)
+                                        .speed(0.1)
+                                        .suffix(" s"),
+                                )
+                            })
+                            .inner;
+                        if settings != state.project.settings {
+                            let before = state.project.settings.clone();
+                            state.execute(EditSettings::new(before, settings, response.id));
+                        }
+                    });
+                    if !is_web {
+                        ui.separator();
+                        if ui.button("Quit").clicked() {
+                            self.quit(ctx);
+                        }
+                    }
+                });
+                ui.menu_button("Edit", |ui| {
+                    let state = &mut self.behavior.state;
+
+                    let undo_name = state.history.undo_name();
+                    if ui
+                        .add_enabled(
+                            undo_name.is_some(),
+                            egui::Button::new(match &undo_name {
+                                Some(name) => format!("Undo {name}"),
+                                None => "Undo".to_owned(),
+                            })
+                            .shortcut_text(ui.ctx().format_shortcut(&History::UNDO_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        state.history.undo(&mut state.project);
+                        ui.close_menu();
+                    }
+
+                    let redo_name = state.history.redo_name();
+                    if ui
+                        .add_enabled(
+                            redo_name.is_some(),
+                            egui::Button::new(match &redo_name {
+                                Some(name) => format!("Redo {name}"),
+                                None => "Redo".to_owned(),
+                            })
+                            .shortcut_text(ui.ctx().format_shortcut(&History::REDO_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        state.history.redo(&mut state.project);
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+                    if ui
+                        .add(
+                            egui::Button::new("Split at playhead")
+                                .shortcut_text(ui.ctx().format_shortcut(&Timeline::SPLIT_SHORTCUT)),
+                        )
+                        .clicked()
+                    {
+                        Timeline::split_at_playhead(state);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new("Add marker").shortcut_text(
+                                ui.ctx().format_shortcut(&Markers::ADD_MARKER_SHORTCUT),
+                            ),
+                        )
+                        .clicked()
+                    {
+                        Markers::add_marker(state);
+                        ui.close_menu();
+                    }
+                    if ui
+                        .add_enabled(
+                            state.project.sequence.in_out_range().is_some(),
+                            egui::Button::new("Add region from in to out"),
+                        )
+                        .clicked()
+                    {
+                        Markers::add_region(state);
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+                    if ui.button("Find duplicates").clicked() {
+                        self.behavior.files.find_duplicates();
+                        ui.close_menu();
                     }
                 });
                 ui.menu_button("Window", |ui| {
                     ui.menu_button("Theme", |ui| {
                         egui::widgets::global_theme_preference_buttons(ui);
                     });
+                    ui.horizontal(|ui| {
+                        ui.label("Thumbnail memory");
+                        let thumbnails = &mut self.behavior.state.thumbnails;
+                        let mut megabytes = thumbnails.memory_budget / (1024 * 1024);
+                        ui.add(
+                            egui::DragValue::new(&mut megabytes)
+                                .range(16..=4096)
+                                .suffix(" MB"),
+                        );
+                        thumbnails.memory_budget = megabytes * 1024 * 1024;
+                    });
                     if ui.button("Reset window layout").clicked() {
                         self.tree = tiles::create_tree();
                         ui.close_menu();
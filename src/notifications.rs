@@ -0,0 +1,179 @@
+/// Maximal number of toasts shown at once, older ones stay in the history.
+const MAX_TOASTS: usize = 5;
+const TOAST_WIDTH: f32 = 320.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+impl Severity {
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Info => "ℹ",
+            Self::Warning => "⚠",
+            Self::Error => "❌",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Self::Info => visuals.text_color(),
+            Self::Warning => visuals.warn_fg_color,
+            Self::Error => visuals.error_fg_color,
+        }
+    }
+
+    /// How long toast stays on screen, in seconds.
+    fn duration(self) -> f64 {
+        match self {
+            Self::Info => 4.,
+            Self::Warning => 6.,
+            Self::Error => 10.,
+        }
+    }
+}
+
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+}
+
+struct Toast {
+    /// Index into history.
+    notification: usize,
+    /// Time when toast was first shown, `None` until then.
+    shown_at: Option<f64>,
+}
+
+/// Non-modal notifications shown as stacked toasts.
+#[derive(Default)]
+pub struct Notifications {
+    history: Vec<Notification>,
+    toasts: Vec<Toast>,
+    pub show_history: bool,
+}
+impl Notifications {
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        let message = message.into();
+        match severity {
+            Severity::Info => log::info!("{message}"),
+            Severity::Warning => log::warn!("{message}"),
+            Severity::Error => log::error!("{message}"),
+        }
+
+        self.toasts.push(Toast {
+            notification: self.history.len(),
+            shown_at: None,
+        });
+        self.history.push(Notification { severity, message });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Info, message);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Warning, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.notify(Severity::Error, message);
+    }
+
+    pub fn history(&self) -> &[Notification] {
+        &self.history
+    }
+
+    /// Draws toasts in the bottom right corner and the history window.
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+
+        // Auto-dismiss
+        self.toasts.retain(|toast| {
+            let duration = self.history[toast.notification].severity.duration();
+            toast
+                .shown_at
+                .is_none_or(|shown_at| now - shown_at < duration)
+        });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.drain(..self.toasts.len() - MAX_TOASTS);
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8., -32.))
+            .interactable(true)
+            .show(ctx, |ui| {
+                ui.set_width(TOAST_WIDTH);
+                for (index, toast) in self.toasts.iter_mut().enumerate() {
+                    let shown_at = *toast.shown_at.get_or_insert(now);
+                    let notification = &self.history[toast.notification];
+                    let remaining = notification.severity.duration() - (now - shown_at);
+                    ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+                        remaining.max(0.),
+                    ));
+
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(TOAST_WIDTH);
+                        ui.horizontal_top(|ui| {
+                            let color = notification.severity.color(ui.visuals());
+                            ui.label(
+                                egui::RichText::new(notification.severity.icon()).color(color),
+                            );
+                            ui.allocate_ui(egui::vec2(ui.available_width() - 24., 0.), |ui| {
+                                ui.add(egui::Label::new(&notification.message).wrap());
+                            });
+                            if ui.small_button("🗙").clicked() {
+                                dismissed = Some(index);
+                            }
+                        });
+                    });
+                }
+            });
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+
+        let mut open = self.show_history;
+        egui::Window::new("Notifications")
+            .open(&mut open)
+            .default_width(TOAST_WIDTH)
+            .show(ctx, |ui| {
+                if self.history.is_empty() {
+                    ui.label("No notifications");
+                    return;
+                }
+                if ui.button("Clear").clicked() {
+                    self.history.clear();
+                    self.toasts.clear();
+                    return;
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for notification in self.history.iter().rev() {
+                        ui.horizontal(|ui| {
+                            let color = notification.severity.color(ui.visuals());
+                            ui.label(
+                                egui::RichText::new(notification.severity.icon()).color(color),
+                            );
+                            ui.add(egui::Label::new(&notification.message).wrap());
+                        });
+                        ui.separator();
+                    }
+                });
+            });
+        self.show_history = open;
+    }
+}
+
+/// Word for the count in messages, e.g. `file` or `files`.
+pub fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
@@ -0,0 +1,386 @@
+use std::path::{Path, PathBuf};
+
+use super::{DraggedFile, Files};
+use crate::{
+    history::{DeleteItems, SetFrameRate},
+    project::{AssetId, Bin, Project},
+    sequence::FrameRate,
+    state::State,
+};
+
+/// File or bin shown in the grid.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    File(AssetId),
+    Bin(AssetId),
+}
+
+/// Drag and drop payload of a bin dragged out of the grid.
+pub struct DraggedBin {
+    pub id: AssetId,
+}
+
+/// What an item is dropped on.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DropTarget {
+    Bin,
+    /// File in the grid, dropped files go before it.
+    File,
+    /// File in the details table, dropped files go above it.
+    Row,
+}
+
+/// Change requested by the grid, applied once all files are drawn.
+pub enum Action {
+    Open(Option<AssetId>),
+    StartRename(Item),
+    Rename(Item, String),
+    Delete(Item),
+    Reveal(PathBuf),
+    /// Opens the relink dialog.
+    #[cfg(not(target_arch = "wasm32"))]
+    Relink,
+    /// Moves the item to the end of the bin.
+    Move(Item, Option<AssetId>),
+    /// Moves the first file right before the second one, into its bin.
+    Reorder(AssetId, AssetId),
+    /// Plays the image sequence at another frame rate.
+    SetFrameRate(AssetId, FrameRate),
+}
+
+impl Files {
+    /// Path to the open bin, where items can be dropped to move them up.
+    pub(super) fn breadcrumb_ui(
+        &self,
+        ui: &mut egui::Ui,
+        project: &Project,
+        action: &mut Option<Action>,
+    ) {
+        ui.horizontal(|ui| {
+            let mut bins: Vec<Option<&Bin>> = vec![None];
+            bins.extend(project.bin_path(self.current_bin).into_iter().map(Some));
+
+            for (index, bin) in bins.iter().enumerate() {
+                if index > 0 {
+                    ui.label("›");
+                }
+                let id = bin.map(|bin| bin.id);
+                let name = bin.map_or("All files", |bin| bin.name.as_str());
+                let response = ui.selectable_label(id == self.current_bin, name);
+                if response.clicked() {
+                    *action = Some(Action::Open(id));
+                }
+                if let Some(item) = Self::drop_payload(&response, DropTarget::Bin) {
+                    *action = Some(Action::Move(item, id));
+                }
+            }
+        });
+    }
+
+    /// Item dropped on the response, highlighting it while something is dragged over.
+    ///
+    /// Files dropped on other files go before them, anything dropped on a bin goes into it.
+    pub(super) fn drop_payload(response: &egui::Response, target: DropTarget) -> Option<Item> {
+        let accept_bins = target == DropTarget::Bin;
+        let hovered = response.dnd_hover_payload::<DraggedFile>().is_some()
+            || (accept_bins && response.dnd_hover_payload::<DraggedBin>().is_some());
+        if hovered {
+            let stroke = response.ctx.style().visuals.selection.stroke;
+            let painter = response.ctx.layer_painter(response.layer_id);
+            match target {
+                DropTarget::Bin => painter.rect_stroke(response.rect, 2., stroke),
+                DropTarget::File => painter.vline(
+                    response.rect.left() - 4.,
+                    response.rect.y_range(),
+                    egui::Stroke::new(2., stroke.color),
+                ),
+                DropTarget::Row => painter.hline(
+                    response.rect.x_range(),
+                    response.rect.top(),
+                    egui::Stroke::new(2., stroke.color),
+                ),
+            };
+        }
+
+        if let Some(file) = response.dnd_release_payload::<DraggedFile>() {
+            Some(Item::File(file.id))
+        } else if accept_bins {
+            response
+                .dnd_release_payload::<DraggedBin>()
+                .map(|bin| Item::Bin(bin.id))
+        } else {
+            None
+        }
+    }
+
+    /// Opening bins, dropping items and the context menu, same in the grid and the table.
+    ///
+    /// Files only accept dropped files when `reorder` is set, as sorted views have no order to change.
+    pub(super) fn item_interaction(
+        response: &egui::Response,
+        project: &Project,
+        item: Item,
+        target: DropTarget,
+        reorder: bool,
+        action: &mut Option<Action>,
+    ) {
+        let path = match item {
+            Item::File(id) => {
+                if reorder {
+                    if let Some(Item::File(dropped)) = Self::drop_payload(response, target) {
+                        *action = Some(Action::Reorder(dropped, id));
+                    }
+                }
+                project.file(id).and_then(|file| file.path.as_deref())
+            }
+            Item::Bin(id) => {
+                if response.double_clicked() {
+                    *action = Some(Action::Open(Some(id)));
+                }
+                if let Some(dropped) = Self::drop_payload(response, DropTarget::Bin) {
+                    if dropped != item {
+                        *action = Some(Action::Move(dropped, Some(id)));
+                    }
+                }
+                None
+            }
+        };
+        response.context_menu(|ui| Self::context_menu_ui(ui, project, item, path, action));
+    }
+
+    /// Rename, Delete, Reveal and Move to bin actions of an item.
+    pub(super) fn context_menu_ui(
+        ui: &mut egui::Ui,
+        project: &Project,
+        item: Item,
+        path: Option<&Path>,
+        action: &mut Option<Action>,
+    ) {
+        if ui.button("Rename").clicked() {
+            *action = Some(Action::StartRename(item));
+            ui.close_menu();
+        }
+        if ui.button("Delete").clicked() {
+            *action = Some(Action::Delete(item));
+            ui.close_menu();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Item::File(id) = item {
+            if project.file(id).is_some_and(|file| file.is_offline())
+                && ui.button("Relink…").clicked()
+            {
+                *action = Some(Action::Relink);
+                ui.close_menu();
+            }
+        }
+        let image_sequence = match item {
+            Item::File(id) => project
+                .file(id)
+                .and_then(|file| file.image_sequence.as_ref()),
+            Item::Bin(_) => None,
+        };
+        if let (Item::File(id), Some(image_sequence)) = (item, image_sequence) {
+            ui.menu_button("Frame rate", |ui| {
+                for frame_rate in FrameRate::COMMON {
+                    let selected = image_sequence.frame_rate == frame_rate;
+                    if ui.selectable_label(selected, frame_rate.name()).clicked() {
+                        *action = Some(Action::SetFrameRate(id, frame_rate));
+                        ui.close_menu();
+                    }
+                }
+            });
+        }
+        if !cfg!(target_arch = "wasm32")
+            && ui
+                .add_enabled(path.is_some(), egui::Button::new("Reveal"))
+                .on_disabled_hover_text("File was imported without a path")
+                .clicked()
+        {
+            *action = path.map(|path| Action::Reveal(path.to_owned()));
+            ui.close_menu();
+        }
+
+        ui.menu_button("Move to bin", |ui| {
+            let location = match item {
+                Item::File(id) => project.file(id).and_then(|file| project.file_bin(file)),
+                Item::Bin(id) => project.bin(id).and_then(|bin| bin.parent),
+            };
+            let mut target = |ui: &mut egui::Ui, bin: Option<AssetId>, depth, name: &str| {
+                // Bin can't go into itself or its own bins
+                let inside_itself = match item {
+                    Item::Bin(id) => project.bin_contains(id, bin),
+                    Item::File(_) => false,
+                };
+                ui.horizontal(|ui| {
+                    ui.add_space(depth as f32 * 12.);
+                    if ui
+                        .add_enabled(bin != location && !inside_itself, egui::Button::new(name))
+                        .clicked()
+                    {
+                        *action = Some(Action::Move(item, bin));
+                        ui.close_menu();
+                    }
+                });
+            };
+
+            target(ui, None, 0, "All files");
+            for bin in &project.bins {
+                let depth = project.bin_path(Some(bin.id)).len();
+                target(ui, Some(bin.id), depth, &bin.name);
+            }
+        });
+    }
+
+    /// Applies change requested by the grid.
+    pub(super) fn apply_action(&mut self, state: &mut State, action: Action) {
+        let project = &state.project;
+        let name = |item| match item {
+            Item::File(id) => project.file(id).map(|file| file.name.clone()),
+            Item::Bin(id) => project.bin(id).map(|bin| bin.name.clone()),
+        };
+
+        match action {
+            Action::Open(bin) => {
+                self.current_bin = bin;
+                self.renaming = None;
+            }
+            Action::StartRename(item) => {
+                self.renaming = name(item).map(|name| (item, name));
+            }
+            Action::Rename(item, new_name) => {
+                self.renaming = None;
+                let new_name = new_name.trim().to_owned();
+                if new_name.is_empty() || name(item).as_ref() == Some(&new_name) {
+                    return;
+                }
+                state.organize_files(format!("Rename to {new_name}"), |organization| match item {
+                    Item::File(id) => {
+                        if let Some(file) = organization.file_mut(id) {
+                            file.name = new_name;
+                        }
+                    }
+                    Item::Bin(id) => {
+                        if let Some(bin) = organization.bin_mut(id) {
+                            bin.name = new_name;
+                        }
+                    }
+                });
+            }
+            Action::Delete(item) => {
+                let Some(name) = name(item) else {
+                    return;
+                };
+                let (files, bins) = match item {
+                    Item::File(id) => (vec![id], Vec::new()),
+                    Item::Bin(id) => (Vec::new(), vec![id]),
+                };
+                state.execute(DeleteItems::new(format!("Delete {name}"), files, bins));
+            }
+            Action::Reveal(path) => {
+                if let Err(err) = reveal(&path) {
+                    state
+                        .notifications
+                        .warning(format!("Can't reveal {}: {err}", path.display()));
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Action::Relink => self.relink_open = true,
+            Action::SetFrameRate(id, frame_rate) => {
+                let before = project
+                    .file(id)
+                    .and_then(|file| file.image_sequence.as_ref())
+                    .map(|image_sequence| image_sequence.frame_rate);
+                if let Some(before) = before.filter(|before| *before != frame_rate) {
+                    state.execute(SetFrameRate::new(id, before, frame_rate));
+                }
+            }
+            Action::Move(item, bin) => {
+                if let Item::Bin(id) = item {
+                    if project.bin_contains(id, bin) {
+                        return;
+                    }
+                }
+                let Some(name) = name(item) else {
+                    return;
+                };
+                state.organize_files(format!("Move {name}"), |organization| match item {
+                    Item::File(id) => {
+                        organization.move_file(id, None);
+                        if let Some(file) = organization.file_mut(id) {
+                            file.bin = bin;
+                        }
+                    }
+                    Item::Bin(id) => {
+                        if let Some(moved) = organization.bin_mut(id) {
+                            moved.parent = bin;
+                        }
+                    }
+                });
+            }
+            Action::Reorder(id, before) => {
+                if id == before {
+                    return;
+                }
+                let Some(name) = name(Item::File(id)) else {
+                    return;
+                };
+                let bin = project.file(before).and_then(|file| project.file_bin(file));
+                state.organize_files(format!("Move {name}"), |organization| {
+                    organization.move_file(id, Some(before));
+                    if let Some(file) = organization.file_mut(id) {
+                        file.bin = bin;
+                    }
+                });
+            }
+        }
+    }
+
+    /// Creates bin in the open bin and starts renaming it.
+    pub(super) fn new_bin(&mut self, state: &mut State) {
+        let name = (1..)
+            .map(|number| format!("Bin {number}"))
+            .find(|name| state.project.bins.iter().all(|bin| &bin.name != name))
+            .unwrap_or_default();
+        let bin = Bin {
+            id: AssetId::new(),
+            name: name.clone(),
+            parent: self.current_bin,
+        };
+
+        self.renaming = Some((Item::Bin(bin.id), name));
+        state.organize_files("New bin", |organization| organization.bins.push(bin));
+    }
+}
+
+/// Shows the file in the system file manager.
+#[cfg(not(target_arch = "wasm32"))]
+fn reveal(path: &Path) -> std::io::Result<()> {
+    use std::process::Command;
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("explorer");
+        command.arg("/select,").arg(path);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    };
+    // Most file managers can't select a file, so open its folder
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+
+    command.spawn().map(|_| ())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn reveal(_path: &Path) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
@@ -0,0 +1,197 @@
+use egui_extras::{Column, TableBuilder};
+
+use super::{
+    bins::{Action, DraggedBin, DropTarget, Item},
+    import::format_size,
+    view::SortColumn,
+    DraggedFile, Files,
+};
+use crate::project::{Bin, File, Project};
+
+const ROW_HEIGHT: f32 = 22.;
+/// Columns of the table, the name column comes first.
+const COLUMNS: [SortColumn; 6] = [
+    SortColumn::Name,
+    SortColumn::Type,
+    SortColumn::Duration,
+    SortColumn::Resolution,
+    SortColumn::Size,
+    SortColumn::Imported,
+];
+
+impl Files {
+    /// Table with a row for each bin and file, clicking a column header sorts by it.
+    pub(super) fn details_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        project: &Project,
+        (bins, files): (&[&Bin], &[&File]),
+        action: &mut Option<Action>,
+    ) {
+        let reorder = self.view.sort == SortColumn::Manual;
+
+        let mut table = TableBuilder::new(ui);
+        if self.scroll_to_highlighted {
+            self.scroll_to_highlighted = false;
+            let row = files
+                .iter()
+                .position(|file| Some(file.id) == self.highlighted)
+                .map(|index| bins.len() + index);
+            if let Some(row) = row {
+                table = table.scroll_to_row(row, Some(egui::Align::Center));
+            }
+        }
+        table
+            .id_salt("files-details")
+            .striped(true)
+            .resizable(true)
+            .sense(egui::Sense::click_and_drag())
+            .column(Column::remainder().at_least(120.).clip(true))
+            .columns(Column::auto().at_least(70.).clip(true), COLUMNS.len() - 1)
+            .header(ROW_HEIGHT, |mut header| {
+                for column in COLUMNS {
+                    header.col(|ui| {
+                        let sorted = self.view.sort == column;
+                        let arrow = match (sorted, self.view.descending) {
+                            (false, _) => "",
+                            (true, false) => " ⏶",
+                            (true, true) => " ⏷",
+                        };
+                        let text =
+                            egui::RichText::new(format!("{}{arrow}", column.name())).strong();
+                        if ui.selectable_label(sorted, text).clicked() {
+                            self.view.sort_by(column);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, bins.len() + files.len(), |mut row| {
+                    let index = row.index();
+                    let (item, name) = match bins.get(index) {
+                        Some(bin) => (Item::Bin(bin.id), bin.name.as_str()),
+                        None => {
+                            let file = files[index - bins.len()];
+                            (Item::File(file.id), file.name.as_str())
+                        }
+                    };
+
+                    let file = index.checked_sub(bins.len()).map(|index| files[index]);
+                    if file.is_some_and(|file| Some(file.id) == self.highlighted) {
+                        row.set_selected(true);
+                    }
+                    row.col(|ui| {
+                        ui.horizontal(|ui| {
+                            match file {
+                                Some(file) if file.is_offline() => {
+                                    ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                                        .on_hover_text("Media offline");
+                                }
+                                Some(_) => {
+                                    ui.label("🗋");
+                                }
+                                None => {
+                                    ui.label("🗀");
+                                }
+                            }
+                            self.name_ui(ui, item, name, action);
+                        });
+                    });
+                    for column in &COLUMNS[1..] {
+                        row.col(|ui| {
+                            let text = match (file, column) {
+                                (None, SortColumn::Type) => "Bin".to_owned(),
+                                (Some(file), column) => column_text(file, *column),
+                                (None, _) => String::new(),
+                            };
+                            ui.add(egui::Label::new(text).truncate().selectable(false));
+                        });
+                    }
+
+                    // Text field needs clicks for itself
+                    if self.is_renaming(item) {
+                        return;
+                    }
+                    let response = row.response();
+                    match item {
+                        Item::Bin(id) => response.dnd_set_drag_payload(DraggedBin { id }),
+                        Item::File(id) => response.dnd_set_drag_payload(DraggedFile { id }),
+                    }
+                    if response.dragged() {
+                        egui::show_tooltip_at_pointer(
+                            &response.ctx,
+                            response.layer_id,
+                            response.id.with("dragged"),
+                            |ui| ui.label(name),
+                        );
+                    }
+                    Self::item_interaction(
+                        &response,
+                        project,
+                        item,
+                        DropTarget::Row,
+                        reorder,
+                        action,
+                    );
+                    if response.clicked()
+                        && matches!(item, Item::File(id) if Some(id) == self.highlighted)
+                    {
+                        self.highlighted = None;
+                    }
+                });
+            });
+    }
+}
+
+fn column_text(file: &File, column: SortColumn) -> String {
+    let info = &file.info;
+    match column {
+        SortColumn::Manual | SortColumn::Name => file.name.clone(),
+        SortColumn::Type if info.format.is_empty() => file.r#type.name().to_owned(),
+        SortColumn::Type => format!("{} · {}", file.r#type.name(), info.format),
+        SortColumn::Duration => info.duration.map(format_duration).unwrap_or_default(),
+        SortColumn::Resolution => info
+            .resolution
+            .map(|[width, height]| format!("{width}×{height}"))
+            .unwrap_or_default(),
+        // Projects saved before sizes were stored
+        SortColumn::Size if file.size == 0 => String::new(),
+        SortColumn::Size => format_size(file.size),
+        SortColumn::Imported => file.imported.map(format_date).unwrap_or_default(),
+    }
+}
+
+/// Duration as `M:SS`, or `H:MM:SS` from an hour up.
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Seconds since the Unix epoch as a `YYYY-MM-DD HH:MM` UTC date.
+fn format_date(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let minutes = timestamp % 86_400 / 60;
+
+    // Days to civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
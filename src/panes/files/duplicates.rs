@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use super::{
+    bins::{Action, Item},
+    import::format_size,
+    Files,
+};
+use crate::{
+    history::ImportFiles,
+    notifications::plural,
+    project::{AssetId, File, Project},
+    state::State,
+};
+
+/// Imported file whose content is already in the project, waiting for the user to decide.
+pub struct Duplicate {
+    file: File,
+    existing: AssetId,
+    import: u64,
+}
+
+/// What to do with the duplicates of an import.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateChoice {
+    Skip,
+    Import,
+}
+
+impl Files {
+    /// Adds the imported file, unless its content is already in the project.
+    pub(super) fn add_file(&mut self, state: &mut State, file: File, import: u64) {
+        let Some(existing) = Self::find_duplicate(&state.project, &file) else {
+            state.execute(ImportFiles::new(import, file));
+            return;
+        };
+        match self.duplicate_choice {
+            Some(DuplicateChoice::Skip) => {}
+            Some(DuplicateChoice::Import) => state.execute(ImportFiles::new(import, file)),
+            None => self.duplicates.push_back(Duplicate {
+                file,
+                existing,
+                import,
+            }),
+        }
+    }
+
+    /// File with the same size and fingerprint.
+    fn find_duplicate(project: &Project, file: &File) -> Option<AssetId> {
+        let hash = file.hash?;
+        project
+            .files
+            .iter()
+            .find(|other| {
+                other.id != file.id && other.hash == Some(hash) && other.size == file.size
+            })
+            .map(|other| other.id)
+    }
+
+    /// Opens the window listing files with the same content.
+    pub fn find_duplicates(&mut self) {
+        self.duplicates_open = true;
+    }
+
+    /// Opens the bin with the file and highlights it.
+    pub(super) fn show_file(&mut self, project: &Project, id: AssetId) {
+        let Some(file) = project.file(id) else {
+            return;
+        };
+        self.current_bin = project.file_bin(file);
+        self.search.clear();
+        self.renaming = None;
+        self.highlighted = Some(id);
+        self.scroll_to_highlighted = true;
+    }
+
+    /// Outlines the shown file in the grid until it's clicked.
+    pub(super) fn highlight_ui(&mut self, ui: &egui::Ui, response: &egui::Response, id: AssetId) {
+        if self.highlighted != Some(id) {
+            return;
+        }
+        if response.clicked() {
+            self.highlighted = None;
+            return;
+        }
+        ui.painter()
+            .rect_stroke(response.rect.expand(2.), 3., ui.visuals().selection.stroke);
+        if std::mem::take(&mut self.scroll_to_highlighted) {
+            response.scroll_to_me(Some(egui::Align::Center));
+        }
+    }
+
+    /// Asks what to do with the next duplicate, one at a time.
+    pub(super) fn duplicate_ui(&mut self, ctx: &egui::Context, state: &mut State) {
+        if self.duplicates.is_empty() {
+            // Choice for all is kept until the import finishes
+            if self.jobs.is_empty() {
+                self.duplicate_choice = None;
+            }
+            return;
+        }
+        let Some(Duplicate { file, existing, .. }) = self.duplicates.front() else {
+            return;
+        };
+        let existing = *existing;
+        let existing_name = state
+            .project
+            .file(existing)
+            .map_or("", |existing| existing.name.as_str());
+        let location = bin_location(&state.project, existing);
+
+        let mut choice = None;
+        let mut show = false;
+        let more = self.duplicates.len() - 1;
+        egui::Modal::new(egui::Id::new("duplicate_import")).show(ctx, |ui| {
+            ui.heading("Already imported");
+            ui.label(format!(
+                "{} has the same content as {existing_name} in {location}.",
+                file.name
+            ));
+            if more > 0 {
+                ui.checkbox(
+                    &mut self.apply_to_all,
+                    format!(
+                        "Do the same for {more} more {}",
+                        plural(more, "duplicate", "duplicates")
+                    ),
+                );
+            }
+            ui.add_space(8.);
+            ui.horizontal(|ui| {
+                if ui.button("Skip").clicked() {
+                    choice = Some(DuplicateChoice::Skip);
+                }
+                if ui.button("Import anyway").clicked() {
+                    choice = Some(DuplicateChoice::Import);
+                }
+                if ui.button("Show existing").clicked() {
+                    choice = Some(DuplicateChoice::Skip);
+                    show = true;
+                }
+            });
+        });
+
+        let Some(choice) = choice else {
+            return;
+        };
+        let count = if self.apply_to_all && !show {
+            self.duplicate_choice = Some(choice);
+            self.duplicates.len()
+        } else {
+            1
+        };
+        self.apply_to_all = false;
+        for Duplicate { file, import, .. } in self.duplicates.drain(..count) {
+            if choice == DuplicateChoice::Import {
+                state.execute(ImportFiles::new(import, file));
+            }
+        }
+        if show {
+            self.show_file(&state.project, existing);
+        }
+    }
+
+    /// Groups of files with the same content, opened by "Find duplicates".
+    pub(super) fn duplicates_window_ui(
+        &mut self,
+        ctx: &egui::Context,
+        project: &Project,
+        action: &mut Option<Action>,
+    ) {
+        if !self.duplicates_open {
+            return;
+        }
+
+        let mut open = true;
+        let mut show = None;
+        egui::Window::new("Duplicates")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                let groups = duplicate_groups(project);
+                if groups.is_empty() {
+                    ui.label("No files have the same content.");
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_height(360.)
+                    .show(ui, |ui| {
+                        for group in groups {
+                            ui.strong(format!(
+                                "{} copies, {}",
+                                group.len(),
+                                format_size(group[0].size)
+                            ));
+                            egui::Grid::new(("duplicates", group[0].id))
+                                .num_columns(3)
+                                .show(ui, |ui| {
+                                    for file in group {
+                                        ui.vertical(|ui| {
+                                            ui.label(&file.name);
+                                            ui.weak(bin_location(project, file.id));
+                                        });
+                                        if ui.button("Show").clicked() {
+                                            show = Some(file.id);
+                                        }
+                                        if ui
+                                            .button("Delete")
+                                            .on_hover_text("Clips using the file are removed too")
+                                            .clicked()
+                                        {
+                                            *action = Some(Action::Delete(Item::File(file.id)));
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                            ui.separator();
+                        }
+                    });
+            });
+        if !open {
+            self.duplicates_open = false;
+        }
+        if let Some(id) = show {
+            self.show_file(project, id);
+        }
+    }
+}
+
+/// Files with the same size and fingerprint, in project order.
+fn duplicate_groups(project: &Project) -> Vec<Vec<&File>> {
+    let mut groups: HashMap<(u64, u64), Vec<&File>> = HashMap::new();
+    for file in &project.files {
+        if let Some(hash) = file.hash {
+            groups.entry((file.size, hash)).or_default().push(file);
+        }
+    }
+
+    let mut groups: Vec<_> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect();
+    let position = |file: &File| project.files.iter().position(|other| other.id == file.id);
+    groups.sort_by_key(|group| position(group[0]));
+    groups
+}
+
+/// Path of bins the file is in, e.g. `All files › Card › Day 1`.
+fn bin_location(project: &Project, id: AssetId) -> String {
+    let bins = project
+        .file(id)
+        .map(|file| project.bin_path(project.file_bin(file)));
+    std::iter::once("All files")
+        .chain(bins.iter().flatten().map(|bin| bin.name.as_str()))
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
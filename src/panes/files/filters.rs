@@ -1,47 +1,108 @@
 use std::path::Path;
 
-use super::{FileData, FileType};
+use super::{import_error::ImportError, FileData};
+use crate::{
+    media::{probe, MediaInfo},
+    project::FileType,
+};
 
-pub const IMAGE_FILTER: [&str; 5] = ["png", "jpg", "jpeg", "gif", "svg"];
-pub const VIDEO_FILTER: [&str; 3] = ["mp4", "mov", "avi"];
-pub const SOUND_FILTER: [&str; 3] = ["mp3", "wav", "ogg"];
+pub const IMAGE_FILTER: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "tif", "tiff",
+];
+pub const VIDEO_FILTER: [&str; 5] = ["mp4", "mov", "avi", "webm", "mkv"];
+pub const SOUND_FILTER: [&str; 6] = ["mp3", "wav", "ogg", "flac", "m4a", "aac"];
+
+/// Supported extensions of each file type, used both by the import dialog and the checks.
+pub const FILTERS: [(FileType, &str, &[&str]); 3] = [
+    (FileType::Image, "Image", &IMAGE_FILTER),
+    (FileType::Video, "Video", &VIDEO_FILTER),
+    (FileType::Sound, "Sound", &SOUND_FILTER),
+];
+
+/// Containers which hold video or just sound, content decides and is checked by probing.
+const CONTAINER_MIMES: [&str; 4] = [
+    "video/mp4",
+    "video/quicktime",
+    "video/webm",
+    "video/x-matroska",
+];
 
 pub struct Filters {}
 impl Filters {
-    pub fn determinate_type(file_data: &FileData) -> Option<FileType> {
-        let extension = Path::new(&file_data.name)
+    fn extension(name: &str) -> Result<String, ImportError> {
+        Path::new(name)
             .extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase());
+            .map(|ext| ext.to_lowercase())
+            .ok_or(ImportError::NoExtension)
+    }
+
+    /// Whether the name has one of the supported extensions, content is checked on import.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_supported(name: &str) -> bool {
+        Self::extension(name).is_ok_and(|extension| {
+            FILTERS
+                .iter()
+                .any(|(_, _, extensions)| extensions.contains(&extension.as_str()))
+        })
+    }
+
+    pub fn determinate_type(file_data: &FileData) -> Result<FileType, ImportError> {
+        let extension = Self::extension(&file_data.name)?;
 
         // No mime for svgs
-        if extension.as_deref() == Some("svg") {
-            return Some(FileType::Image);
+        if extension == "svg" {
+            return Ok(FileType::Image);
         }
 
         let mime_type = file_data
             .mime
             .clone()
             .filter(|m| !m.trim().is_empty())
-            .or_else(|| infer::get(&file_data.bytes).map(|kind| kind.mime_type().to_string()));
+            .or_else(|| infer::get(&file_data.content).map(|kind| kind.mime_type().to_string()));
 
-        Self::check_file_type(extension.as_deref(), mime_type.as_deref())
+        Self::check_file_type(&extension, mime_type.as_deref())
     }
 
-    fn check_file_type(extension: Option<&str>, mime_type: Option<&str>) -> Option<FileType> {
-        match (extension, mime_type) {
-            (Some(ext), Some(mime)) => {
-                if IMAGE_FILTER.contains(&ext) && mime.starts_with("image/") {
-                    Some(FileType::Image)
-                } else if VIDEO_FILTER.contains(&ext) && mime.starts_with("video/") {
-                    Some(FileType::Video)
-                } else if SOUND_FILTER.contains(&ext) && mime.starts_with("audio/") {
-                    Some(FileType::Sound)
-                } else {
-                    None
-                }
+    /// Reads media properties, catching damaged files and content of another kind.
+    pub fn probe(file_data: &FileData, file_type: FileType) -> Result<MediaInfo, ImportError> {
+        match probe::probe(&file_data.content) {
+            Some(info) if info.file_type().is_some_and(|kind| kind != file_type) => {
+                Err(ImportError::ContentMismatch {
+                    extension: Self::extension(&file_data.name)?,
+                    mime: info.format,
+                })
+            }
+            Some(info) => Ok(info),
+            // Image loaders support more formats than probing, e.g. svg
+            None if file_type == FileType::Image => Ok(MediaInfo::default()),
+            None => Err(ImportError::Damaged),
+        }
+    }
+
+    fn check_file_type(extension: &str, mime_type: Option<&str>) -> Result<FileType, ImportError> {
+        let Some((file_type, _, _)) = FILTERS
+            .into_iter()
+            .find(|(_, _, extensions)| extensions.contains(&extension))
+        else {
+            return Err(ImportError::UnsupportedExtension(extension.to_owned()));
+        };
+        let mime_prefix = match file_type {
+            FileType::Image => "image/",
+            FileType::Video => "video/",
+            FileType::Sound => "audio/",
+        };
+
+        match mime_type {
+            Some(mime) if mime.starts_with(mime_prefix) => Ok(file_type),
+            Some(mime) if file_type == FileType::Sound && CONTAINER_MIMES.contains(&mime) => {
+                Ok(file_type)
             }
-            _ => None,
+            Some(mime) => Err(ImportError::ContentMismatch {
+                extension: extension.to_owned(),
+                mime: mime.to_owned(),
+            }),
+            None => Err(ImportError::UnknownContent(extension.to_owned())),
         }
     }
 }
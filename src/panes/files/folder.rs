@@ -0,0 +1,207 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use walkdir::{DirEntry, WalkDir};
+
+use super::{
+    filters::Filters,
+    import::{next_import_id, ImportMessage},
+    Files,
+};
+use crate::{
+    history::ImportFiles,
+    notifications::plural,
+    project::{AssetId, Bin},
+    state::State,
+    task,
+};
+
+/// Supported files found in a folder, with what was skipped.
+pub struct FolderScan {
+    name: String,
+    /// Bin the folder is imported into.
+    bin: Option<AssetId>,
+    /// Supported files with names of the folders between the root and the file.
+    files: Vec<(Vec<String>, PathBuf)>,
+    /// Number of skipped files by their lowercase extension, empty for files without one.
+    skipped: BTreeMap<String, usize>,
+    /// Files and folders which couldn't be listed.
+    unreadable: usize,
+}
+
+impl Files {
+    /// Walks a folder picked in a dialog, then imports it.
+    pub fn import_folder_dialog(&mut self, ui: &mut egui::Ui) {
+        let sender = self.channel.0.clone();
+        let ctx = ui.ctx().clone();
+        let bin = self.current_bin;
+        task::spawn(async move {
+            let Some(folder) = rfd::AsyncFileDialog::new().pick_folder().await else {
+                return;
+            };
+            let root = folder.path().to_path_buf();
+            let scan = async_std::task::spawn_blocking(move || scan(&root, bin)).await;
+            let _ = sender.send(ImportMessage::Folder(scan));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Walks a dropped folder in the background, then imports it.
+    pub(super) fn scan_folder(&self, ctx: &egui::Context, root: PathBuf) {
+        let sender = self.channel.0.clone();
+        let ctx = ctx.clone();
+        let bin = self.current_bin;
+        task::spawn_blocking(move || {
+            let _ = sender.send(ImportMessage::Folder(scan(&root, bin)));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Creates bins for the folders with supported files and starts reading the files.
+    pub(super) fn import_folder(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        scan: FolderScan,
+    ) {
+        let skipped = skipped_summary(&scan);
+        if scan.files.is_empty() {
+            state
+                .notifications
+                .warning(format!("No supported files in {}{skipped}", scan.name));
+            return;
+        }
+
+        // One bin for each folder on the way to a file, the root folder included
+        let mut bins: HashMap<Vec<String>, AssetId> = HashMap::new();
+        let mut new_bins = Vec::new();
+        let mut files = Vec::with_capacity(scan.files.len());
+        for (folders, path) in scan.files {
+            let mut parent = scan.bin;
+            for depth in 0..=folders.len() {
+                let key = folders[..depth].to_vec();
+                let name = match depth {
+                    0 => scan.name.clone(),
+                    _ => folders[depth - 1].clone(),
+                };
+                let id = *bins.entry(key).or_insert_with(|| {
+                    let id = AssetId::new();
+                    new_bins.push(Bin { id, name, parent });
+                    id
+                });
+                parent = Some(id);
+            }
+
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            files.push((name, path, parent));
+        }
+
+        state.notifications.info(format!(
+            "Importing {} {} from {}{skipped}",
+            files.len(),
+            plural(files.len(), "file", "files"),
+            scan.name
+        ));
+        // Bins and files are undone together
+        let import = next_import_id();
+        state.execute(ImportFiles::bins(
+            import,
+            format!("Import {}", scan.name),
+            new_bins,
+        ));
+        self.import_files(ctx, state, files, import);
+    }
+}
+
+/// Lists supported files in the folder and all folders inside it, hidden ones are ignored.
+fn scan(root: &Path, bin: Option<AssetId>) -> FolderScan {
+    let name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| root.display().to_string());
+    let mut scan = FolderScan {
+        name,
+        bin,
+        files: Vec::new(),
+        skipped: BTreeMap::new(),
+        unreadable: 0,
+    };
+
+    let entries = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry));
+    for entry in entries {
+        let Ok(entry) = entry else {
+            scan.unreadable += 1;
+            continue;
+        };
+        // Links aren't followed, but the ones pointing to files are fine
+        if !entry.path().is_file() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy();
+        if Filters::is_supported(&name) {
+            let folders = entry
+                .path()
+                .parent()
+                .and_then(|parent| parent.strip_prefix(root).ok())
+                .map(|parent| {
+                    parent
+                        .components()
+                        .map(|folder| folder.as_os_str().to_string_lossy().into_owned())
+                        .collect()
+                })
+                .unwrap_or_default();
+            scan.files.push((folders, entry.into_path()));
+        } else {
+            let extension = Path::new(name.as_ref())
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            *scan.skipped.entry(extension).or_default() += 1;
+        }
+    }
+    scan
+}
+
+/// Dot files, like `.DS_Store`, which nobody means to import.
+fn is_hidden(entry: &DirEntry) -> bool {
+    entry.file_name().to_string_lossy().starts_with('.')
+}
+
+/// E.g. `, skipped 5 files: 3 .xml, 2 .thm`, empty if nothing was skipped.
+fn skipped_summary(scan: &FolderScan) -> String {
+    let total: usize = scan.skipped.values().sum();
+    let mut summary = String::new();
+    if total > 0 {
+        let mut counts: Vec<_> = scan.skipped.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1));
+        let counts: Vec<_> = counts
+            .into_iter()
+            .map(|(extension, count)| match extension.as_str() {
+                "" => format!("{count} without extension"),
+                extension => format!("{count} .{extension}"),
+            })
+            .collect();
+        summary += &format!(
+            ", skipped {total} unsupported {}: {}",
+            plural(total, "file", "files"),
+            counts.join(", ")
+        );
+    }
+    if scan.unreadable > 0 {
+        summary += &format!(
+            ", {} {} couldn't be read",
+            scan.unreadable,
+            plural(scan.unreadable, "item", "items")
+        );
+    }
+    summary
+}
@@ -0,0 +1,170 @@
+use egui_taffy::{
+    taffy::{
+        self,
+        prelude::{fr, length, percent},
+        Overflow,
+    },
+    tui, TuiBuilderLogic,
+};
+
+use super::{
+    bins::{Action, DraggedBin, DropTarget, Item},
+    view::SortColumn,
+    DraggedFile, Files,
+};
+use crate::{
+    project::{Bin, File, Project},
+    thumbnails::Thumbnails,
+};
+
+const GAP: f32 = 8.;
+
+impl Files {
+    /// Cells with thumbnails and names, as many columns as fit the thumbnail size.
+    pub(super) fn grid_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        project: &Project,
+        thumbnails: &mut Thumbnails,
+        (bins, files): (&[&Bin], &[&File]),
+        action: &mut Option<Action>,
+    ) {
+        let size = self.view.thumbnail_size;
+        let columns = ((ui.available_width() - 2. * GAP + GAP) / (size + GAP))
+            .floor()
+            .max(1.) as usize;
+        let thumbnail_height = (size * 0.625).round();
+        let reorder = self.view.sort == SortColumn::Manual;
+
+        // TODO: Refactor this in future
+        tui(ui, "files-grid")
+            .reserve_available_space()
+            // Do padding on parent because it overflows
+            .style(taffy::Style {
+                padding: length(GAP),
+                size: percent(1.),
+
+                ..Default::default()
+            })
+            .show(|tui| {
+                tui.style(taffy::Style {
+                    display: taffy::Display::Grid,
+
+                    grid_template_columns: vec![fr(1.); columns],
+                    grid_auto_rows: vec![length(thumbnail_height + 40.)],
+                    padding: length(1.), // For border
+
+                    gap: length(GAP),
+                    size: percent(1.),
+
+                    overflow: taffy::Point {
+                        x: Overflow::Scroll,
+                        y: Overflow::Scroll,
+                    },
+
+                    ..Default::default()
+                })
+                .add(|tui| {
+                    let cell_style = || taffy::Style {
+                        display: taffy::Display::Flex,
+                        flex_direction: taffy::FlexDirection::Column,
+
+                        justify_content: Some(taffy::JustifyContent::Center),
+                        align_items: Some(taffy::AlignItems::Center),
+                        padding: length(4.),
+                        gap: length(GAP),
+
+                        ..Default::default()
+                    };
+
+                    for bin in bins {
+                        tui.style(cell_style()).add_with_border(|tui| {
+                            let x = tui.egui_ui().available_width();
+                            if x <= 0. {
+                                return;
+                            }
+
+                            tui.ui(|ui| {
+                                let item = Item::Bin(bin.id);
+                                let mut cell = |ui: &mut egui::Ui, files: &mut Self| {
+                                    ui.add_sized(
+                                        egui::vec2(x - 16., thumbnail_height),
+                                        egui::Label::new(
+                                            egui::RichText::new("🗀").size(thumbnail_height * 0.64),
+                                        )
+                                        .selectable(false),
+                                    );
+                                    files.name_ui(ui, item, &bin.name, action);
+                                };
+                                // Text field needs clicks for itself
+                                if self.is_renaming(item) {
+                                    cell(ui, self);
+                                    return;
+                                }
+                                let response = ui
+                                    .dnd_drag_source(
+                                        egui::Id::new(("bin", bin.id)),
+                                        DraggedBin { id: bin.id },
+                                        |ui| cell(ui, self),
+                                    )
+                                    .response;
+
+                                let response = response.interact(egui::Sense::click());
+                                Self::item_interaction(
+                                    &response,
+                                    project,
+                                    item,
+                                    DropTarget::Bin,
+                                    reorder,
+                                    action,
+                                );
+                            });
+                        });
+                    }
+
+                    for file in files {
+                        tui.style(cell_style()).add_with_border(|tui| {
+                            let x = tui.egui_ui().available_width();
+                            if x <= 0. {
+                                return;
+                            }
+
+                            tui.ui(|ui| {
+                                let item = Item::File(file.id);
+                                let mut cell = |ui: &mut egui::Ui, files: &mut Self| {
+                                    Self::thumbnail_ui(
+                                        ui,
+                                        thumbnails,
+                                        file,
+                                        egui::vec2(x - 16., thumbnail_height),
+                                    );
+                                    files.name_ui(ui, item, &file.name, action);
+                                };
+                                if self.is_renaming(item) {
+                                    cell(ui, self);
+                                    return;
+                                }
+                                let response = ui
+                                    .dnd_drag_source(
+                                        egui::Id::new(("file", file.id)),
+                                        DraggedFile { id: file.id },
+                                        |ui| cell(ui, self),
+                                    )
+                                    .response;
+
+                                Self::item_interaction(
+                                    &response,
+                                    project,
+                                    item,
+                                    DropTarget::File,
+                                    reorder,
+                                    action,
+                                );
+                                self.highlight_ui(ui, &response, file.id);
+                            });
+                        });
+                    }
+                })
+            });
+    }
+}
@@ -1,18 +1,69 @@
-use super::{filters::Filters, File, FileData, Files};
+use super::{filters::Filters, import_error::ImportFailure, FileData, Files, ImportResult};
+use crate::{
+    project::{AssetId, File, FileType},
+    state::State,
+};
 
-impl Files {
-    pub fn handle_file(&mut self, file_data: FileData) {
-        let file_type = Filters::determinate_type(&file_data);
+impl FileData {
+    /// Checks the type, probes properties and fingerprints the content.
+    ///
+    /// Probing scans whole MP3 and ADTS files and hashing reads samples of the content, so it
+    /// runs in the import job.
+    pub(super) fn into_file(self) -> ImportResult {
+        let probed = Filters::determinate_type(&self)
+            .and_then(|file_type| Filters::probe(&self, file_type).map(|info| (file_type, info)));
+        let (file_type, info) = probed.map_err(|error| ImportFailure {
+            name: self.name.clone(),
+            error,
+        })?;
+
+        // Sequence plays as a video, its first frame was probed as an image
+        let (file_type, info, size, hash) = match &self.image_sequence {
+            Some(image_sequence) => (
+                FileType::Video,
+                image_sequence.info(info),
+                image_sequence.size(),
+                image_sequence.fingerprint(),
+            ),
+            None => (
+                file_type,
+                info,
+                self.content.len() as u64,
+                self.content.fingerprint(),
+            ),
+        };
+        Ok(Box::new(File {
+            id: AssetId::new(),
+            name: self.name,
+            path: self.path,
+            relative_path: None,
+            size,
+            hash: Some(hash),
+            content: self.content,
+            r#type: file_type,
+            video_thumbnail: None,
+            info,
+            bin: self.bin,
+            image_sequence: self.image_sequence,
+            imported: web_time::SystemTime::now()
+                .duration_since(web_time::UNIX_EPOCH)
+                .ok()
+                .map(|time| time.as_secs()),
+        }))
+    }
+}
 
-        if let Some(file_type) = file_type {
-            self.files.push(File {
-                name: file_data.name,
-                bytes: file_data.bytes,
-                r#type: file_type,
-                video_thumbnail: None,
-            });
-        } else {
-            // TODO: Notify user about wrong file type
+impl Files {
+    pub fn handle_file(&mut self, state: &mut State, result: ImportResult, import: u64) {
+        match result {
+            Ok(file) => self.add_file(state, *file, import),
+            Err(failure) => Self::import_failed(state, failure),
         }
     }
+
+    pub fn import_failed(state: &mut State, failure: ImportFailure) {
+        state
+            .notifications
+            .warning(format!("Can't import {}: {}", failure.name, failure.error));
+    }
 }
@@ -0,0 +1,303 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{atomic::AtomicBool, mpsc::Sender, Arc},
+};
+
+use super::{
+    import::{
+        next_import_id, probe_file, read_file, read_files, ImportItem, ImportMessage, Source,
+    },
+    import_error::{ImportError, ImportFailure},
+    FileData, Files,
+};
+use crate::{
+    project::{AssetId, ImageSequence},
+    sequence::FrameRate,
+    state::State,
+    task,
+};
+
+/// Least number of numbered images offered as a sequence.
+const MIN_FRAMES: usize = 3;
+/// Extensions of images which can be frames, animated and vector formats can't.
+const FRAME_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "bmp", "tif", "tiff"];
+
+/// Numbered images found among imported files, waiting for the user to decide.
+pub struct SequenceOffer {
+    /// E.g. `shot_[0001-2000].png`.
+    name: String,
+    bin: Option<AssetId>,
+    /// Id of the import the images were picked in.
+    import: u64,
+    /// Names and sources of the frames in order.
+    frames: Vec<(String, Source)>,
+    frame_rate: FrameRate,
+}
+
+impl Files {
+    /// Reads the files in the background, numbered images are offered as sequences first.
+    pub(super) fn import_files(
+        &mut self,
+        ctx: &egui::Context,
+        state: &State,
+        files: Vec<ImportItem>,
+        import: u64,
+    ) {
+        let (offers, files) = find_sequences(files, state.project.sequence.frame_rate, import);
+        self.sequence_offers.extend(offers);
+        if !files.is_empty() {
+            task::spawn(read_files(
+                files,
+                import,
+                self.channel.0.clone(),
+                ctx.clone(),
+            ));
+        }
+    }
+
+    /// Asks whether the next found sequence is imported as a video or as separate images.
+    pub(super) fn sequence_offer_ui(&mut self, ctx: &egui::Context) {
+        // One dialog at a time
+        if !self.duplicates.is_empty() {
+            return;
+        }
+        let Some(offer) = self.sequence_offers.front_mut() else {
+            return;
+        };
+
+        let mut as_video = None;
+        egui::Modal::new(egui::Id::new("image_sequence")).show(ctx, |ui| {
+            ui.heading("Image sequence");
+            ui.label(format!(
+                "Found {} numbered images {}.",
+                offer.frames.len(),
+                offer.name
+            ));
+            ui.horizontal(|ui| {
+                ui.label("Frame rate");
+                egui::ComboBox::from_id_salt("image_sequence_frame_rate")
+                    .selected_text(offer.frame_rate.name())
+                    .show_ui(ui, |ui| {
+                        for frame_rate in FrameRate::COMMON {
+                            ui.selectable_value(
+                                &mut offer.frame_rate,
+                                frame_rate,
+                                frame_rate.name(),
+                            );
+                        }
+                    });
+                ui.weak(format!(
+                    "{:.1} s",
+                    offer
+                        .frame_rate
+                        .frames_to_seconds(offer.frames.len() as i64)
+                ));
+            });
+            ui.add_space(8.);
+            ui.horizontal(|ui| {
+                if ui.button("Import as video").clicked() {
+                    as_video = Some(true);
+                }
+                if ui.button("Import as images").clicked() {
+                    as_video = Some(false);
+                }
+            });
+        });
+
+        let Some(as_video) = as_video else {
+            return;
+        };
+        let Some(offer) = self.sequence_offers.pop_front() else {
+            return;
+        };
+        let sender = self.channel.0.clone();
+        if as_video {
+            task::spawn(read_sequence(offer, sender, ctx.clone()));
+        } else {
+            let files = offer
+                .frames
+                .into_iter()
+                .map(|(name, source)| (name, source, offer.bin))
+                .collect();
+            task::spawn(read_files(files, offer.import, sender, ctx.clone()));
+        }
+    }
+}
+
+/// Splits numbered images of the same folder and bin into sequences.
+fn find_sequences(
+    files: Vec<ImportItem>,
+    frame_rate: FrameRate,
+    import: u64,
+) -> (Vec<SequenceOffer>, Vec<ImportItem>) {
+    // Frames by everything around the number, with the number for sorting
+    type Key = (Option<AssetId>, Option<std::path::PathBuf>, String, String);
+    let mut groups: HashMap<Key, Vec<(u64, String, ImportItem)>> = HashMap::new();
+    let mut order = Vec::new();
+    let mut rest = Vec::new();
+    for file in files {
+        let Some((prefix, digits, suffix)) = split_number(&file.0) else {
+            rest.push(file);
+            continue;
+        };
+        let Ok(number) = digits.parse() else {
+            rest.push(file);
+            continue;
+        };
+        let key = (
+            file.2,
+            folder(&file.1),
+            prefix.to_owned(),
+            suffix.to_lowercase(),
+        );
+        let digits = digits.to_owned();
+        let group = groups.entry(key.clone()).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        group.push((number, digits, file));
+    }
+
+    let mut offers = Vec::new();
+    for key in order {
+        let Some(mut frames) = groups.remove(&key) else {
+            continue;
+        };
+        if frames.len() < MIN_FRAMES {
+            rest.extend(frames.into_iter().map(|(_, _, file)| file));
+            continue;
+        }
+
+        frames.sort_by_key(|(number, _, _)| *number);
+        let (bin, ..) = key;
+        let (_, first, (first_name, ..)) = &frames[0];
+        let (_, last, _) = &frames[frames.len() - 1];
+        let name = match split_number(first_name) {
+            Some((prefix, _, suffix)) => format!("{prefix}[{first}-{last}]{suffix}"),
+            None => first_name.clone(),
+        };
+        offers.push(SequenceOffer {
+            name,
+            bin,
+            import,
+            frames: frames
+                .into_iter()
+                .map(|(_, _, (name, source, _))| (name, source))
+                .collect(),
+            frame_rate,
+        });
+    }
+    (offers, rest)
+}
+
+/// Name around the last number, e.g. `shot_`, `0001` and `.png` for `shot_0001.png`.
+///
+/// `None` for names without a number and for other files than images.
+fn split_number(name: &str) -> Option<(&str, &str, &str)> {
+    let extension = Path::new(name).extension()?.to_str()?.to_lowercase();
+    if !FRAME_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let stem = &name[..name.len() - extension.len() - 1];
+    let end = stem.rfind(|c: char| c.is_ascii_digit())? + 1;
+    let start = stem[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |index| index + 1);
+    Some((&name[..start], &name[start..end], &name[end..]))
+}
+
+/// Folder of the file, frames of a sequence are next to each other.
+#[cfg(not(target_arch = "wasm32"))]
+fn folder(source: &Source) -> Option<std::path::PathBuf> {
+    source.parent().map(Path::to_path_buf)
+}
+
+/// Browsers don't tell where files are from.
+#[cfg(target_arch = "wasm32")]
+fn folder(_source: &Source) -> Option<std::path::PathBuf> {
+    None
+}
+
+/// Reads all frames as one import, the first frame is probed like a single image.
+async fn read_sequence(offer: SequenceOffer, sender: Sender<ImportMessage>, ctx: egui::Context) {
+    let id = next_import_id();
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let size = Some(
+        offer
+            .frames
+            .iter()
+            .filter_map(|(_, path)| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum(),
+    );
+    #[cfg(target_arch = "wasm32")]
+    let size = None;
+    let _ = sender.send(ImportMessage::Started {
+        id,
+        name: offer.name.clone(),
+        size,
+        cancel: cancel.clone(),
+    });
+    ctx.request_repaint();
+
+    let mut content = Vec::with_capacity(offer.frames.len());
+    for (index, (name, source)) in offer.frames.iter().enumerate() {
+        let message = match read_file(source, &cancel).await {
+            Ok(Some(frame)) if frame.is_empty() => ImportMessage::Finished {
+                id,
+                import: offer.import,
+                result: Err(ImportFailure {
+                    name: name.clone(),
+                    error: ImportError::Empty,
+                }),
+            },
+            Ok(Some(frame)) => {
+                content.push(frame);
+                let _ = sender.send(ImportMessage::Progress {
+                    id,
+                    progress: (index + 1) as f32 / offer.frames.len() as f32,
+                });
+                ctx.request_repaint();
+                continue;
+            }
+            Ok(None) => ImportMessage::Canceled { id },
+            Err(error) => ImportMessage::Finished {
+                id,
+                import: offer.import,
+                result: Err(ImportFailure {
+                    name: name.clone(),
+                    error,
+                }),
+            },
+        };
+        let _ = sender.send(message);
+        ctx.request_repaint();
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let path = offer.frames.first().map(|(_, path)| path.clone());
+    #[cfg(target_arch = "wasm32")]
+    let path = None;
+    let names = offer.frames.into_iter().map(|(name, _)| name).collect();
+    let file_data = FileData {
+        name: offer.name,
+        content: content.first().cloned().unwrap_or_default(),
+        mime: None,
+        path,
+        bin: offer.bin,
+        image_sequence: Some(ImageSequence {
+            frames: names,
+            frame_rate: offer.frame_rate,
+            content,
+        }),
+    };
+    let import = offer.import;
+    let result = probe_file(file_data).await;
+    let _ = sender.send(ImportMessage::Finished { id, import, result });
+    ctx.request_repaint();
+}
@@ -0,0 +1,480 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    mpsc::Sender,
+    Arc,
+};
+
+use super::{
+    filters::FILTERS,
+    import_error::{ImportError, ImportFailure},
+    FileData, Files, ImportResult,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use super::{folder::FolderScan, relink::Relinked};
+use crate::{
+    media::thumbnail::video_frame,
+    project::{AssetId, Content, FileType},
+    state::State,
+    task,
+};
+
+/// Source of a file read in the background.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) type Source = std::path::PathBuf;
+#[cfg(target_arch = "wasm32")]
+pub(super) type Source = rfd::FileHandle;
+/// Name and source of a file to import, with the bin it goes to.
+pub(super) type ImportItem = (String, Source, Option<AssetId>);
+
+static NEXT_IMPORT_ID: AtomicU64 = AtomicU64::new(0);
+/// Size of the parts mapped files are read in, progress is reported after each.
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// Reading one byte of each page loads the whole page.
+#[cfg(not(target_arch = "wasm32"))]
+const PAGE_SIZE: usize = 4096;
+
+/// Message sent from import tasks to the Files pane.
+pub enum ImportMessage {
+    /// Files picked in the dialog, looked through for sequences before reading.
+    Picked(Vec<ImportItem>),
+    Started {
+        id: u64,
+        name: String,
+        /// Unknown on the web until the file is read.
+        size: Option<u64>,
+        cancel: Arc<AtomicBool>,
+    },
+    /// Part of the import that was read, from 0 to 1.
+    Progress {
+        id: u64,
+        progress: f32,
+    },
+    Finished {
+        id: u64,
+        /// Files read together are added in one undo step, see `ImportFiles`.
+        import: u64,
+        result: ImportResult,
+    },
+    Canceled {
+        id: u64,
+    },
+    /// Folder walked before its files are read.
+    #[cfg(not(target_arch = "wasm32"))]
+    Folder(FolderScan),
+    /// New locations of offline files.
+    #[cfg(not(target_arch = "wasm32"))]
+    Relinked(Relinked),
+    /// Frame extracted from an imported video.
+    VideoThumbnail {
+        id: AssetId,
+        thumbnail: Option<Vec<u8>>,
+    },
+}
+
+/// File being imported.
+pub struct ImportJob {
+    id: u64,
+    name: String,
+    size: Option<u64>,
+    /// Unknown until reading starts, and for files the browser reads at once.
+    progress: Option<f32>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Files {
+    pub const IMPORT_FILE_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::O);
+
+    pub fn import_file_dialog(&mut self, ui: &mut egui::Ui) {
+        let sender = self.channel.0.clone();
+        let ctx = ui.ctx().clone();
+        let bin = self.current_bin;
+        task::spawn(async move {
+            let all: Vec<_> = FILTERS
+                .iter()
+                .flat_map(|(_, _, extensions)| extensions.iter())
+                .collect();
+            let dialog = FILTERS.iter().fold(
+                rfd::AsyncFileDialog::new().add_filter("All", &all),
+                |dialog, (_, name, extensions)| dialog.add_filter(*name, extensions),
+            );
+            let Some(files) = dialog.pick_files().await else {
+                return;
+            };
+
+            let files = files
+                .into_iter()
+                .map(|file| {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    return (file.file_name(), file.path().to_path_buf(), bin);
+                    #[cfg(target_arch = "wasm32")]
+                    return (file.file_name(), file, bin);
+                })
+                .collect();
+            let _ = sender.send(ImportMessage::Picked(files));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Cancels all running imports, files already read are still added.
+    pub fn cancel_imports(&self) {
+        for job in &self.jobs {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn import_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        while let Ok(message) = self.channel.1.try_recv() {
+            match message {
+                ImportMessage::Picked(files) => {
+                    self.import_files(ui.ctx(), state, files, next_import_id())
+                }
+                ImportMessage::Started {
+                    id,
+                    name,
+                    size,
+                    cancel,
+                } => self.jobs.push(ImportJob {
+                    id,
+                    name,
+                    size,
+                    progress: None,
+                    cancel,
+                }),
+                ImportMessage::Progress { id, progress } => {
+                    if let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) {
+                        job.progress = Some(progress);
+                    }
+                }
+                ImportMessage::Finished { id, import, result } => {
+                    self.jobs.retain(|job| job.id != id);
+                    self.handle_file(state, result, import);
+                }
+                ImportMessage::Canceled { id } => self.jobs.retain(|job| job.id != id),
+                #[cfg(not(target_arch = "wasm32"))]
+                ImportMessage::Folder(scan) => self.import_folder(ui.ctx(), state, scan),
+                #[cfg(not(target_arch = "wasm32"))]
+                ImportMessage::Relinked(relinked) => Self::relinked(state, relinked),
+                ImportMessage::VideoThumbnail { id, thumbnail } => {
+                    if let Some(file) = state.project.files.iter_mut().find(|file| file.id == id) {
+                        file.video_thumbnail = thumbnail;
+                    }
+                }
+            }
+        }
+
+        let dropped_files = ui.ctx().input(|i| i.raw.dropped_files.clone());
+        if !dropped_files.is_empty() {
+            self.import_dropped(ui.ctx(), state, dropped_files);
+        }
+
+        self.extract_video_thumbnails(ui.ctx(), state);
+
+        if ui.input_mut(|i| i.consume_shortcut(&Self::IMPORT_FILE_SHORTCUT)) {
+            self.import_file_dialog(ui);
+        }
+    }
+
+    /// Imports files dropped on the window together, folders on native are walked first.
+    fn import_dropped(
+        &mut self,
+        ctx: &egui::Context,
+        state: &mut State,
+        dropped_files: Vec<egui::DroppedFile>,
+    ) {
+        let import = next_import_id();
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut paths = Vec::new();
+        for file in dropped_files {
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(path) = file.path {
+                if path.is_dir() {
+                    self.scan_folder(ctx, path);
+                } else if let Some(name) = path.file_name() {
+                    // Big files would freeze the frame loop, read them in the background
+                    paths.push((name.to_string_lossy().into_owned(), path, self.current_bin));
+                }
+                continue;
+            }
+
+            if file.name.is_empty() {
+                continue;
+            }
+            let content = file.bytes.map(Content::from).unwrap_or_default();
+            if content.is_empty() {
+                Self::import_failed(
+                    state,
+                    ImportFailure {
+                        name: file.name,
+                        error: ImportError::Empty,
+                    },
+                );
+                continue;
+            }
+
+            let file_data = FileData {
+                name: file.name,
+                content,
+                mime: Some(file.mime),
+                path: None,
+                bin: self.current_bin,
+                image_sequence: None,
+            };
+            let sender = self.channel.0.clone();
+            let ctx = ctx.clone();
+            task::spawn_blocking(move || {
+                let result = file_data.into_file();
+                let _ = sender.send(ImportMessage::Finished {
+                    id: next_import_id(),
+                    import,
+                    result,
+                });
+                ctx.request_repaint();
+            });
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if !paths.is_empty() {
+            self.import_files(ctx, state, paths, import);
+        }
+    }
+
+    /// Extracts frames of videos imported or loaded with the project in the background.
+    fn extract_video_thumbnails(&mut self, ctx: &egui::Context, state: &State) {
+        for file in &state.project.files {
+            // Frames of image sequences are thumbnails already
+            if file.r#type != FileType::Video
+                || file.image_sequence.is_some()
+                || file.video_thumbnail.is_some()
+                || file.content.is_empty()
+                || !self.video_thumbnails.insert(file.id)
+            {
+                continue;
+            }
+
+            let id = file.id;
+            let name = file.name.clone();
+            let content = file.content.clone();
+            let sender = self.channel.0.clone();
+            let ctx = ctx.clone();
+            task::spawn_blocking(move || {
+                let thumbnail = video_frame(&content);
+                if thumbnail.is_none() {
+                    log::info!("No decodable frame for thumbnail of {name}");
+                }
+                let _ = sender.send(ImportMessage::VideoThumbnail { id, thumbnail });
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Progress of running imports.
+    pub(super) fn import_progress_ui(&self, ui: &mut egui::Ui) {
+        if self.jobs.is_empty() {
+            return;
+        }
+
+        egui::Frame::none()
+            .inner_margin(egui::Margin::symmetric(8., 4.))
+            .show(ui, |ui| {
+                for job in &self.jobs {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("🗙").on_hover_text("Cancel").clicked() {
+                            job.cancel.store(true, Ordering::Relaxed);
+                        }
+
+                        let text = match job.size {
+                            Some(size) => format!("{}: {}", job.name, format_size(size)),
+                            None => job.name.clone(),
+                        };
+                        match job.progress {
+                            Some(progress) => {
+                                ui.add(egui::ProgressBar::new(progress).text(text));
+                            }
+                            // Browsers read files at once without telling how far they got
+                            None => {
+                                ui.spinner();
+                                ui.label(text);
+                            }
+                        }
+                    });
+                }
+            });
+        ui.separator();
+    }
+}
+
+/// Id of a new import job.
+pub(super) fn next_import_id() -> u64 {
+    NEXT_IMPORT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Reads files one by one, checking cancellation between them.
+pub(super) async fn read_files(
+    files: Vec<ImportItem>,
+    import: u64,
+    sender: Sender<ImportMessage>,
+    ctx: egui::Context,
+) {
+    // Announce all files first, so they can be canceled before being read
+    let files: Vec<_> = files
+        .into_iter()
+        .map(|(name, source, bin)| {
+            let id = next_import_id();
+            let cancel = Arc::new(AtomicBool::new(false));
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let size = std::fs::metadata(&source)
+                .ok()
+                .map(|metadata| metadata.len());
+            #[cfg(target_arch = "wasm32")]
+            let size = None;
+
+            let _ = sender.send(ImportMessage::Started {
+                id,
+                name: name.clone(),
+                size,
+                cancel: cancel.clone(),
+            });
+            (id, name, source, bin, cancel)
+        })
+        .collect();
+    ctx.request_repaint();
+
+    for (id, name, source, bin, cancel) in files {
+        let message = match read_file(&source, &cancel).await {
+            Ok(Some(content)) if content.is_empty() => ImportMessage::Finished {
+                id,
+                import,
+                result: Err(ImportFailure {
+                    name,
+                    error: ImportError::Empty,
+                }),
+            },
+            // Mapping is instant, reading the file through shows how far the import got
+            #[cfg(not(target_arch = "wasm32"))]
+            Ok(Some(content)) if !preload(id, content.clone(), &cancel, &sender, &ctx).await => {
+                ImportMessage::Canceled { id }
+            }
+            Ok(Some(content)) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                let path = Some(source);
+                #[cfg(target_arch = "wasm32")]
+                let path = None;
+
+                let file_data = FileData {
+                    name,
+                    content,
+                    mime: None,
+                    path,
+                    bin,
+                    image_sequence: None,
+                };
+                ImportMessage::Finished {
+                    id,
+                    import,
+                    result: probe_file(file_data).await,
+                }
+            }
+            Ok(None) => ImportMessage::Canceled { id },
+            Err(error) => ImportMessage::Finished {
+                id,
+                import,
+                result: Err(ImportFailure { name, error }),
+            },
+        };
+
+        // Send file data by channel because of wasm compilation: E0521
+        let _ = sender.send(message);
+        ctx.request_repaint();
+    }
+}
+
+/// Reads mapped content through once, reporting progress of the import.
+///
+/// Probing and playback then find the file in the system cache, which can drop it again.
+/// Returns `false` when canceled.
+#[cfg(not(target_arch = "wasm32"))]
+async fn preload(
+    id: u64,
+    content: Content,
+    cancel: &Arc<AtomicBool>,
+    sender: &Sender<ImportMessage>,
+    ctx: &egui::Context,
+) -> bool {
+    let (cancel, sender, ctx) = (cancel.clone(), sender.clone(), ctx.clone());
+    async_std::task::spawn_blocking(move || {
+        let chunks = content.len().div_ceil(CHUNK_SIZE);
+        for (index, chunk) in content.chunks(CHUNK_SIZE).enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            std::hint::black_box(
+                chunk
+                    .iter()
+                    .step_by(PAGE_SIZE)
+                    .fold(0u8, |sum, byte| sum.wrapping_add(*byte)),
+            );
+            let _ = sender.send(ImportMessage::Progress {
+                id,
+                progress: (index + 1) as f32 / chunks as f32,
+            });
+            ctx.request_repaint();
+        }
+        true
+    })
+    .await
+}
+
+/// Probes the read file without blocking the frame loop.
+pub(super) async fn probe_file(file_data: FileData) -> ImportResult {
+    #[cfg(not(target_arch = "wasm32"))]
+    return async_std::task::spawn_blocking(move || file_data.into_file()).await;
+    // No threads on the web
+    #[cfg(target_arch = "wasm32")]
+    file_data.into_file()
+}
+
+/// Maps the file instead of reading it, returns `None` when canceled.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) async fn read_file(
+    path: &Source,
+    cancel: &AtomicBool,
+) -> Result<Option<Content>, ImportError> {
+    if cancel.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    Content::map(path)
+        .map(Some)
+        .map_err(|err| ImportError::Read(err.to_string()))
+}
+
+/// Returns `None` when canceled.
+#[cfg(target_arch = "wasm32")]
+pub(super) async fn read_file(
+    file: &Source,
+    cancel: &AtomicBool,
+) -> Result<Option<Content>, ImportError> {
+    // Browser reads the whole file at once
+    if cancel.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+    let bytes = file.read().await;
+    Ok((!cancel.load(Ordering::Relaxed)).then(|| bytes.into()))
+}
+
+/// Human readable size, e.g. `12.3 MB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1000.;
+    let mut unit = 0;
+    while size >= 1000. && unit < UNITS.len() - 1 {
+        size /= 1000.;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
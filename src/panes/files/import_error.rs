@@ -0,0 +1,37 @@
+use std::fmt;
+
+/// Reason why a file wasn't imported.
+#[derive(Debug)]
+pub enum ImportError {
+    Read(String),
+    Empty,
+    NoExtension,
+    UnsupportedExtension(String),
+    UnknownContent(String),
+    ContentMismatch { extension: String, mime: String },
+    Damaged,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(err) => write!(f, "can't read file: {err}"),
+            Self::Empty => write!(f, "file is empty"),
+            Self::NoExtension => write!(f, "file has no extension"),
+            Self::UnsupportedExtension(ext) => write!(f, "extension .{ext} not supported"),
+            Self::UnknownContent(ext) => {
+                write!(f, "extension says {ext} but content is not recognized")
+            }
+            Self::ContentMismatch { extension, mime } => {
+                write!(f, "extension says {extension} but content is {mime}")
+            }
+            Self::Damaged => write!(f, "content is damaged or not supported"),
+        }
+    }
+}
+
+/// File that failed to import, sent instead of its data.
+pub struct ImportFailure {
+    pub name: String,
+    pub error: ImportError,
+}
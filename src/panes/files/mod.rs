@@ -1,233 +1,329 @@
+mod bins;
+mod details;
+mod duplicates;
 mod filters;
+#[cfg(not(target_arch = "wasm32"))]
+mod folder;
+mod grid;
 mod handle_file;
+mod image_sequence;
+mod import;
+mod import_error;
+#[cfg(not(target_arch = "wasm32"))]
+mod relink;
+mod view;
 
-use egui_taffy::{
-    taffy::{
-        self,
-        prelude::{fr, length, percent},
-        Overflow,
-    },
-    tui, TuiBuilderLogic,
-};
-use filters::{IMAGE_FILTER, SOUND_FILTER, VIDEO_FILTER};
+use bins::{Action, Item};
+use duplicates::{Duplicate, DuplicateChoice};
+use image_sequence::SequenceOffer;
+use import::{ImportJob, ImportMessage};
+use import_error::ImportFailure;
+use serde::{Deserialize, Serialize};
 use std::{
-    fs,
+    collections::{HashSet, VecDeque},
+    path::PathBuf,
     sync::mpsc::{channel, Receiver, Sender},
 };
 
 use super::PaneBehavior;
-
-#[derive(Debug)]
-pub enum FileType {
-    Image,
-    Video,
-    Sound,
-}
+use crate::{
+    project::{AssetId, Content, File, FileType, ImageSequence},
+    state::State,
+    thumbnails::Thumbnails,
+};
+use view::{SortColumn, View, ViewMode};
 
 pub struct FileData {
     name: String,
-    bytes: Vec<u8>,
+    content: Content,
     mime: Option<String>,
+    /// Where the content comes from, `None` when it's only in memory.
+    path: Option<PathBuf>,
+    /// Bin the file goes to, `None` for the top level.
+    bin: Option<AssetId>,
+    /// Frames when numbered images are imported as one video.
+    image_sequence: Option<ImageSequence>,
 }
 
-struct File {
-    name: String,
-    bytes: Vec<u8>,
-    r#type: FileType,
-    video_thumbnail: Option<Vec<u8>>,
+/// Drag and drop payload of a file dragged out of the grid.
+pub struct DraggedFile {
+    pub id: AssetId,
 }
 
+/// Probed file ready to be added, or why it can't be imported.
+type ImportResult = Result<Box<File>, ImportFailure>;
+
+/// Only the view is saved, everything else is about the current session.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Files {
-    files: Vec<File>,
-    channel: (Sender<FileData>, Receiver<FileData>),
+    #[serde(skip)]
+    channel: (Sender<ImportMessage>, Receiver<ImportMessage>),
+    /// Files being read in the background.
+    #[serde(skip)]
+    jobs: Vec<ImportJob>,
+    /// Videos whose thumbnail was already extracted or attempted.
+    #[serde(skip)]
+    video_thumbnails: HashSet<AssetId>,
+    /// Bin whose content is shown, `None` for the top level.
+    #[serde(skip)]
+    current_bin: Option<AssetId>,
+    /// Item being renamed with its edited name.
+    #[serde(skip)]
+    renaming: Option<(Item, String)>,
+    /// Relink dialog is shown.
+    #[serde(skip)]
+    relink_open: bool,
+    /// Text files are filtered by, matched anywhere in their names.
+    #[serde(skip)]
+    search: String,
+    /// Numbered images found on import, waiting for the user.
+    #[serde(skip)]
+    sequence_offers: VecDeque<SequenceOffer>,
+    /// Imported files with content already in the project, waiting for the user.
+    #[serde(skip)]
+    duplicates: VecDeque<Duplicate>,
+    /// Choice made for all duplicates of the running import.
+    #[serde(skip)]
+    duplicate_choice: Option<DuplicateChoice>,
+    /// "Do the same" is checked in the duplicate dialog.
+    #[serde(skip)]
+    apply_to_all: bool,
+    /// Window with groups of duplicates is shown.
+    #[serde(skip)]
+    duplicates_open: bool,
+    /// File shown with "Show existing" or from the duplicates window.
+    #[serde(skip)]
+    highlighted: Option<AssetId>,
+    #[serde(skip)]
+    scroll_to_highlighted: bool,
+    view: View,
 }
-impl Files {
-    pub fn default() -> Self {
+impl Default for Files {
+    fn default() -> Self {
         Self {
-            files: Vec::new(),
             channel: channel(),
+            jobs: Vec::new(),
+            video_thumbnails: HashSet::new(),
+            current_bin: None,
+            renaming: None,
+            relink_open: false,
+            search: String::new(),
+            sequence_offers: VecDeque::new(),
+            duplicates: VecDeque::new(),
+            duplicate_choice: None,
+            apply_to_all: false,
+            duplicates_open: false,
+            highlighted: None,
+            scroll_to_highlighted: false,
+            view: View::default(),
         }
     }
-
-    pub const IMPORT_FILE_SHORTCUT: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::CTRL, egui::Key::O);
-
-    pub fn import_file_dialog(&mut self, ui: &mut egui::Ui) {
-        let sender = self.channel.0.clone();
-        let ctx = ui.ctx().clone();
-        async_std::task::block_on(async move {
-            if let Some(files) = rfd::AsyncFileDialog::new()
-                .add_filter(
-                    "All",
-                    &[&IMAGE_FILTER[..], &VIDEO_FILTER[..], &SOUND_FILTER[..]].concat(),
-                )
-                .add_filter("Image", &IMAGE_FILTER)
-                .add_filter("Video", &VIDEO_FILTER)
-                .add_filter("Sound", &SOUND_FILTER)
-                .pick_files()
-                .await
-            {
-                for file in files {
-                    let name = file.file_name();
-                    let bytes = file.read().await;
-                    if bytes.is_empty() {
-                        continue;
-                    }
-
-                    // Send file data by channel because of wasm compilation: E0521
-                    let _ = sender.send(FileData {
-                        name,
-                        bytes,
-                        mime: None,
-                    });
-                    ctx.request_repaint();
+}
+impl Files {
+    /// Name of the item, or text field while it's being renamed.
+    fn name_ui(&mut self, ui: &mut egui::Ui, item: Item, name: &str, action: &mut Option<Action>) {
+        let width = ui.available_width();
+        match &mut self.renaming {
+            Some((renamed, new_name)) if *renamed == item => {
+                let response = ui.add_sized([width, 10.], egui::TextEdit::singleline(new_name));
+                if ui.memory(|memory| memory.focused().is_none()) {
+                    response.request_focus();
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    self.renaming = None;
+                } else if response.lost_focus() {
+                    *action = Some(Action::Rename(item, new_name.clone()));
                 }
             }
-        });
+            _ => {
+                ui.add_sized(
+                    [width, 10.],
+                    egui::Label::new(name).truncate().selectable(false),
+                );
+            }
+        }
     }
 
-    fn import_ui(&mut self, ui: &mut egui::Ui) {
-        if let Ok(file_data) = self.channel.1.try_recv() {
-            self.handle_file(file_data);
-        }
-
-        ui.ctx().input(|i| {
-            if !i.raw.dropped_files.is_empty() {
-                let dropped_files = &i.raw.dropped_files;
-                for file in dropped_files {
-                    let name = if let Some(path) = &file.path {
-                        let name = path
-                            .file_name()
-                            .map(|name| name.to_string_lossy().to_string());
-                        if name.is_none() {
-                            continue;
-                        }
-                        name.unwrap()
-                    } else if !file.name.is_empty() {
-                        file.name.clone()
-                    } else {
-                        continue;
-                    };
-
-                    let bytes = if let Some(path) = &file.path {
-                        fs::read(path).unwrap_or_else(|_| Vec::new())
-                    } else {
-                        file.bytes.clone().unwrap_or_else(|| [].into()).to_vec()
-                    };
-                    if bytes.is_empty() {
-                        continue;
-                    }
-
-                    self.handle_file(FileData {
-                        name,
-                        bytes,
-                        mime: Some(file.mime.clone()),
-                    });
-                }
+    fn is_renaming(&self, item: Item) -> bool {
+        self.renaming
+            .as_ref()
+            .is_some_and(|(renamed, _)| *renamed == item)
+    }
+
+    /// Tinted tile with a message in place of a thumbnail.
+    fn message_tile_ui(ui: &mut egui::Ui, size: egui::Vec2, text: &str, color: egui::Color32) {
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        ui.painter()
+            .rect_filled(rect, 2., color.gamma_multiply(0.25));
+        ui.painter().text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(13.),
+            color,
+        );
+    }
+
+    fn thumbnail_ui(ui: &mut egui::Ui, thumbnails: &mut Thumbnails, file: &File, size: egui::Vec2) {
+        if file.is_offline() {
+            Self::message_tile_ui(ui, size, "Media offline", ui.visuals().error_fg_color);
+            return;
+        }
+
+        if file.r#type == FileType::Sound {
+            if let Some(waveform) = thumbnails.waveform(ui.ctx(), file) {
+                let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+                let painter = ui.painter_at(rect);
+                painter.rect_filled(rect, 2., ui.visuals().extreme_bg_color);
+                waveform.paint(
+                    &painter,
+                    rect.shrink2(egui::vec2(4., 8.)),
+                    0.0..waveform.duration(),
+                    ui.visuals().text_color(),
+                );
+                return;
             }
-        });
+        } else if let Some(texture) = thumbnails.get(ui.ctx(), file) {
+            ui.add_sized(
+                size,
+                egui::Image::from_texture(texture).maintain_aspect_ratio(true),
+            );
+            return;
+        }
 
-        if ui.input_mut(|i| i.consume_shortcut(&Self::IMPORT_FILE_SHORTCUT)) {
-            self.import_file_dialog(ui);
+        if thumbnails.is_loading(file) {
+            ui.add_sized(size, egui::Spinner::new());
+            return;
+        }
+        let placeholder = match file.r#type {
+            // Thumbnail is the image itself, so it couldn't be decoded
+            FileType::Image => {
+                Self::message_tile_ui(ui, size, "🖼 Can't decode", ui.visuals().warn_fg_color);
+                return;
+            }
+            FileType::Video => egui::Image::new(egui::include_image!("../../assets/video.png")),
+            FileType::Sound => egui::Image::new(egui::include_image!("../../assets/sound.png")),
+        };
+        let response = ui.add_sized(size, placeholder.maintain_aspect_ratio(true));
+        if let Some(codec) = file
+            .info
+            .video_codec
+            .as_ref()
+            .filter(|_| file.r#type == FileType::Video)
+        {
+            response.on_hover_text(format!(
+                "No preview of {codec}, only Motion JPEG, PNG and uncompressed frames or cover art are shown"
+            ));
         }
     }
 }
 
 impl PaneBehavior for Files {
-    fn ui(&mut self, ui: &mut egui::Ui) {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
         // Import UI logic
-        self.import_ui(ui);
-
-        // Show imported files
-        // TODO: Refactor this in future
-        tui(ui, "files-grid")
-            .reserve_available_space()
-            // Do padding on parent because it overflows
-            .style(taffy::Style {
-                padding: length(8.),
-                size: percent(1.),
-
-                ..Default::default()
-            })
-            .show(|tui| {
-                tui.style(taffy::Style {
-                    display: taffy::Display::Grid,
-
-                    grid_template_columns: vec![fr(1.), fr(1.), fr(1.)],
-                    grid_auto_rows: vec![length(140.)],
-                    padding: length(1.), // For border
-
-                    gap: length(8.),
-                    size: percent(1.),
-
-                    overflow: taffy::Point {
-                        x: Overflow::Scroll,
-                        y: Overflow::Scroll,
-                    },
-
-                    ..Default::default()
-                })
-                .add(|tui| {
-                    for file in &self.files {
-                        tui.style(taffy::Style {
-                            display: taffy::Display::Flex,
-                            flex_direction: taffy::FlexDirection::Column,
-
-                            justify_content: Some(taffy::JustifyContent::Center),
-                            align_items: Some(taffy::AlignItems::Center),
-                            padding: length(4.),
-                            gap: length(8.),
-
-                            ..Default::default()
-                        })
-                        .add_with_border(|tui| {
-                            let x = tui.egui_ui().available_width();
-                            if x <= 0. {
-                                return;
-                            }
-
-                            tui.ui(|ui| {
-                                // Thumbnail
-                                ui.add_sized(
-                                    [(x - 16.), 100.],
-                                    match file.r#type {
-                                        FileType::Image => egui::Image::from_bytes(
-                                            format!("bytes://{}", file.name),
-                                            file.bytes.clone(),
-                                        ),
-                                        FileType::Video => {
-                                            if let Some(video_thumbnail) = &file.video_thumbnail {
-                                                egui::Image::from_bytes(
-                                                    format!("bytes://{}", file.name),
-                                                    video_thumbnail.clone(),
-                                                )
-                                            } else {
-                                                egui::Image::new(egui::include_image!(
-                                                    "../../assets/video.png"
-                                                ))
-                                            }
-                                        }
-                                        FileType::Sound => egui::Image::new(egui::include_image!(
-                                            "../../assets/sound.png"
-                                        )),
-                                    }
-                                    .maintain_aspect_ratio(true),
-                                );
-
-                                ui.add_sized(
-                                    [(x - 16.), 10.],
-                                    egui::Label::new(&file.name).truncate(),
-                                );
-                            });
-                        });
-                    }
-                })
-            })
+        self.import_ui(ui, state);
+        self.import_progress_ui(ui);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.relink_ui(ui.ctx(), state);
+        self.duplicate_ui(ui.ctx(), state);
+        self.sequence_offer_ui(ui.ctx());
+
+        // Bin may be gone after undo
+        if self
+            .current_bin
+            .is_some_and(|bin| state.project.bin(bin).is_none())
+        {
+            self.current_bin = None;
+        }
+
+        let mut action = None;
+        self.breadcrumb_ui(ui, &state.project, &mut action);
+
+        let State {
+            project,
+            thumbnails,
+            ..
+        } = state;
+        let (bins, files) = self.visible_items(project);
+        if !self.search.trim().is_empty() && files.is_empty() {
+            ui.weak("No files match the search");
+        }
+        match self.view.mode {
+            ViewMode::Grid => self.grid_ui(ui, project, thumbnails, (&bins, &files), &mut action),
+            ViewMode::Details => self.details_ui(ui, project, (&bins, &files), &mut action),
+        }
+        self.duplicates_window_ui(ui.ctx(), project, &mut action);
+
+        if let Some(action) = action {
+            self.apply_action(state, action);
+        }
     }
 
-    fn top_bar_ui(&mut self, ui: &mut egui::Ui) {
+    fn top_bar_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
         if ui.button("Import file").clicked() {
             self.import_file_dialog(ui);
         };
+        if ui.button("New bin").clicked() {
+            self.new_bin(state);
+        }
+        if !self.jobs.is_empty() && ui.button("Cancel import").clicked() {
+            self.cancel_imports();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let offline = state.project.offline_files().count();
+            let text = egui::RichText::new(format!("⚠ {offline} offline"))
+                .color(ui.visuals().error_fg_color);
+            if offline > 0
+                && ui
+                    .button(text)
+                    .on_hover_text("Relink moved or renamed media")
+                    .clicked()
+            {
+                self.relink_open = true;
+            }
+        }
+
+        ui.separator();
+        // Right to left, so the order is reversed
+        ui.selectable_value(&mut self.view.mode, ViewMode::Details, "Details")
+            .on_hover_text("Show a table with details");
+        ui.selectable_value(&mut self.view.mode, ViewMode::Grid, "Grid")
+            .on_hover_text("Show thumbnails");
+        if self.view.mode == ViewMode::Grid {
+            ui.add(
+                egui::Slider::new(&mut self.view.thumbnail_size, 80.0..=320.0).show_value(false),
+            )
+            .on_hover_text("Thumbnail size");
+        }
+
+        egui::ComboBox::from_id_salt("files-sort")
+            .selected_text(format!("Sort: {}", self.view.sort.name()))
+            .show_ui(ui, |ui| {
+                for column in SortColumn::ALL {
+                    ui.selectable_value(&mut self.view.sort, column, column.name());
+                }
+            });
+        if self.view.sort != SortColumn::Manual {
+            let (arrow, hint) = if self.view.descending {
+                ("⏷", "Descending")
+            } else {
+                ("⏶", "Ascending")
+            };
+            if ui.button(arrow).on_hover_text(hint).clicked() {
+                self.view.descending = !self.view.descending;
+            }
+        }
+
+        if !self.search.is_empty() && ui.small_button("✖").on_hover_text("Clear search").clicked()
+        {
+            self.search.clear();
+        }
+        ui.add(
+            egui::TextEdit::singleline(&mut self.search)
+                .hint_text("Search")
+                .desired_width(120.),
+        );
     }
 }
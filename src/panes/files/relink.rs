@@ -0,0 +1,311 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use super::{import::ImportMessage, Files};
+use crate::{
+    history::RelinkFiles,
+    project::{AssetId, Content, File},
+    state::State,
+    task,
+};
+
+/// How deep the new location is searched for files which moved elsewhere in it.
+const SEARCH_DEPTH: usize = 8;
+/// Maximal number of files looked at, in case the new location is a whole drive.
+const SEARCH_LIMIT: usize = 100_000;
+
+/// Offline file as the project knows it, found files are compared with it.
+#[derive(Clone)]
+struct Missing {
+    id: AssetId,
+    name: String,
+    path: PathBuf,
+    size: u64,
+    hash: Option<u64>,
+}
+impl Missing {
+    fn new(file: &File) -> Option<Self> {
+        // Path of a sequence is its first frame, size and hash are of all frames
+        let (size, hash) = match file.image_sequence {
+            Some(_) => (0, None),
+            None => (file.size, file.hash),
+        };
+        Some(Self {
+            id: file.id,
+            name: file.name.clone(),
+            path: file.path.clone()?,
+            size,
+            hash,
+        })
+    }
+
+    /// Whether the file at the path has the same size and content.
+    ///
+    /// Projects saved before sizes and hashes were stored only compare what is known.
+    fn matches(&self, path: &Path) -> bool {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        if !metadata.is_file() || (self.size > 0 && metadata.len() != self.size) {
+            return false;
+        }
+        match self.hash {
+            Some(hash) => Content::map(path).is_ok_and(|content| content.fingerprint() == hash),
+            None => true,
+        }
+    }
+}
+
+/// Location picked for one file and locations found for the others.
+pub struct Relinked {
+    picked: (AssetId, PathBuf),
+    /// Picked file has other content than the original.
+    mismatch: bool,
+    found: Vec<(AssetId, PathBuf)>,
+}
+
+impl Files {
+    /// Lists offline files, locating one of them searches for the others next to it.
+    pub(super) fn relink_ui(&mut self, ctx: &egui::Context, state: &State) {
+        if !self.relink_open {
+            return;
+        }
+
+        let mut open = true;
+        let mut locate = None;
+        egui::Window::new("Relink media")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.)
+            .show(ctx, |ui| {
+                let offline: Vec<_> = state.project.offline_files().collect();
+                if offline.is_empty() {
+                    ui.label("All media is online.");
+                    return;
+                }
+
+                ui.label(
+                    "These files were moved or renamed. Locate one of them and the others are \
+                     looked for in the same place.",
+                );
+                ui.add_space(4.);
+                egui::ScrollArea::vertical()
+                    .max_height(320.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("relink-files")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for file in &offline {
+                                    ui.vertical(|ui| {
+                                        ui.colored_label(ui.visuals().error_fg_color, &file.name);
+                                        if let Some(path) = &file.path {
+                                            ui.add(
+                                                egui::Label::new(
+                                                    egui::RichText::new(path.display().to_string())
+                                                        .weak()
+                                                        .small(),
+                                                )
+                                                .truncate(),
+                                            );
+                                        }
+                                    });
+                                    if ui.button("Locate…").clicked() {
+                                        locate = Some(file.id);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        if !open {
+            self.relink_open = false;
+        }
+
+        if let Some(id) = locate {
+            self.locate(ctx, state, id);
+        }
+    }
+
+    /// Asks for the new location of the file, then looks for the other offline files.
+    fn locate(&self, ctx: &egui::Context, state: &State, id: AssetId) {
+        let offline: Vec<_> = state
+            .project
+            .offline_files()
+            .filter_map(Missing::new)
+            .collect();
+        let Some(picked) = offline.iter().find(|missing| missing.id == id).cloned() else {
+            return;
+        };
+        let others: Vec<_> = offline.into_iter().filter(|other| other.id != id).collect();
+
+        // Start where the file used to be, as far as it still exists
+        let mut dialog = rfd::AsyncFileDialog::new().set_title(format!("Locate {}", picked.name));
+        if let Some(folder) = picked
+            .path
+            .ancestors()
+            .skip(1)
+            .find(|folder| folder.is_dir())
+        {
+            dialog = dialog.set_directory(folder);
+        }
+
+        let sender = self.channel.0.clone();
+        let ctx = ctx.clone();
+        task::spawn(async move {
+            let Some(file) = dialog.pick_file().await else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            let relinked = async_std::task::spawn_blocking(move || Relinked {
+                mismatch: !picked.matches(&path),
+                found: search(&picked, &path, &others),
+                picked: (picked.id, path),
+            })
+            .await;
+            let _ = sender.send(ImportMessage::Relinked(relinked));
+            ctx.request_repaint();
+        });
+    }
+
+    /// Points the files to their new locations as one undoable step.
+    pub(super) fn relinked(state: &mut State, relinked: Relinked) {
+        let Relinked {
+            picked: (id, path),
+            mismatch,
+            found,
+        } = relinked;
+        let Some(file) = state.project.file(id) else {
+            return;
+        };
+        if mismatch {
+            state.notifications.warning(format!(
+                "{} has other content than {}, relinked anyway",
+                path.display(),
+                file.name
+            ));
+        }
+
+        let paths: Vec<_> = std::iter::once((id, path))
+            .chain(found)
+            .filter_map(|(id, path)| {
+                let file = state.project.file(id)?;
+                Some((id, file.path.clone(), path))
+            })
+            .collect();
+        let count = paths.len();
+        let name = match count {
+            1 => format!("Relink {}", file.name),
+            _ => format!("Relink {count} files"),
+        };
+        state.execute(RelinkFiles::new(name, paths));
+
+        let left = state.project.offline_files().count();
+        let message = match (count, left) {
+            (1, 0) => "Relinked 1 file".to_owned(),
+            (_, 0) => format!("Relinked {count} files"),
+            _ => format!("Relinked {count}, {left} still offline"),
+        };
+        state.notifications.info(message);
+    }
+}
+
+/// Locations of the other files, found where the picked file moved to.
+///
+/// Files are tried at the same path relative to the moved folder first, then by name and
+/// by content anywhere in that folder, which also finds renamed files.
+fn search(picked: &Missing, path: &Path, others: &[Missing]) -> Vec<(AssetId, PathBuf)> {
+    let (old_root, new_root) = moved_folders(&picked.path, path);
+    let mut used = HashSet::from([path.to_path_buf()]);
+    let mut found = Vec::new();
+    let mut left = Vec::new();
+    for missing in others {
+        let moved = missing
+            .path
+            .strip_prefix(old_root)
+            .ok()
+            .map(|relative| new_root.join(relative))
+            .filter(|moved| !used.contains(moved) && missing.matches(moved));
+        match moved {
+            Some(moved) => {
+                used.insert(moved.clone());
+                found.push((missing.id, moved));
+            }
+            None => left.push(missing),
+        }
+    }
+    if left.is_empty() {
+        return found;
+    }
+
+    let mut by_name: HashMap<OsString, Vec<PathBuf>> = HashMap::new();
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let entries = WalkDir::new(new_root)
+        .max_depth(SEARCH_DEPTH)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .take(SEARCH_LIMIT);
+    for entry in entries {
+        if let Ok(metadata) = entry.metadata() {
+            by_size
+                .entry(metadata.len())
+                .or_default()
+                .push(entry.path().to_path_buf());
+        }
+        by_name
+            .entry(entry.file_name().to_owned())
+            .or_default()
+            .push(entry.into_path());
+    }
+
+    for missing in left {
+        let named = missing
+            .path
+            .file_name()
+            .and_then(|name| by_name.get(name))
+            .into_iter()
+            .flatten();
+        // Without a hash any file of the same size would do, so renamed files aren't guessed
+        let same_content = missing
+            .hash
+            .and_then(|_| by_size.get(&missing.size))
+            .into_iter()
+            .flatten();
+        let candidate = named
+            .chain(same_content)
+            .find(|candidate| !used.contains(*candidate) && missing.matches(candidate))
+            .cloned();
+        if let Some(candidate) = candidate {
+            used.insert(candidate.clone());
+            found.push((missing.id, candidate));
+        }
+    }
+    found
+}
+
+/// Folders which moved, found by stripping folder names both paths end with.
+///
+/// E.g. `/old/card/clip.mp4` found at `/new/footage/card/clip.mp4` gives `/old` and
+/// `/new/footage`.
+fn moved_folders<'a>(old: &'a Path, new: &'a Path) -> (&'a Path, &'a Path) {
+    // File itself may have been renamed, so start at the folders
+    let mut old = old.parent().unwrap_or(old);
+    let mut new = new.parent().unwrap_or(new);
+    while let (Some(old_name), Some(new_name)) = (old.file_name(), new.file_name()) {
+        let (Some(old_parent), Some(new_parent)) = (old.parent(), new.parent()) else {
+            break;
+        };
+        if old_name != new_name {
+            break;
+        }
+        old = old_parent;
+        new = new_parent;
+    }
+    (old, new)
+}
@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::Files;
+use crate::project::{Bin, File, Project};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViewMode {
+    #[default]
+    Grid,
+    Details,
+}
+
+/// What files are sorted by, bins are only sorted by name.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortColumn {
+    /// Order the user arranged files in.
+    #[default]
+    Manual,
+    Name,
+    Type,
+    Duration,
+    Resolution,
+    Size,
+    Imported,
+}
+impl SortColumn {
+    pub const ALL: [Self; 7] = [
+        Self::Manual,
+        Self::Name,
+        Self::Type,
+        Self::Duration,
+        Self::Resolution,
+        Self::Size,
+        Self::Imported,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Manual => "Manual",
+            Self::Name => "Name",
+            Self::Type => "Type",
+            Self::Duration => "Duration",
+            Self::Resolution => "Resolution",
+            Self::Size => "Size",
+            Self::Imported => "Imported",
+        }
+    }
+
+    fn compare(self, a: &File, b: &File) -> Ordering {
+        let pixels = |file: &File| file.info.resolution.map(|[w, h]| w as u64 * h as u64);
+        match self {
+            Self::Manual => Ordering::Equal,
+            Self::Name => compare_names(&a.name, &b.name),
+            Self::Type => (a.r#type.name(), &a.info.format).cmp(&(b.r#type.name(), &b.info.format)),
+            Self::Duration => a
+                .info
+                .duration
+                .partial_cmp(&b.info.duration)
+                .unwrap_or(Ordering::Equal),
+            Self::Resolution => pixels(a).cmp(&pixels(b)),
+            Self::Size => a.size.cmp(&b.size),
+            Self::Imported => a.imported.cmp(&b.imported),
+        }
+    }
+}
+
+/// How files are shown, kept between sessions.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    pub mode: ViewMode,
+    /// Width of grid cells in points.
+    pub thumbnail_size: f32,
+    pub sort: SortColumn,
+    pub descending: bool,
+}
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            mode: ViewMode::Grid,
+            thumbnail_size: 160.,
+            sort: SortColumn::Manual,
+            descending: false,
+        }
+    }
+}
+impl View {
+    /// Sorts by the column, or toggles the direction if already sorted by it.
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort = column;
+            self.descending = false;
+        }
+    }
+
+    fn sorted<T>(&self, items: &mut [T], compare: impl Fn(&T, &T) -> Ordering) {
+        // Stable sort keeps the manual order between equal items
+        items.sort_by(|a, b| {
+            let ordering = compare(a, b);
+            if self.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
+impl Files {
+    /// Bins and files to show, sorted by the view.
+    ///
+    /// Searching shows matching files of the open bin and all bins inside it, without bins.
+    pub(super) fn visible_items<'a>(&self, project: &'a Project) -> (Vec<&'a Bin>, Vec<&'a File>) {
+        let query = self.search.trim().to_lowercase();
+        let in_view = |file: &File| {
+            let bin = project.file_bin(file);
+            if query.is_empty() {
+                bin == self.current_bin
+            } else {
+                self.current_bin
+                    .is_none_or(|current| project.bin_contains(current, bin))
+                    && file.name.to_lowercase().contains(&query)
+            }
+        };
+
+        let mut bins: Vec<_> = project
+            .bins
+            .iter()
+            .filter(|bin| query.is_empty() && bin.parent == self.current_bin)
+            .collect();
+        let mut files: Vec<_> = project.files.iter().filter(|file| in_view(file)).collect();
+
+        if self.view.sort != SortColumn::Manual {
+            self.view
+                .sorted(&mut files, |a, b| self.view.sort.compare(a, b));
+        }
+        if self.view.sort == SortColumn::Name {
+            self.view
+                .sorted(&mut bins, |a, b| compare_names(&a.name, &b.name));
+        }
+        (bins, files)
+    }
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
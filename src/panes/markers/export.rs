@@ -0,0 +1,220 @@
+use super::Markers;
+use crate::{
+    sequence::{MarkerLocation, Sequence},
+    task,
+};
+
+/// File formats markers can be saved as.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// FFmpeg metadata, which can add chapters to a rendered video.
+    Chapters,
+}
+impl ExportFormat {
+    pub const ALL: [Self; 2] = [Self::Csv, Self::Chapters];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV…",
+            Self::Chapters => "Chapters…",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Csv => "Table of all markers for spreadsheets",
+            Self::Chapters => "Markers of the sequence as FFmpeg chapter metadata",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Chapters => "txt",
+        }
+    }
+}
+
+/// Result of saving an export, sent back from the background.
+pub enum ExportMessage {
+    Saved(String),
+    Failed(String),
+}
+
+impl Markers {
+    /// Asks where to save the markers and writes them in the background.
+    pub(super) fn export(&self, ctx: &egui::Context, sequence: &Sequence, format: ExportFormat) {
+        let text = match format {
+            ExportFormat::Csv => markers_csv(sequence),
+            ExportFormat::Chapters => chapters_metadata(sequence),
+        };
+        let file_name = format!("{} markers.{}", sequence.name, format.extension());
+        let dialog = rfd::AsyncFileDialog::new()
+            .add_filter(format.description(), &[format.extension()])
+            .set_file_name(file_name);
+        let sender = self.channel.0.clone();
+        let ctx = ctx.clone();
+
+        task::spawn(async move {
+            let Some(file) = dialog.save_file().await else {
+                return;
+            };
+            let message = match file.write(text.as_bytes()).await {
+                Ok(()) => ExportMessage::Saved(file.file_name()),
+                Err(err) => ExportMessage::Failed(format!("Failed to export markers: {err}")),
+            };
+            let _ = sender.send(message);
+            ctx.request_repaint();
+        });
+    }
+}
+
+/// Quotes a CSV field with separators, quotes or line breaks.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// All markers with SMPTE timecodes, clip markers name their track.
+fn markers_csv(sequence: &Sequence) -> String {
+    let timecode = |frames| sequence.frame_rate.timecode(frames);
+    let mut csv = String::from("Name,Start,End,Duration,Color,Track,Notes\r\n");
+    for (location, frame, marker) in sequence.all_markers() {
+        let track = match location {
+            MarkerLocation::Sequence(_) => "",
+            MarkerLocation::Clip { track, .. } => &sequence.tracks[track].name,
+        };
+        let row = [
+            csv_field(&marker.name),
+            timecode(frame),
+            timecode(frame + marker.duration),
+            timecode(marker.duration),
+            marker.color.name().to_owned(),
+            csv_field(track),
+            csv_field(&marker.notes),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Escapes characters with a meaning in FFmpeg metadata.
+fn metadata_value(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Markers of the sequence as chapters, for `ffmpeg -i video -i chapters.txt -map_metadata 1`.
+///
+/// Chapters last until the next marker or the end of the sequence, regions until their end.
+fn chapters_metadata(sequence: &Sequence) -> String {
+    let milliseconds = |frames| (sequence.frame_rate.frames_to_seconds(frames) * 1000.).round();
+    let mut metadata = String::from(";FFMETADATA1\n");
+    for (index, marker) in sequence.markers.iter().enumerate() {
+        let end = if marker.is_region() {
+            marker.end()
+        } else {
+            sequence
+                .markers
+                .get(index + 1)
+                .map_or(sequence.duration(), |next| next.frame)
+                .max(marker.frame + 1)
+        };
+        metadata.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            milliseconds(marker.frame),
+            milliseconds(end),
+            metadata_value(&marker.name)
+        ));
+    }
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        project::AssetId,
+        sequence::{Clip, Marker, MarkerColor},
+    };
+
+    /// Markers at 0, 1 s and 10 s at 30 fps, and a clip marker at 130 frames.
+    fn sequence() -> Sequence {
+        let mut sequence = Sequence::default();
+        sequence.add_marker(Marker {
+            name: "Intro".to_owned(),
+            ..Default::default()
+        });
+        sequence.add_marker(Marker {
+            name: "Act, one".to_owned(),
+            color: MarkerColor::Red,
+            frame: 30,
+            duration: 60,
+            ..Default::default()
+        });
+        sequence.add_marker(Marker {
+            name: "Outro=end".to_owned(),
+            frame: 300,
+            ..Default::default()
+        });
+        sequence.tracks[0].clips.push(Clip {
+            file: AssetId::new(),
+            start: 100,
+            source_in: 20,
+            source_out: 320,
+            link: None,
+            markers: vec![Marker {
+                name: "Take 2".to_owned(),
+                notes: "said \"go\", then left".to_owned(),
+                frame: 50,
+                ..Default::default()
+            }],
+        });
+        sequence
+    }
+
+    #[test]
+    fn csv_of_all_markers() {
+        let csv = markers_csv(&sequence());
+        let rows: Vec<_> = csv.split_terminator("\r\n").collect();
+        assert_eq!(
+            rows,
+            [
+                "Name,Start,End,Duration,Color,Track,Notes",
+                "Intro,00:00:00:00,00:00:00:00,00:00:00:00,Blue,,",
+                "\"Act, one\",00:00:01:00,00:00:03:00,00:00:02:00,Red,,",
+                "Take 2,00:00:04:10,00:00:04:10,00:00:00:00,Blue,V1,\"said \"\"go\"\", then left\"",
+                "Outro=end,00:00:10:00,00:00:10:00,00:00:00:00,Blue,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn chapters_of_sequence_markers() {
+        let metadata = chapters_metadata(&sequence());
+        let chapters: Vec<_> = metadata.split("\n[CHAPTER]\n").collect();
+        assert_eq!(
+            chapters,
+            [
+                ";FFMETADATA1\n",
+                // Until the next marker
+                "TIMEBASE=1/1000\nSTART=0\nEND=1000\ntitle=Intro\n",
+                // Region until its end
+                "TIMEBASE=1/1000\nSTART=1000\nEND=3000\ntitle=Act, one\n",
+                // Last until the end of the sequence
+                "TIMEBASE=1/1000\nSTART=10000\nEND=13333\ntitle=Outro\\=end\n",
+            ]
+        );
+    }
+}
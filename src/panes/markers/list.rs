@@ -0,0 +1,210 @@
+use egui_extras::{Column, TableBuilder};
+
+use super::{export::ExportFormat, marker_color, Markers};
+use crate::{
+    history::EditSequence,
+    sequence::{Marker, MarkerColor, MarkerLocation},
+    state::State,
+};
+
+const ROW_HEIGHT: f32 = 22.;
+
+/// Change made in the list, applied after it's drawn.
+enum Action {
+    /// Merged into one undo step with following edits of the same field.
+    Edit(MarkerLocation, Marker, egui::Id),
+    Remove(MarkerLocation),
+    Seek(i64),
+}
+
+impl Markers {
+    /// Table of all markers, names and notes can be edited right in it.
+    pub(super) fn list_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let sequence = &state.project.sequence;
+        let markers = sequence.all_markers();
+        if markers.is_empty() {
+            ui.centered_and_justified(|ui| {
+                ui.weak(format!(
+                    "Press {} to add a marker at the playhead",
+                    ui.ctx().format_shortcut(&Self::ADD_MARKER_SHORTCUT)
+                ));
+            });
+            return;
+        }
+
+        let format = state.project.settings.timecode_format;
+        let playhead = state.playback.playhead();
+        let mut action = None;
+        TableBuilder::new(ui)
+            .id_salt("markers")
+            .striped(true)
+            .resizable(true)
+            .column(Column::exact(24.))
+            .column(Column::auto().at_least(80.))
+            .column(Column::auto().at_least(60.))
+            .column(Column::initial(140.).at_least(80.).clip(true))
+            .column(Column::remainder().at_least(120.).clip(true))
+            .column(Column::auto().at_least(40.))
+            .column(Column::exact(24.))
+            .header(ROW_HEIGHT, |mut header| {
+                for title in ["", "Start", "Duration", "Name", "Notes", "Track", ""] {
+                    header.col(|ui| {
+                        ui.strong(title);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(ROW_HEIGHT, markers.len(), |mut row| {
+                    let (location, frame, marker) = markers[row.index()];
+                    let mut edited = marker.clone();
+                    let mut edit_id = None;
+                    let end = frame + marker.duration.max(1);
+                    row.set_selected((frame..end).contains(&playhead));
+
+                    row.col(|ui| {
+                        let color = marker_color(marker.color);
+                        ui.menu_button(egui::RichText::new("⏺").color(color), |ui| {
+                            for color in MarkerColor::ALL {
+                                let text = egui::RichText::new(format!("⏺ {}", color.name()))
+                                    .color(marker_color(color));
+                                if ui.selectable_label(marker.color == color, text).clicked() {
+                                    edited.color = color;
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                    row.col(|ui| {
+                        let text = sequence.frame_rate.format(frame, format);
+                        if ui
+                            .link(egui::RichText::new(text).monospace())
+                            .on_hover_text("Move the playhead here")
+                            .clicked()
+                        {
+                            action = Some(Action::Seek(frame));
+                        }
+                    });
+                    row.col(|ui| {
+                        if marker.is_region() {
+                            ui.monospace(sequence.frame_rate.format(marker.duration, format));
+                        }
+                    });
+                    row.col(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut edited.name)
+                                .frame(false)
+                                .desired_width(f32::INFINITY),
+                        );
+                        if response.changed() {
+                            edit_id = Some(response.id);
+                        }
+                    });
+                    row.col(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut edited.notes)
+                                .frame(false)
+                                .hint_text("Notes")
+                                .desired_width(f32::INFINITY),
+                        );
+                        if response.changed() {
+                            edit_id = Some(response.id);
+                        }
+                    });
+                    row.col(|ui| {
+                        if let MarkerLocation::Clip { track, .. } = location {
+                            ui.label(&sequence.tracks[track].name)
+                                .on_hover_text("Marker of a clip, moves with it");
+                        }
+                    });
+                    row.col(|ui| {
+                        if ui
+                            .small_button("✖")
+                            .on_hover_text("Remove marker")
+                            .clicked()
+                        {
+                            action = Some(Action::Remove(location));
+                        }
+                    });
+
+                    if edited != *marker {
+                        let id = edit_id.unwrap_or_else(|| egui::Id::new(("marker", location)));
+                        action = Some(Action::Edit(location, edited, id));
+                    }
+                });
+            });
+
+        match action {
+            Some(Action::Edit(location, marker, merge_id)) => {
+                let before = state.project.sequence.clone();
+                let mut after = before.clone();
+                if let Some(edited) = after.marker_mut(location) {
+                    *edited = marker;
+                }
+                state.execute(EditSequence::new("Edit marker", before, after).merge_id(merge_id));
+            }
+            Some(Action::Remove(location)) => {
+                state.edit_sequence("Remove marker", |sequence| {
+                    sequence.remove_marker(location);
+                });
+            }
+            Some(Action::Seek(frame)) => state.playback.seek(ui.ctx(), frame),
+            None => {}
+        }
+    }
+
+    /// Buttons to add and jump between markers and the export menu, right to left.
+    pub(super) fn top_bar_buttons_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let hint = |ui: &egui::Ui, text: &str, shortcut| {
+            format!("{text} ({})", ui.ctx().format_shortcut(shortcut))
+        };
+
+        ui.menu_button("Export", |ui| {
+            for format in ExportFormat::ALL {
+                if ui.button(format.name()).clicked() {
+                    self.export(ui.ctx(), &state.project.sequence, format);
+                    ui.close_menu();
+                }
+            }
+        });
+        ui.separator();
+        let text = hint(ui, "Next marker", &Self::NEXT_MARKER_SHORTCUT);
+        if ui.button("⏵").on_hover_text(text).clicked() {
+            Self::go_to_next_marker(ui.ctx(), state);
+        }
+        let text = hint(ui, "Previous marker", &Self::PREVIOUS_MARKER_SHORTCUT);
+        if ui.button("⏴").on_hover_text(text).clicked() {
+            Self::go_to_previous_marker(ui.ctx(), state);
+        }
+        ui.separator();
+        let has_range = state.project.sequence.in_out_range().is_some();
+        let text = format!(
+            "Region from the in to the out point, set them with {} and {}",
+            ui.ctx().format_shortcut(&Self::SET_IN_SHORTCUT),
+            ui.ctx().format_shortcut(&Self::SET_OUT_SHORTCUT)
+        );
+        if ui
+            .add_enabled(has_range, egui::Button::new("Add region"))
+            .on_hover_text(&text)
+            .on_disabled_hover_text(&text)
+            .clicked()
+        {
+            Self::add_region(state);
+        }
+        let text = hint(
+            ui,
+            "Marker on the clip under the playhead",
+            &Self::ADD_CLIP_MARKER_SHORTCUT,
+        );
+        if ui.button("Add clip marker").on_hover_text(text).clicked() {
+            Self::add_clip_marker(state);
+        }
+        let text = hint(
+            ui,
+            "Marker of the sequence at the playhead",
+            &Self::ADD_MARKER_SHORTCUT,
+        );
+        if ui.button("Add marker").on_hover_text(text).clicked() {
+            Self::add_marker(state);
+        }
+    }
+}
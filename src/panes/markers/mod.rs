@@ -0,0 +1,213 @@
+mod export;
+mod list;
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::PaneBehavior;
+use crate::{
+    sequence::{Marker, MarkerColor},
+    state::State,
+};
+use export::ExportMessage;
+
+/// Color of markers drawn in the timeline and the list.
+pub fn marker_color(color: MarkerColor) -> egui::Color32 {
+    match color {
+        MarkerColor::Blue => egui::Color32::from_rgb(80, 140, 230),
+        MarkerColor::Green => egui::Color32::from_rgb(90, 190, 100),
+        MarkerColor::Yellow => egui::Color32::from_rgb(230, 200, 60),
+        MarkerColor::Orange => egui::Color32::from_rgb(235, 140, 50),
+        MarkerColor::Red => egui::Color32::from_rgb(225, 70, 70),
+        MarkerColor::Purple => egui::Color32::from_rgb(160, 100, 220),
+    }
+}
+
+/// List of markers and regions of the sequence and its clips, e.g. notes from a review.
+pub struct Markers {
+    channel: (Sender<ExportMessage>, Receiver<ExportMessage>),
+}
+impl Default for Markers {
+    fn default() -> Self {
+        Self { channel: channel() }
+    }
+}
+
+impl Markers {
+    pub const ADD_MARKER_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::NONE, Key::M);
+    pub const ADD_CLIP_MARKER_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::ALT, Key::M);
+    pub const NEXT_MARKER_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::SHIFT, Key::M);
+    pub const PREVIOUS_MARKER_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::M);
+    pub const SET_IN_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::I);
+    pub const SET_OUT_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::O);
+    pub const CLEAR_IN_OUT_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::ALT, Key::X);
+
+    /// Adds a marker to the sequence at the playhead.
+    pub fn add_marker(state: &mut State) {
+        let sequence = &state.project.sequence;
+        let marker = Marker {
+            name: sequence.next_marker_name(),
+            frame: state.playback.playhead(),
+            ..Default::default()
+        };
+        state.edit_sequence("Add marker", |sequence| {
+            sequence.add_marker(marker);
+        });
+    }
+
+    /// Adds a marker to the topmost clip of an unlocked track under the playhead.
+    pub fn add_clip_marker(state: &mut State) {
+        let sequence = &state.project.sequence;
+        let playhead = state.playback.playhead();
+        let clip = sequence
+            .tracks
+            .iter()
+            .enumerate()
+            .filter(|(_, track)| !track.locked)
+            .find_map(|(track_index, track)| {
+                let index = track
+                    .clips
+                    .iter()
+                    .position(|clip| clip.contains(playhead))?;
+                Some((track_index, index))
+            });
+        let Some((track, index)) = clip else {
+            state
+                .notifications
+                .info("No clip of an unlocked track is under the playhead");
+            return;
+        };
+
+        let clip = &sequence.tracks[track].clips[index];
+        let marker = Marker {
+            name: sequence.next_marker_name(),
+            frame: clip.source_in + playhead - clip.start,
+            ..Default::default()
+        };
+        state.edit_sequence("Add clip marker", |sequence| {
+            sequence.tracks[track].clips[index].markers.push(marker);
+        });
+    }
+
+    /// Adds a region of the sequence from the in point to the out point.
+    pub fn add_region(state: &mut State) {
+        let sequence = &state.project.sequence;
+        let Some((in_point, out_point)) = sequence.in_out_range() else {
+            state.notifications.info("Set the in and out points first");
+            return;
+        };
+        let marker = Marker {
+            name: sequence.next_marker_name(),
+            frame: in_point,
+            duration: out_point - in_point,
+            ..Default::default()
+        };
+        state.edit_sequence("Add region", |sequence| {
+            sequence.add_marker(marker);
+        });
+    }
+
+    pub fn go_to_next_marker(ctx: &egui::Context, state: &mut State) {
+        if let Some(frame) = state
+            .project
+            .sequence
+            .next_marker(state.playback.playhead())
+        {
+            state.playback.seek(ctx, frame);
+        }
+    }
+
+    pub fn go_to_previous_marker(ctx: &egui::Context, state: &mut State) {
+        if let Some(frame) = state
+            .project
+            .sequence
+            .previous_marker(state.playback.playhead())
+        {
+            state.playback.seek(ctx, frame);
+        }
+    }
+
+    pub fn set_in_point(state: &mut State) {
+        let playhead = state.playback.playhead();
+        state.edit_sequence("Set in point", |sequence| {
+            sequence.in_point = Some(playhead);
+        });
+    }
+
+    pub fn set_out_point(state: &mut State) {
+        let playhead = state.playback.playhead();
+        state.edit_sequence("Set out point", |sequence| {
+            sequence.out_point = Some(playhead);
+        });
+    }
+
+    pub fn clear_in_out(state: &mut State) {
+        let sequence = &state.project.sequence;
+        if sequence.in_point.is_none() && sequence.out_point.is_none() {
+            return;
+        }
+        state.edit_sequence("Clear in and out", |sequence| {
+            sequence.in_point = None;
+            sequence.out_point = None;
+        });
+    }
+
+    pub fn shortcuts(ctx: &egui::Context, state: &mut State) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        // Shortcuts with more modifiers first, the plain key matches them too
+        let pressed = ctx.input_mut(|i| {
+            [
+                Self::PREVIOUS_MARKER_SHORTCUT,
+                Self::NEXT_MARKER_SHORTCUT,
+                Self::ADD_CLIP_MARKER_SHORTCUT,
+                Self::ADD_MARKER_SHORTCUT,
+                Self::SET_IN_SHORTCUT,
+                Self::SET_OUT_SHORTCUT,
+                Self::CLEAR_IN_OUT_SHORTCUT,
+            ]
+            .into_iter()
+            .find(|shortcut| i.consume_shortcut(shortcut))
+        });
+
+        match pressed {
+            Some(Self::PREVIOUS_MARKER_SHORTCUT) => Self::go_to_previous_marker(ctx, state),
+            Some(Self::NEXT_MARKER_SHORTCUT) => Self::go_to_next_marker(ctx, state),
+            Some(Self::ADD_CLIP_MARKER_SHORTCUT) => Self::add_clip_marker(state),
+            Some(Self::ADD_MARKER_SHORTCUT) => Self::add_marker(state),
+            Some(Self::SET_IN_SHORTCUT) => Self::set_in_point(state),
+            Some(Self::SET_OUT_SHORTCUT) => Self::set_out_point(state),
+            Some(Self::CLEAR_IN_OUT_SHORTCUT) => Self::clear_in_out(state),
+            _ => {}
+        }
+    }
+}
+
+impl PaneBehavior for Markers {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        while let Ok(message) = self.channel.1.try_recv() {
+            match message {
+                ExportMessage::Saved(name) => {
+                    state
+                        .notifications
+                        .info(format!("Exported markers to {name}"));
+                }
+                ExportMessage::Failed(message) => state.notifications.error(message),
+            }
+        }
+
+        self.list_ui(ui, state);
+    }
+
+    fn top_bar_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        self.top_bar_buttons_ui(ui, state);
+    }
+}
@@ -1,11 +1,15 @@
-mod files;
+pub mod files;
 pub use files::Files;
+mod markers;
+pub use markers::Markers;
 mod timeline;
 pub use timeline::Timeline;
 mod video;
 pub use video::Video;
 
+use crate::state::State;
+
 pub trait PaneBehavior {
-    fn ui(&mut self, ui: &mut egui::Ui);
-    fn top_bar_ui(&mut self, _ui: &mut egui::Ui) {}
+    fn ui(&mut self, ui: &mut egui::Ui, state: &mut State);
+    fn top_bar_ui(&mut self, _ui: &mut egui::Ui, _state: &mut State) {}
 }
@@ -0,0 +1,501 @@
+use super::{tool::Tool, Timeline};
+use crate::{
+    history::EditSequence,
+    project::{FileType, Project},
+    sequence::{Clip, Edge, FrameRate, Sequence, TimecodeFormat},
+    state::State,
+};
+
+/// Width of the area at clip edges where edge tools grab the edge.
+const EDGE_WIDTH: f32 = 6.;
+
+/// What dragging a clip does, depends on the tool and where the clip was grabbed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DragMode {
+    Move,
+    Trim(Edge),
+    Ripple(Edge),
+    Roll(Edge),
+    Slip,
+    Slide,
+}
+impl DragMode {
+    /// Trimming ripples in the magnetic mode, so it doesn't open gaps.
+    fn new(tool: Tool, edge: Option<Edge>, magnetic: bool) -> Option<Self> {
+        match (tool, edge) {
+            (Tool::Select, None) => Some(Self::Move),
+            (Tool::Select, Some(edge)) if magnetic => Some(Self::Ripple(edge)),
+            (Tool::Select, Some(edge)) => Some(Self::Trim(edge)),
+            (Tool::Ripple, Some(edge)) => Some(Self::Ripple(edge)),
+            (Tool::Roll, Some(edge)) => Some(Self::Roll(edge)),
+            (Tool::Ripple | Tool::Roll, None) => None,
+            (Tool::Slip, _) => Some(Self::Slip),
+            (Tool::Slide, _) => Some(Self::Slide),
+            (Tool::Razor, _) => None,
+        }
+    }
+
+    /// Name of the undo step.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Move => "Move clip",
+            Self::Trim(_) => "Trim clip",
+            Self::Ripple(_) => "Ripple trim",
+            Self::Roll(_) => "Roll edit",
+            Self::Slip => "Slip clip",
+            Self::Slide => "Slide clip",
+        }
+    }
+
+    /// Edges which move with the pointer and snap, where they are in the clip before the drag.
+    fn edges(self, clip: &Clip) -> Vec<i64> {
+        match self {
+            Self::Move | Self::Slide => vec![clip.start, clip.end()],
+            Self::Trim(Edge::Start) | Self::Ripple(Edge::Start) | Self::Roll(Edge::Start) => {
+                vec![clip.start]
+            }
+            Self::Trim(Edge::End) | Self::Ripple(Edge::End) | Self::Roll(Edge::End) => {
+                vec![clip.end()]
+            }
+            Self::Slip => Vec::new(),
+        }
+    }
+
+    /// Whether the clip moves along with the dragged one, its edges aren't snap targets.
+    ///
+    /// Clips of locked tracks never move.
+    fn moves(self, dragged: (usize, usize, &Clip), other: (usize, usize, &Clip)) -> bool {
+        let ((track, index, clip), (other_track, other_index, other)) = (dragged, other);
+        if track != other_track {
+            let linked = clip.link.is_some() && other.link == clip.link;
+            // Ripple moves everything after the edit point
+            return linked
+                || match self {
+                    Self::Ripple(Edge::Start) => other.start > clip.start,
+                    Self::Ripple(Edge::End) => other.start >= clip.end(),
+                    _ => false,
+                };
+        }
+        match self {
+            Self::Ripple(_) => other_index >= index,
+            Self::Roll(_) | Self::Slide => other_index.abs_diff(index) <= 1,
+            _ => other_index == index,
+        }
+    }
+
+    fn cursor(self) -> egui::CursorIcon {
+        match self {
+            Self::Move => egui::CursorIcon::Grab,
+            Self::Trim(Edge::Start) | Self::Ripple(Edge::Start) => egui::CursorIcon::ResizeWest,
+            Self::Trim(Edge::End) | Self::Ripple(Edge::End) => egui::CursorIcon::ResizeEast,
+            Self::Roll(_) => egui::CursorIcon::ResizeColumn,
+            Self::Slip | Self::Slide => egui::CursorIcon::ResizeHorizontal,
+        }
+    }
+}
+
+/// Clip being moved or edited with the mouse.
+pub struct ClipDrag {
+    /// Sequence before the drag started, every step is computed from it.
+    origin: Sequence,
+    mode: DragMode,
+    track: usize,
+    clip: usize,
+    /// Frame where the clip was grabbed.
+    grab_frame: i64,
+    /// Last applied track and start when moving, last requested offset otherwise.
+    target: (usize, i64),
+    /// Timecodes shown next to the pointer.
+    feedback: String,
+    format: TimecodeFormat,
+    /// Frames the moving edges snap to.
+    targets: Vec<i64>,
+    /// Frame the edges snapped to in the last step.
+    pub snapped: Option<i64>,
+    /// Merges all steps of the drag into a single undo step.
+    merge_id: egui::Id,
+}
+
+impl Timeline {
+    /// Track and index of the clip at the position, with the edge if it's close to one.
+    ///
+    /// Clips of locked tracks can't be edited and aren't found.
+    pub(super) fn clip_at(
+        &self,
+        sequence: &Sequence,
+        pos: egui::Pos2,
+        lanes_rect: egui::Rect,
+    ) -> Option<(usize, usize, Option<Edge>)> {
+        let track_index = self.track_at(pos.y, lanes_rect)?;
+        let track = sequence
+            .tracks
+            .get(track_index)
+            .filter(|track| !track.locked)?;
+        track.clips.iter().enumerate().find_map(|(index, clip)| {
+            let left = self.frame_to_x(clip.start, sequence.frame_rate, lanes_rect.left());
+            let right = self.frame_to_x(clip.end(), sequence.frame_rate, lanes_rect.left());
+            if !(left..right).contains(&pos.x) {
+                return None;
+            }
+            // Short clips can still be grabbed in the middle
+            let edge_width = EDGE_WIDTH.min((right - left) / 3.);
+            let edge = if pos.x < left + edge_width {
+                Some(Edge::Start)
+            } else if pos.x >= right - edge_width {
+                Some(Edge::End)
+            } else {
+                None
+            };
+            Some((track_index, index, edge))
+        })
+    }
+
+    /// Moves and edits clips by dragging them with the current tool.
+    ///
+    /// Clips move within their track or to another track of the same kind. Moving edges
+    /// snap to the playhead and edges of other clips. In the magnetic mode moved clips are
+    /// inserted at cuts and edits don't leave gaps.
+    pub fn clip_drag_ui(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        lanes_rect: egui::Rect,
+        state: &mut State,
+    ) {
+        let sequence = &state.project.sequence;
+        let frame_rate = sequence.frame_rate;
+        let format = state.project.settings.timecode_format;
+
+        if let Some(drag) = &self.drag {
+            ui.ctx().set_cursor_icon(match drag.mode {
+                DragMode::Move => egui::CursorIcon::Grabbing,
+                mode => mode.cursor(),
+            });
+            egui::show_tooltip_at_pointer(
+                ui.ctx(),
+                ui.layer_id(),
+                response.id.with("feedback"),
+                |ui| ui.label(egui::RichText::new(&drag.feedback).monospace()),
+            );
+        } else if let Some(hovered) = response.hover_pos() {
+            let mode = self
+                .clip_at(sequence, hovered, lanes_rect)
+                .and_then(|(_, _, edge)| DragMode::new(self.tool, edge, self.magnetic));
+            if let Some(mode) = mode {
+                ui.ctx().set_cursor_icon(mode.cursor());
+            }
+        }
+
+        let Some(pointer) = response.interact_pointer_pos() else {
+            self.drag = None;
+            return;
+        };
+        let frame = self.x_to_frame(pointer.x, frame_rate, lanes_rect.left());
+        let hovered_track = self.track_at(pointer.y, lanes_rect);
+        let magnetic = self.magnetic;
+
+        if response.drag_started() {
+            // Drag starts after the pointer moved a bit, the clip is where it was pressed
+            let pressed = ui.input(|i| i.pointer.press_origin()).unwrap_or(pointer);
+            let grab_frame = self.x_to_frame(pressed.x, frame_rate, lanes_rect.left());
+            self.drag =
+                self.clip_at(sequence, pressed, lanes_rect)
+                    .and_then(|(track, clip, edge)| {
+                        let mode = DragMode::new(self.tool, edge, self.magnetic)?;
+                        let dragged = &sequence.tracks[track].clips[clip];
+                        let start = dragged.start;
+                        let targets = Self::snap_targets(
+                            sequence,
+                            state.playback.playhead(),
+                            |other_track, other_index, other| {
+                                sequence.tracks[other_track].locked
+                                    || !mode.moves(
+                                        (track, clip, dragged),
+                                        (other_track, other_index, other),
+                                    )
+                            },
+                        );
+                        Some(ClipDrag {
+                            origin: sequence.clone(),
+                            mode,
+                            track,
+                            clip,
+                            grab_frame,
+                            target: match mode {
+                                DragMode::Move => (track, start),
+                                _ => (track, 0),
+                            },
+                            feedback: feedback(mode, dragged, 0, frame_rate, format),
+                            format,
+                            targets,
+                            snapped: None,
+                            merge_id: response.id.with(response.ctx.input(|i| i.time).to_bits()),
+                        })
+                    });
+        }
+
+        let snap = self.drag.as_ref().and_then(|drag| {
+            let clip = &drag.origin.tracks[drag.track].clips[drag.clip];
+            let offset = frame - drag.grab_frame;
+            let edges: Vec<i64> = drag
+                .mode
+                .edges(clip)
+                .into_iter()
+                .map(|edge| edge + offset)
+                .collect();
+            self.snap(&edges, &drag.targets, frame_rate)
+        });
+        let frame = frame + snap.map_or(0, |snap| snap.delta);
+
+        let Some(drag) = &mut self.drag else {
+            return;
+        };
+        if response.drag_stopped() {
+            self.drag = None;
+            return;
+        }
+        drag.snapped = snap.map(|snap| snap.target);
+
+        let (after, target, feedback) = match drag.mode {
+            DragMode::Move => {
+                let Some(step) = move_clip(drag, hovered_track, frame, magnetic) else {
+                    return;
+                };
+                step
+            }
+            mode => {
+                let offset = frame - drag.grab_frame;
+                if offset == drag.target.1 {
+                    return;
+                }
+                let mut after = drag.origin.clone();
+                let source_length = |clip: &Clip| source_length(&state.project, clip);
+                let (track, index) = (drag.track, drag.clip);
+                let applied = match mode {
+                    DragMode::Trim(edge) => after.trim(track, index, edge, offset, &source_length),
+                    DragMode::Ripple(edge) => {
+                        after.ripple_trim(track, index, edge, offset, &source_length)
+                    }
+                    DragMode::Roll(edge) => after.roll(track, index, edge, offset, &source_length),
+                    DragMode::Slip => after.slip(track, index, offset, &source_length),
+                    DragMode::Slide => after.slide(track, index, offset, &source_length),
+                    DragMode::Move => unreachable!(),
+                };
+                let clip = &after.tracks[track].clips[index];
+                let feedback = feedback(mode, clip, applied, frame_rate, drag.format);
+                (after, (track, offset), feedback)
+            }
+        };
+        drag.target = target;
+        drag.feedback = feedback;
+
+        let before = state.project.sequence.clone();
+        let command = EditSequence::new(drag.mode.name(), before, after).merge_id(drag.merge_id);
+        state.execute(command);
+    }
+}
+
+/// Sequence with the clip moved to the hovered track and frame, `None` if it's already there.
+///
+/// Linked clips of other unlocked tracks move along by the same offset. In the magnetic
+/// mode the clip is inserted at the closest cut of its track and the gap it left closes,
+/// linked clips are placed by the same offset.
+fn move_clip(
+    drag: &ClipDrag,
+    hovered_track: Option<usize>,
+    frame: i64,
+    magnetic: bool,
+) -> Option<(Sequence, (usize, i64), String)> {
+    let origin = &drag.origin;
+    // Stay on the current track when hovering a locked track or a track of other kind
+    let kind = origin.tracks[drag.track].kind;
+    let track = hovered_track
+        .filter(|track| {
+            origin
+                .tracks
+                .get(*track)
+                .is_some_and(|t| t.kind == kind && !t.locked)
+        })
+        .unwrap_or(drag.target.0);
+    let grab_offset = drag.grab_frame - origin.tracks[drag.track].clips[drag.clip].start;
+    let start = (frame - grab_offset).max(0);
+    if (track, start) == drag.target {
+        return None;
+    }
+
+    let mut after = origin.clone();
+    let mut moved = after.remove_linked(drag.track, drag.clip);
+    moved[0].0 = track;
+    let first = moved.iter().map(|(_, clip)| clip.start).min().unwrap_or(0);
+    let offset = if magnetic {
+        let last = moved.iter().map(|(_, clip)| clip.end()).max().unwrap_or(0);
+        after.close_gap(first, last);
+        after.tracks[track].closest_cut(start) - moved[0].1.start
+    } else {
+        start - moved[0].1.start
+    };
+    // Linked clips keep their offsets, none goes before the start
+    let offset = offset.max(-first);
+    for (_, clip) in &mut moved {
+        clip.start += offset;
+    }
+    let feedback = feedback(
+        DragMode::Move,
+        &moved[0].1,
+        0,
+        origin.frame_rate,
+        drag.format,
+    );
+
+    if magnetic {
+        after.insert_clips(moved);
+    } else {
+        for (track, clip) in moved {
+            let track = &mut after.tracks[track];
+            track.clear_range(clip.start, clip.end());
+            track.insert_clip(clip);
+        }
+    }
+    Some((after, (track, start), feedback))
+}
+
+/// Frames of the media the clip uses, unlimited for stills and media of unknown length.
+fn source_length(project: &Project, clip: &Clip) -> Option<i64> {
+    let file = project.file(clip.file)?;
+    if file.r#type == FileType::Image {
+        return None;
+    }
+    let duration = file.info.duration?;
+    Some(project.sequence.frame_rate.seconds_to_frames(duration))
+}
+
+/// Timecodes of the edited clip, e.g. the change and the new duration when trimming.
+fn feedback(
+    mode: DragMode,
+    clip: &Clip,
+    offset: i64,
+    frame_rate: FrameRate,
+    format: TimecodeFormat,
+) -> String {
+    let timecode = |frames| frame_rate.format(frames, format);
+    let change = if offset > 0 {
+        format!("+{}", timecode(offset))
+    } else {
+        timecode(offset)
+    };
+    match mode {
+        DragMode::Move => format!("Start {}", timecode(clip.start)),
+        DragMode::Trim(_) | DragMode::Ripple(_) | DragMode::Roll(_) => {
+            format!("{change}  Duration {}", timecode(clip.duration()))
+        }
+        DragMode::Slip => format!(
+            "{change}  In {}  Out {}",
+            timecode(clip.source_in),
+            timecode(clip.source_out)
+        ),
+        DragMode::Slide => format!("{change}  Start {}", timecode(clip.start)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        project::AssetId,
+        sequence::{LinkId, Track, TrackKind},
+    };
+
+    fn clip(start: i64, duration: i64) -> Clip {
+        Clip {
+            file: AssetId::new(),
+            start,
+            source_in: 0,
+            source_out: duration,
+            link: None,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Drag of the clip at the index of the track, grabbed at its start.
+    fn drag(origin: Sequence, track: usize, clip: usize) -> ClipDrag {
+        let start = origin.tracks[track].clips[clip].start;
+        ClipDrag {
+            origin,
+            mode: DragMode::Move,
+            track,
+            clip,
+            grab_frame: start,
+            target: (track, start),
+            feedback: String::new(),
+            format: TimecodeFormat::default(),
+            targets: Vec::new(),
+            snapped: None,
+            merge_id: egui::Id::new("drag"),
+        }
+    }
+
+    fn starts(sequence: &Sequence, track: usize) -> Vec<i64> {
+        sequence.tracks[track]
+            .clips
+            .iter()
+            .map(|clip| clip.start)
+            .collect()
+    }
+
+    #[test]
+    fn clips_stay_off_locked_tracks() {
+        let mut locked = Track::new("V2", TrackKind::Video);
+        locked.clips.push(clip(0, 50));
+        locked.locked = true;
+        let mut sequence = Sequence::default();
+        sequence.tracks.insert(0, locked);
+        sequence.tracks[1].clips.push(clip(0, 10));
+
+        for magnetic in [false, true] {
+            let drag = drag(sequence.clone(), 1, 0);
+            let (after, target, _) = move_clip(&drag, Some(0), 20, magnetic).unwrap();
+            assert_eq!(target.0, 1);
+            assert_eq!(after.tracks[0].clips.len(), 1);
+            assert_eq!(after.tracks[0].clips[0].duration(), 50);
+            assert_eq!(after.tracks[1].clips.len(), 1);
+        }
+    }
+
+    #[test]
+    fn clips_move_to_tracks_of_same_kind() {
+        let mut sequence = Sequence::default();
+        sequence.add_track(TrackKind::Video);
+        sequence.tracks[1].clips.push(clip(0, 10));
+
+        let (after, target, _) =
+            move_clip(&drag(sequence.clone(), 1, 0), Some(0), 20, false).unwrap();
+        assert_eq!(target, (0, 20));
+        assert_eq!(starts(&after, 0), [20]);
+        assert!(after.tracks[1].clips.is_empty());
+
+        // Audio track is of other kind
+        let (after, target, _) = move_clip(&drag(sequence, 1, 0), Some(2), 20, false).unwrap();
+        assert_eq!(target, (1, 20));
+        assert_eq!(starts(&after, 1), [20]);
+    }
+
+    #[test]
+    fn magnetic_moves_keep_linked_clips_together() {
+        let mut sequence = Sequence::default();
+        for start in [5, 15, 25] {
+            let mut clip = clip(start, 10);
+            clip.link = Some(LinkId::new());
+            for track in 0..2 {
+                sequence.tracks[track].clips.push(clip.clone());
+            }
+        }
+        let moved = sequence.tracks[0].clips[1].link;
+
+        let (after, _, _) = move_clip(&drag(sequence, 0, 1), Some(0), 30, true).unwrap();
+        // Inserted at the closest cut after its gap closed, the gap before the first clip stays
+        for track in 0..2 {
+            assert_eq!(starts(&after, track), [5, 15, 25]);
+            assert_eq!(after.tracks[track].clips[2].link, moved);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Timeline, TRACK_HEIGHT};
+use crate::{
+    panes::files::DraggedFile,
+    project::FileType,
+    sequence::{Clip, LinkId, TrackKind},
+    state::State,
+};
+
+impl Timeline {
+    fn track_kind(file_type: FileType) -> TrackKind {
+        match file_type {
+            FileType::Image | FileType::Video => TrackKind::Video,
+            FileType::Sound => TrackKind::Audio,
+        }
+    }
+
+    /// Creates clip from file dragged from the Files pane.
+    ///
+    /// The new clip snaps like moved clips. In the magnetic mode it's inserted at the closest
+    /// cut, later clips move to make room.
+    pub fn drop_ui(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        lanes_rect: egui::Rect,
+        state: &mut State,
+    ) {
+        let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+            return;
+        };
+        let Some(dragged) = response.dnd_hover_payload::<DraggedFile>() else {
+            return;
+        };
+        let Some(file) = state.project.file(dragged.id) else {
+            return;
+        };
+        if !lanes_rect.contains(pointer) {
+            return;
+        }
+
+        let sequence = &state.project.sequence;
+        let frame_rate = sequence.frame_rate;
+        let still_duration = state.project.settings.still_duration;
+
+        let Some(track_index) = self.track_at(pointer.y, lanes_rect) else {
+            return;
+        };
+        let Some(track) = sequence.tracks.get(track_index) else {
+            return;
+        };
+
+        let duration = match file.r#type {
+            FileType::Image => frame_rate.seconds_to_frames(still_duration),
+            FileType::Video | FileType::Sound => frame_rate
+                .seconds_to_frames(file.info.duration.unwrap_or(still_duration))
+                .max(1),
+        };
+        let start = self
+            .x_to_frame(pointer.x, frame_rate, lanes_rect.left())
+            .max(0);
+        let targets = Self::snap_targets(sequence, state.playback.playhead(), |_, _, _| true);
+        let snap = self.snap(&[start, start + duration], &targets, frame_rate);
+        let start = (start + snap.map_or(0, |snap| snap.delta)).max(0);
+        let accepted = track.kind == Self::track_kind(file.r#type) && !track.locked;
+
+        // Preview of the new clip
+        let top = self.track_top(track_index, lanes_rect);
+        let preview = egui::Rect::from_min_max(
+            egui::pos2(
+                self.frame_to_x(start, frame_rate, lanes_rect.left()),
+                top + 2.,
+            ),
+            egui::pos2(
+                self.frame_to_x(start + duration, frame_rate, lanes_rect.left()),
+                top + TRACK_HEIGHT - 2.,
+            ),
+        );
+        let color = if accepted {
+            ui.visuals().selection.stroke.color
+        } else {
+            ui.visuals().error_fg_color
+        };
+        ui.painter_at(lanes_rect)
+            .rect_stroke(preview, 3., egui::Stroke::new(2., color));
+        if let Some(snap) = snap {
+            self.snap_indicator_ui(ui, lanes_rect, frame_rate, snap.target);
+        }
+
+        if accepted && response.dnd_release_payload::<DraggedFile>().is_some() {
+            // Sound of a video goes to the first unlocked audio track, linked to the picture
+            let sound_track = (file.r#type == FileType::Video && file.info.audio_codec.is_some())
+                .then(|| {
+                    sequence
+                        .tracks
+                        .iter()
+                        .position(|track| track.kind == TrackKind::Audio && !track.locked)
+                })
+                .flatten();
+            let clip = Clip {
+                file: dragged.id,
+                start,
+                source_in: 0,
+                source_out: duration,
+                link: sound_track.map(|_| LinkId::new()),
+                markers: Vec::new(),
+            };
+            let magnetic = self.magnetic;
+            state.edit_sequence(format!("Add {}", file.name), |sequence| {
+                let mut clips = vec![(track_index, clip.clone())];
+                clips.extend(sound_track.map(|track| (track, clip)));
+                if magnetic {
+                    // Picture and sound are inserted at the same cut
+                    let cut = sequence.tracks[track_index].closest_cut(start);
+                    for (_, clip) in &mut clips {
+                        clip.start = cut;
+                    }
+                    sequence.insert_clips(clips);
+                } else {
+                    for (track, clip) in clips {
+                        let track = &mut sequence.tracks[track];
+                        track.clear_range(clip.start, clip.end());
+                        track.insert_clip(clip);
+                    }
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::Timeline;
+use crate::{
+    panes::markers::marker_color,
+    sequence::{Clip, FrameRate, Marker, Sequence, TimecodeFormat},
+};
+
+/// Distance from a marker in pixels at which its name is shown.
+const HOVER_DISTANCE: f32 = 5.;
+
+/// Small flag pointing down at the frame.
+fn flag(painter: &egui::Painter, x: f32, top: f32, color: egui::Color32) {
+    painter.add(egui::Shape::convex_polygon(
+        vec![
+            egui::pos2(x - 4., top),
+            egui::pos2(x + 4., top),
+            egui::pos2(x + 4., top + 5.),
+            egui::pos2(x, top + 9.),
+            egui::pos2(x - 4., top + 5.),
+        ],
+        color,
+        egui::Stroke::NONE,
+    ));
+}
+
+/// Name and notes of the marker.
+fn hover_text(
+    marker: &Marker,
+    frame_rate: FrameRate,
+    format: TimecodeFormat,
+    frame: i64,
+) -> String {
+    let mut text = format!("{}  {}", marker.name, frame_rate.format(frame, format));
+    if !marker.notes.is_empty() {
+        text.push('\n');
+        text.push_str(&marker.notes);
+    }
+    text
+}
+
+impl Timeline {
+    /// Draws the in and out points, regions and markers of the sequence on the ruler.
+    ///
+    /// The range between in and out is shaded over the lanes too.
+    pub(super) fn markers_ui(
+        &self,
+        ui: &egui::Ui,
+        ruler_rect: egui::Rect,
+        lanes_rect: egui::Rect,
+        sequence: &Sequence,
+        format: TimecodeFormat,
+    ) {
+        let frame_rate = sequence.frame_rate;
+        let x = |frame| self.frame_to_x(frame, frame_rate, ruler_rect.left());
+        let painter = ui.painter_at(ruler_rect);
+        let lanes_painter = ui.painter_at(lanes_rect);
+        let selection = ui.visuals().selection.bg_fill;
+
+        if let Some((in_point, out_point)) = sequence.in_out_range() {
+            let range = egui::Rangef::new(x(in_point), x(out_point));
+            painter.rect_filled(
+                egui::Rect::from_x_y_ranges(range, ruler_rect.y_range()),
+                0.,
+                selection.gamma_multiply(0.5),
+            );
+            lanes_painter.rect_filled(
+                egui::Rect::from_x_y_ranges(range, lanes_rect.y_range()),
+                0.,
+                selection.gamma_multiply(0.12),
+            );
+        }
+        let stroke = egui::Stroke::new(2., selection);
+        if let Some(in_point) = sequence.in_point {
+            let x = x(in_point);
+            painter.line_segment(
+                [
+                    egui::pos2(x + 4., ruler_rect.bottom() - 2.),
+                    egui::pos2(x, ruler_rect.bottom() - 2.),
+                ],
+                stroke,
+            );
+            painter.vline(x, ruler_rect.y_range(), stroke);
+        }
+        if let Some(out_point) = sequence.out_point {
+            let x = x(out_point);
+            painter.line_segment(
+                [
+                    egui::pos2(x - 4., ruler_rect.bottom() - 2.),
+                    egui::pos2(x, ruler_rect.bottom() - 2.),
+                ],
+                stroke,
+            );
+            painter.vline(x, ruler_rect.y_range(), stroke);
+        }
+
+        let pointer = ui
+            .ctx()
+            .pointer_hover_pos()
+            .filter(|pos| ruler_rect.contains(*pos));
+        let mut hovered = None;
+        for marker in &sequence.markers {
+            let color = marker_color(marker.color);
+            let left = x(marker.frame);
+            if marker.is_region() {
+                let right = x(marker.end());
+                painter.rect_filled(
+                    egui::Rect::from_min_max(
+                        egui::pos2(left, ruler_rect.top()),
+                        egui::pos2(right, ruler_rect.top() + 4.),
+                    ),
+                    0.,
+                    color,
+                );
+                painter.rect_filled(
+                    egui::Rect::from_x_y_ranges(left..=right, ruler_rect.y_range()),
+                    0.,
+                    color.gamma_multiply(0.15),
+                );
+            }
+            flag(&painter, left, ruler_rect.top(), color);
+
+            if pointer.is_some_and(|pointer| (pointer.x - left).abs() <= HOVER_DISTANCE) {
+                hovered = Some(hover_text(marker, frame_rate, format, marker.frame));
+            }
+        }
+
+        if let Some(text) = hovered {
+            egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), ui.id().with("marker"), |ui| {
+                ui.label(text)
+            });
+        }
+    }
+
+    /// Draws markers of the clip along its top edge.
+    pub(super) fn clip_markers_ui(
+        &self,
+        ui: &egui::Ui,
+        clip: &Clip,
+        clip_rect: egui::Rect,
+        lanes_rect: egui::Rect,
+        frame_rate: FrameRate,
+        format: TimecodeFormat,
+    ) {
+        let pointer = ui
+            .ctx()
+            .pointer_hover_pos()
+            .filter(|pos| clip_rect.contains(*pos));
+        let painter = ui.painter_at(lanes_rect);
+        let mut hovered = None;
+        for (_, marker) in clip.visible_markers() {
+            let frame = clip.to_sequence_frame(marker.frame);
+            let x = self.frame_to_x(frame, frame_rate, lanes_rect.left());
+            flag(&painter, x, clip_rect.top(), marker_color(marker.color));
+            if pointer.is_some_and(|pointer| {
+                (pointer.x - x).abs() <= HOVER_DISTANCE && pointer.y < clip_rect.top() + 12.
+            }) {
+                hovered = Some(hover_text(marker, frame_rate, format, frame));
+            }
+        }
+
+        if let Some(text) = hovered {
+            egui::show_tooltip_at_pointer(
+                ui.ctx(),
+                ui.layer_id(),
+                ui.id().with("clip_marker"),
+                |ui| ui.label(text),
+            );
+        }
+    }
+}
@@ -0,0 +1,297 @@
+mod clip_drag;
+mod drop;
+mod markers;
+mod razor;
+mod ruler;
+mod snap;
+mod tool;
+mod zoom;
+
+use super::PaneBehavior;
+use crate::{
+    sequence::{FrameRate, TrackKind},
+    state::State,
+};
+use tool::Tool;
+
+const HEADER_WIDTH: f32 = 80.;
+const RULER_HEIGHT: f32 = 24.;
+const TRACK_HEIGHT: f32 = 48.;
+
+pub struct Timeline {
+    /// Horizontal zoom.
+    pixels_per_second: f32,
+    /// Horizontal scroll in pixels.
+    scroll_x: f32,
+    /// Vertical scroll of tracks in pixels.
+    scroll_y: f32,
+
+    tool: Tool,
+    /// Dragged edges snap to the playhead and other clips.
+    snapping: bool,
+    /// Edited tracks close their gaps.
+    magnetic: bool,
+    /// Zoom to fit once the lanes size is known.
+    fit_requested: bool,
+    drag: Option<clip_drag::ClipDrag>,
+}
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            pixels_per_second: 100.,
+            scroll_x: 0.,
+            scroll_y: 0.,
+            tool: Tool::default(),
+            snapping: true,
+            magnetic: false,
+            fit_requested: false,
+            drag: None,
+        }
+    }
+}
+impl Timeline {
+    fn frame_to_x(&self, frame: i64, frame_rate: FrameRate, origin: f32) -> f32 {
+        origin + frame_rate.frames_to_seconds(frame) as f32 * self.pixels_per_second - self.scroll_x
+    }
+
+    fn x_to_frame(&self, x: f32, frame_rate: FrameRate, origin: f32) -> i64 {
+        frame_rate.seconds_to_frames(((x - origin + self.scroll_x) / self.pixels_per_second) as f64)
+    }
+
+    /// Top edge of the track lane.
+    fn track_top(&self, index: usize, lanes_rect: egui::Rect) -> f32 {
+        lanes_rect.top() + index as f32 * TRACK_HEIGHT - self.scroll_y
+    }
+
+    /// Index of the track lane under the position, may be out of bounds.
+    fn track_at(&self, y: f32, lanes_rect: egui::Rect) -> Option<usize> {
+        let index = (y - lanes_rect.top() + self.scroll_y) / TRACK_HEIGHT;
+        (index >= 0.).then_some(index as usize)
+    }
+
+    fn track_color(kind: TrackKind) -> egui::Color32 {
+        match kind {
+            TrackKind::Video => egui::Color32::from_rgb(70, 110, 170),
+            TrackKind::Audio => egui::Color32::from_rgb(70, 150, 100),
+        }
+    }
+}
+
+impl PaneBehavior for Timeline {
+    fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let (rect, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+        if rect.width() <= HEADER_WIDTH || rect.height() <= RULER_HEIGHT {
+            return;
+        }
+
+        let lanes_rect = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + HEADER_WIDTH, rect.top() + RULER_HEIGHT),
+            rect.max,
+        );
+        let lanes_response = ui.interact(
+            lanes_rect,
+            ui.id().with("lanes"),
+            egui::Sense::click_and_drag(),
+        );
+
+        self.scroll_zoom_ui(ui, rect, lanes_rect, state);
+        self.tool_shortcuts(ui.ctx(), state);
+        self.clip_drag_ui(ui, &lanes_response, lanes_rect, state);
+
+        let sequence = &state.project.sequence;
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals().clone();
+        let mut toggle_lock = None;
+
+        // Tracks
+        for (index, track) in sequence.tracks.iter().enumerate() {
+            let top = self.track_top(index, lanes_rect);
+            let lane = egui::Rect::from_min_max(
+                egui::pos2(lanes_rect.left(), top),
+                egui::pos2(lanes_rect.right(), top + TRACK_HEIGHT),
+            );
+            if !lane.intersects(lanes_rect) {
+                continue;
+            }
+
+            let lane_painter = painter.with_clip_rect(lanes_rect);
+            lane_painter.rect_filled(
+                lane.shrink2(egui::vec2(0., 1.)),
+                0.,
+                visuals.extreme_bg_color,
+            );
+
+            let color = Self::track_color(track.kind);
+            // Clips of locked tracks are dimmed
+            let opacity = if track.locked { 0.4 } else { 1. };
+            for clip in &track.clips {
+                let clip_rect = egui::Rect::from_min_max(
+                    egui::pos2(
+                        self.frame_to_x(clip.start, sequence.frame_rate, lane.left()),
+                        lane.top() + 2.,
+                    ),
+                    egui::pos2(
+                        self.frame_to_x(clip.end(), sequence.frame_rate, lane.left()),
+                        lane.bottom() - 2.,
+                    ),
+                );
+                if !clip_rect.intersects(lanes_rect) {
+                    continue;
+                }
+
+                let file = state.project.file(clip.file);
+                // Clips of missing media stand out until they are relinked
+                let color = match file {
+                    Some(file) if file.is_offline() => visuals.error_fg_color,
+                    _ => color,
+                };
+                lane_painter.rect(
+                    clip_rect,
+                    3.,
+                    color.gamma_multiply(0.8 * opacity),
+                    egui::Stroke::new(1., color.gamma_multiply(opacity)),
+                );
+                self.clip_markers_ui(
+                    ui,
+                    clip,
+                    clip_rect,
+                    lanes_rect,
+                    sequence.frame_rate,
+                    state.project.settings.timecode_format,
+                );
+                if let Some(file) = file {
+                    lane_painter
+                        .with_clip_rect(clip_rect.shrink(2.).intersect(lanes_rect))
+                        .text(
+                            clip_rect.left_top() + egui::vec2(6., 4.),
+                            egui::Align2::LEFT_TOP,
+                            &file.name,
+                            egui::FontId::proportional(12.),
+                            visuals.strong_text_color(),
+                        );
+                }
+            }
+
+            // Header
+            let header = egui::Rect::from_min_max(
+                egui::pos2(rect.left(), top),
+                egui::pos2(lanes_rect.left(), top + TRACK_HEIGHT),
+            );
+            let header_painter = painter.with_clip_rect(egui::Rect::from_min_max(
+                egui::pos2(rect.left(), lanes_rect.top()),
+                egui::pos2(lanes_rect.left(), rect.bottom()),
+            ));
+            header_painter.rect_filled(
+                header.shrink2(egui::vec2(0., 1.)),
+                0.,
+                visuals.faint_bg_color,
+            );
+            header_painter.rect_filled(
+                egui::Rect::from_min_size(
+                    header.min + egui::vec2(0., 1.),
+                    egui::vec2(3., TRACK_HEIGHT - 2.),
+                ),
+                0.,
+                color,
+            );
+            header_painter.text(
+                header.left_center() + egui::vec2(10., 0.),
+                egui::Align2::LEFT_CENTER,
+                &track.name,
+                egui::FontId::proportional(14.),
+                visuals.text_color(),
+            );
+
+            let lock_rect = egui::Rect::from_center_size(
+                header.right_center() - egui::vec2(14., 0.),
+                egui::vec2(20., 20.),
+            );
+            let lock = ui
+                .interact(
+                    lock_rect.intersect(lanes_rect.with_min_x(rect.left())),
+                    ui.id().with(("lock", index)),
+                    egui::Sense::click(),
+                )
+                .on_hover_text(if track.locked {
+                    "Unlock track"
+                } else {
+                    "Lock track, its clips can't be edited"
+                });
+            if lock.clicked() {
+                toggle_lock = Some(index);
+            }
+            header_painter.text(
+                lock_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                if track.locked { "🔒" } else { "🔓" },
+                egui::FontId::proportional(12.),
+                if track.locked || lock.hovered() {
+                    visuals.strong_text_color()
+                } else {
+                    visuals.weak_text_color()
+                },
+            );
+        }
+
+        if let Some(index) = toggle_lock {
+            let track = &state.project.sequence.tracks[index];
+            let name = format!(
+                "{} {}",
+                if track.locked { "Unlock" } else { "Lock" },
+                track.name
+            );
+            state.edit_sequence(name, |sequence| {
+                let track = &mut sequence.tracks[index];
+                track.locked = !track.locked;
+            });
+        }
+
+        if let Some(target) = self.drag.as_ref().and_then(|drag| drag.snapped) {
+            self.snap_indicator_ui(ui, lanes_rect, state.project.sequence.frame_rate, target);
+        }
+        self.razor_ui(ui, &lanes_response, lanes_rect, state);
+        self.scrollbar_ui(ui, lanes_rect, state);
+        self.drop_ui(ui, &lanes_response, lanes_rect, state);
+
+        // Ruler
+        let sequence = &state.project.sequence;
+        let ruler_rect = egui::Rect::from_min_max(
+            egui::pos2(lanes_rect.left(), rect.top()),
+            egui::pos2(rect.right(), lanes_rect.top()),
+        );
+        self.ruler_ui(
+            &painter.with_clip_rect(ruler_rect),
+            ruler_rect,
+            sequence.frame_rate,
+            state.project.settings.timecode_format,
+        );
+        self.markers_ui(
+            ui,
+            ruler_rect,
+            lanes_rect,
+            sequence,
+            state.project.settings.timecode_format,
+        );
+
+        self.playhead_ui(ui, ruler_rect, lanes_rect, state);
+    }
+
+    fn top_bar_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        if ui.button("Add audio track").clicked() {
+            state.edit_sequence("Add audio track", |sequence| {
+                sequence.add_track(TrackKind::Audio);
+            });
+        }
+        if ui.button("Add video track").clicked() {
+            state.edit_sequence("Add video track", |sequence| {
+                sequence.add_track(TrackKind::Video);
+            });
+        }
+        ui.separator();
+        self.tool_ui(ui);
+        ui.separator();
+        self.snap_toggles_ui(ui);
+        ui.separator();
+        self.zoom_ui(ui, state);
+    }
+}
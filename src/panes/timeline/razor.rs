@@ -0,0 +1,46 @@
+use super::{tool::Tool, Timeline, TRACK_HEIGHT};
+use crate::state::State;
+
+impl Timeline {
+    /// Cuts the clicked clip and clips linked to it with the razor tool.
+    ///
+    /// A line shows where the hovered clip would be cut.
+    pub(super) fn razor_ui(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        lanes_rect: egui::Rect,
+        state: &mut State,
+    ) {
+        if self.tool != Tool::Razor {
+            return;
+        }
+        let Some(pointer) = response.hover_pos() else {
+            return;
+        };
+        let sequence = &state.project.sequence;
+        let Some((track, index, _)) = self.clip_at(sequence, pointer, lanes_rect) else {
+            return;
+        };
+        let frame = self.x_to_frame(pointer.x, sequence.frame_rate, lanes_rect.left());
+        let clip = &sequence.tracks[track].clips[index];
+        if frame <= clip.start || frame >= clip.end() {
+            return;
+        }
+
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+        let x = self.frame_to_x(frame, sequence.frame_rate, lanes_rect.left());
+        let top = self.track_top(track, lanes_rect);
+        ui.painter_at(lanes_rect).vline(
+            x,
+            top..=top + TRACK_HEIGHT,
+            egui::Stroke::new(1.5, ui.visuals().strong_text_color()),
+        );
+
+        if response.clicked() {
+            state.edit_sequence("Split clip", |sequence| {
+                sequence.split_clip(track, index, frame);
+            });
+        }
+    }
+}
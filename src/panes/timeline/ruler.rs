@@ -0,0 +1,176 @@
+use super::Timeline;
+use crate::{
+    history::EditSettings,
+    sequence::{FrameRate, TimecodeFormat, FRAMES_PER_FOOT},
+    state::State,
+};
+
+/// Space around each label on the ruler.
+const RULER_LABEL_PADDING: f32 = 16.;
+/// Minimal distance between two minor ticks.
+const MINOR_TICK_SPACING: f32 = 6.;
+/// Distances between labels in seconds, when they are a second or longer.
+const SECOND_STEPS: [f64; 17] = [
+    1., 2., 5., 10., 15., 30., 60., 120., 300., 600., 900., 1800., 3600., 7200., 14400., 28800.,
+    86400.,
+];
+
+/// Distances between labels in frames which read well in the format, shortest first.
+fn label_steps(frame_rate: FrameRate, format: TimecodeFormat) -> Vec<f64> {
+    // 1, 2, 5, 10, 20, 50…
+    let decimal = (0..8).flat_map(|exponent| [1., 2., 5.].map(|m| m * 10_f64.powi(exponent)));
+    match format {
+        TimecodeFormat::Frames => decimal.collect(),
+        TimecodeFormat::FeetFrames => [1., 2., 4., 8.]
+            .into_iter()
+            .chain(decimal.map(|feet| feet * FRAMES_PER_FOOT as f64))
+            .collect(),
+        TimecodeFormat::Smpte | TimecodeFormat::Seconds => {
+            let timebase = frame_rate.timebase() as f64;
+            [1., 2., 5., 10.]
+                .into_iter()
+                .filter(|frames| *frames < timebase)
+                .chain(SECOND_STEPS.map(|seconds| seconds * frame_rate.fps()))
+                .collect()
+        }
+    }
+}
+
+impl Timeline {
+    /// Draws labels and ticks, spaced by the zoom so the labels don't overlap.
+    pub fn ruler_ui(
+        &self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        frame_rate: FrameRate,
+        format: TimecodeFormat,
+    ) {
+        let visuals = painter.ctx().style().visuals.clone();
+        painter.rect_filled(rect, 0., visuals.faint_bg_color);
+
+        let font = egui::FontId::monospace(10.);
+        let first_frame = self.x_to_frame(rect.left(), frame_rate, rect.left());
+        let last_frame = self.x_to_frame(rect.right(), frame_rate, rect.left());
+        // The latest label is the widest
+        let label_width = painter
+            .layout_no_wrap(
+                frame_rate.format(last_frame, format),
+                font.clone(),
+                visuals.text_color(),
+            )
+            .size()
+            .x;
+
+        // Pick the smallest step that keeps labels readable
+        let pixels_per_frame = self.pixels_per_second / frame_rate.fps() as f32;
+        let steps = label_steps(frame_rate, format);
+        let step = steps
+            .iter()
+            .copied()
+            .find(|step| *step as f32 * pixels_per_frame >= label_width + RULER_LABEL_PADDING)
+            .unwrap_or(steps[steps.len() - 1]);
+        let step_width = step as f32 * pixels_per_frame;
+        // Minor ticks on whole frames, or anywhere between seconds
+        let minor_ticks = [5, 4, 2]
+            .into_iter()
+            .filter(|&count| step_width / count as f32 >= MINOR_TICK_SPACING)
+            .find(|&count| (step / count as f64).fract() == 0. || step >= frame_rate.fps())
+            .unwrap_or(1);
+
+        let first = (first_frame as f64 / step).floor() as i64;
+        let last = (last_frame as f64 / step).ceil() as i64;
+        for i in first..=last {
+            let frame = (i as f64 * step).round() as i64;
+            let x = self.frame_to_x(frame, frame_rate, rect.left());
+
+            painter.line_segment(
+                [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                visuals.widgets.noninteractive.bg_stroke,
+            );
+            painter.text(
+                egui::pos2(x + 4., rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                frame_rate.format(frame, format),
+                font.clone(),
+                visuals.text_color(),
+            );
+
+            for j in 1..minor_ticks {
+                let x = x + step_width * j as f32 / minor_ticks as f32;
+                painter.line_segment(
+                    [
+                        egui::pos2(x, rect.bottom() - 6.),
+                        egui::pos2(x, rect.bottom()),
+                    ],
+                    visuals.widgets.noninteractive.bg_stroke,
+                );
+            }
+        }
+    }
+
+    /// Picks how times are shown in the ruler and the viewer.
+    pub(super) fn timecode_format_ui(&self, ui: &mut egui::Ui, state: &mut State) {
+        let mut settings = state.project.settings.clone();
+        let response = egui::ComboBox::from_id_salt("timecode_format")
+            .selected_text(settings.timecode_format.name())
+            .show_ui(ui, |ui| {
+                for format in TimecodeFormat::ALL {
+                    ui.selectable_value(&mut settings.timecode_format, format, format.name());
+                }
+            })
+            .response;
+        if settings != state.project.settings {
+            let before = state.project.settings.clone();
+            state.execute(EditSettings::new(before, settings, response.id));
+        }
+    }
+
+    /// Draws playhead and moves it when clicking or dragging on the ruler.
+    pub fn playhead_ui(
+        &mut self,
+        ui: &egui::Ui,
+        ruler_rect: egui::Rect,
+        lanes_rect: egui::Rect,
+        state: &mut State,
+    ) {
+        let frame_rate = state.project.sequence.frame_rate;
+
+        let response = ui.interact(
+            ruler_rect,
+            ui.id().with("ruler"),
+            egui::Sense::click_and_drag(),
+        );
+        if response.clicked() || response.dragged() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                let frame = self.x_to_frame(pointer.x, frame_rate, ruler_rect.left());
+                state.playback.seek(ui.ctx(), frame);
+            }
+        }
+
+        // Follow playhead during playback
+        let mut x = self.frame_to_x(state.playback.playhead(), frame_rate, lanes_rect.left());
+        if state.playback.is_playing() && (x < lanes_rect.left() || x > lanes_rect.right()) {
+            self.scroll_x = (self.scroll_x + x - lanes_rect.left()).max(0.);
+            x = self.frame_to_x(state.playback.playhead(), frame_rate, lanes_rect.left());
+        }
+
+        let color = egui::Color32::from_rgb(220, 60, 60);
+        let painter = ui.painter_at(ruler_rect.union(lanes_rect));
+        painter.line_segment(
+            [
+                egui::pos2(x, ruler_rect.top()),
+                egui::pos2(x, lanes_rect.bottom()),
+            ],
+            egui::Stroke::new(1., color),
+        );
+        painter.add(egui::Shape::convex_polygon(
+            vec![
+                egui::pos2(x - 5., ruler_rect.top()),
+                egui::pos2(x + 5., ruler_rect.top()),
+                egui::pos2(x, ruler_rect.top() + 8.),
+            ],
+            color,
+            egui::Stroke::NONE,
+        ));
+    }
+}
@@ -0,0 +1,101 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::Timeline;
+use crate::sequence::{Clip, FrameRate, Sequence};
+
+/// Distance in pixels within which dragged edges snap.
+const SNAP_DISTANCE: f32 = 8.;
+
+/// Frame a dragged edge snapped to.
+#[derive(Clone, Copy)]
+pub struct Snap {
+    /// Frames the edges moved to reach the target.
+    pub delta: i64,
+    pub target: i64,
+}
+
+impl Timeline {
+    pub const SNAP_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::S);
+
+    /// Frames edges can snap to, the sequence start, the playhead, in and out points, markers
+    /// and edges of clips.
+    ///
+    /// Clips rejected by the filter are left out with their markers, e.g. the ones being dragged.
+    pub(super) fn snap_targets(
+        sequence: &Sequence,
+        playhead: i64,
+        filter: impl Fn(usize, usize, &Clip) -> bool,
+    ) -> Vec<i64> {
+        let mut targets = vec![0, playhead];
+        targets.extend(sequence.in_point);
+        targets.extend(sequence.out_point);
+        for marker in &sequence.markers {
+            targets.extend([marker.frame, marker.end()]);
+        }
+        for (track_index, track) in sequence.tracks.iter().enumerate() {
+            for (index, clip) in track.clips.iter().enumerate() {
+                if filter(track_index, index, clip) {
+                    targets.extend([clip.start, clip.end()]);
+                    targets.extend(
+                        clip.visible_markers()
+                            .map(|(_, marker)| clip.to_sequence_frame(marker.frame)),
+                    );
+                }
+            }
+        }
+        targets
+    }
+
+    /// Closest target to any of the edges, if it's near enough on screen.
+    ///
+    /// Positions are in whole frames, so without a target the edges stay on a frame.
+    pub(super) fn snap(
+        &self,
+        edges: &[i64],
+        targets: &[i64],
+        frame_rate: FrameRate,
+    ) -> Option<Snap> {
+        if !self.snapping {
+            return None;
+        }
+        let max_delta =
+            frame_rate.seconds_to_frames((SNAP_DISTANCE / self.pixels_per_second) as f64);
+        edges
+            .iter()
+            .flat_map(|&edge| {
+                targets.iter().map(move |&target| Snap {
+                    delta: target - edge,
+                    target,
+                })
+            })
+            .filter(|snap| snap.delta.abs() <= max_delta)
+            .min_by_key(|snap| snap.delta.abs())
+    }
+
+    /// Line across the lanes at the frame an edge snapped to.
+    pub(super) fn snap_indicator_ui(
+        &self,
+        ui: &egui::Ui,
+        lanes_rect: egui::Rect,
+        frame_rate: FrameRate,
+        target: i64,
+    ) {
+        let x = self.frame_to_x(target, frame_rate, lanes_rect.left());
+        ui.painter_at(lanes_rect).vline(
+            x,
+            lanes_rect.y_range(),
+            egui::Stroke::new(1.5, ui.visuals().warn_fg_color),
+        );
+    }
+
+    /// Toggles of snapping and of the magnetic mode, right to left.
+    pub(super) fn snap_toggles_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.magnetic, "Magnetic")
+            .on_hover_text("Edits don't leave gaps, moved clips are inserted at cuts");
+        ui.toggle_value(&mut self.snapping, "Snap")
+            .on_hover_text(format!(
+                "Snap edges to the playhead, markers and other clips ({})",
+                ui.ctx().format_shortcut(&Self::SNAP_SHORTCUT)
+            ));
+    }
+}
@@ -0,0 +1,117 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::Timeline;
+use crate::{history::EditSequence, state::State};
+
+/// What dragging a clip does.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tool {
+    /// Moves clips, their edges trim them.
+    #[default]
+    Select,
+    Ripple,
+    Roll,
+    Slip,
+    Slide,
+    /// Cuts clips where they are clicked.
+    Razor,
+}
+impl Tool {
+    pub const ALL: [Self; 6] = [
+        Self::Select,
+        Self::Ripple,
+        Self::Roll,
+        Self::Slip,
+        Self::Slide,
+        Self::Razor,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Select => "Select",
+            Self::Ripple => "Ripple",
+            Self::Roll => "Roll",
+            Self::Slip => "Slip",
+            Self::Slide => "Slide",
+            Self::Razor => "Razor",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Select => "Move clips, drag an edge to trim",
+            Self::Ripple => "Trim an edge, later clips follow",
+            Self::Roll => "Move the cut between two clips",
+            Self::Slip => "Change the used part of the media, the clip stays",
+            Self::Slide => "Move a clip, its neighbours are trimmed",
+            Self::Razor => "Cut a clip where it's clicked",
+        }
+    }
+
+    pub fn shortcut(self) -> KeyboardShortcut {
+        let key = match self {
+            Self::Select => Key::V,
+            Self::Ripple => Key::B,
+            Self::Roll => Key::N,
+            Self::Slip => Key::Y,
+            Self::Slide => Key::U,
+            Self::Razor => Key::C,
+        };
+        KeyboardShortcut::new(Modifiers::NONE, key)
+    }
+}
+
+impl Timeline {
+    pub const SPLIT_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+
+    /// Cuts clips of all unlocked tracks at the playhead.
+    pub fn split_at_playhead(state: &mut State) {
+        let before = state.project.sequence.clone();
+        let mut after = before.clone();
+        if after.split_all(state.playback.playhead()) == 0 {
+            state
+                .notifications
+                .info("No clip of an unlocked track crosses the playhead");
+            return;
+        }
+        state.execute(EditSequence::new("Split at playhead", before, after));
+    }
+
+    /// Buttons to pick the tool, right to left.
+    pub(super) fn tool_ui(&mut self, ui: &mut egui::Ui) {
+        for tool in Tool::ALL.into_iter().rev() {
+            let hint = format!(
+                "{} ({})",
+                tool.description(),
+                ui.ctx().format_shortcut(&tool.shortcut())
+            );
+            ui.selectable_value(&mut self.tool, tool, tool.name())
+                .on_hover_text(hint);
+        }
+    }
+
+    /// Picks the tool by its shortcut, splits at the playhead and toggles snapping.
+    pub(super) fn tool_shortcuts(&mut self, ctx: &egui::Context, state: &mut State) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (pressed, split, snap) = ctx.input_mut(|i| {
+            (
+                Tool::ALL
+                    .into_iter()
+                    .find(|tool| i.consume_shortcut(&tool.shortcut())),
+                i.consume_shortcut(&Self::SPLIT_SHORTCUT),
+                i.consume_shortcut(&Self::SNAP_SHORTCUT),
+            )
+        });
+        if let Some(tool) = pressed {
+            self.tool = tool;
+        }
+        if snap {
+            self.snapping = !self.snapping;
+        }
+        if split {
+            Self::split_at_playhead(state);
+        }
+    }
+}
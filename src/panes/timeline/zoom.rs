@@ -0,0 +1,130 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::{Timeline, TRACK_HEIGHT};
+use crate::{sequence::Sequence, state::State};
+
+/// Closest zoom, a frame is this wide.
+const MAX_PIXELS_PER_FRAME: f32 = 120.;
+/// Shortest span shown when zoomed out, so empty sequences can be zoomed out too.
+const MIN_SPAN_SECONDS: f64 = 60.;
+const SCROLLBAR_HEIGHT: f32 = 8.;
+
+impl Timeline {
+    pub const ZOOM_TO_FIT_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::NONE, Key::Backslash);
+
+    /// Zoom range, from a frame filling a good part of the lanes to the whole sequence
+    /// taking half of them.
+    fn zoom_range(sequence: &Sequence, lanes_width: f32) -> (f32, f32) {
+        let span = sequence
+            .frame_rate
+            .frames_to_seconds(sequence.duration())
+            .max(MIN_SPAN_SECONDS);
+        let min = lanes_width / (span * 2.) as f32;
+        let max = MAX_PIXELS_PER_FRAME * sequence.frame_rate.fps() as f32;
+        (min, max.max(min))
+    }
+
+    /// Zooms so the whole sequence fits into the lanes.
+    fn zoom_to_fit(&mut self, sequence: &Sequence, lanes_width: f32) {
+        let duration = sequence.frame_rate.frames_to_seconds(sequence.duration());
+        let (min, max) = Self::zoom_range(sequence, lanes_width);
+        self.pixels_per_second = if duration > 0. {
+            // Leave a bit of space after the end
+            (lanes_width * 0.95 / duration as f32).clamp(min, max)
+        } else {
+            100_f32.clamp(min, max)
+        };
+        self.scroll_x = 0.;
+    }
+
+    /// Scrolls with the mouse wheel, zooms around the pointer with Ctrl and scroll.
+    pub(super) fn scroll_zoom_ui(
+        &mut self,
+        ui: &egui::Ui,
+        rect: egui::Rect,
+        lanes_rect: egui::Rect,
+        state: &State,
+    ) {
+        let sequence = &state.project.sequence;
+        if std::mem::take(&mut self.fit_requested)
+            || (!ui.ctx().wants_keyboard_input()
+                && ui.input_mut(|i| i.consume_shortcut(&Self::ZOOM_TO_FIT_SHORTCUT)))
+        {
+            self.zoom_to_fit(sequence, lanes_rect.width());
+        }
+
+        let Some(pointer) = ui
+            .ctx()
+            .pointer_hover_pos()
+            .filter(|pos| rect.contains(*pos))
+        else {
+            return;
+        };
+        let (delta, zoom) = ui.input(|i| (i.smooth_scroll_delta, i.zoom_delta()));
+
+        if zoom != 1. {
+            // Keep the time under the pointer in place
+            let (min, max) = Self::zoom_range(sequence, lanes_rect.width());
+            let x = (pointer.x - lanes_rect.left()).max(0.);
+            let seconds = (x + self.scroll_x) / self.pixels_per_second;
+            self.pixels_per_second = (self.pixels_per_second * zoom).clamp(min, max);
+            self.scroll_x = (seconds * self.pixels_per_second - x).max(0.);
+        }
+
+        let content_height = sequence.tracks.len() as f32 * TRACK_HEIGHT;
+        let max_scroll_y = (content_height - lanes_rect.height()).max(0.);
+        self.scroll_x = (self.scroll_x - delta.x).max(0.);
+        self.scroll_y = (self.scroll_y - delta.y).clamp(0., max_scroll_y);
+    }
+
+    /// Bar at the bottom of the lanes showing the visible part of the sequence, can be dragged.
+    pub(super) fn scrollbar_ui(&mut self, ui: &egui::Ui, lanes_rect: egui::Rect, state: &State) {
+        let sequence = &state.project.sequence;
+        let end = self.frame_to_x(sequence.duration(), sequence.frame_rate, 0.) + self.scroll_x;
+        // Room to scroll a bit past the end
+        let content_width = (end + lanes_rect.width() / 2.).max(self.scroll_x + lanes_rect.width());
+        if content_width <= lanes_rect.width() {
+            return;
+        }
+
+        let track = egui::Rect::from_min_max(
+            egui::pos2(lanes_rect.left(), lanes_rect.bottom() - SCROLLBAR_HEIGHT),
+            lanes_rect.max,
+        );
+        let scale = track.width() / content_width;
+        let handle = egui::Rect::from_min_size(
+            egui::pos2(track.left() + self.scroll_x * scale, track.top()),
+            egui::vec2((lanes_rect.width() * scale).max(20.), SCROLLBAR_HEIGHT),
+        );
+        let response = ui.interact(handle, ui.id().with("scrollbar"), egui::Sense::drag());
+        if response.dragged() {
+            self.scroll_x = (self.scroll_x + response.drag_delta().x / scale).max(0.);
+        }
+
+        let visuals = ui.visuals();
+        let color = if response.hovered() || response.dragged() {
+            visuals.widgets.hovered.bg_fill
+        } else {
+            visuals.widgets.inactive.bg_fill
+        };
+        let painter = ui.painter_at(lanes_rect);
+        painter.rect_filled(track, 0., visuals.extreme_bg_color.gamma_multiply(0.8));
+        painter.rect_filled(handle.shrink(1.), 3., color);
+    }
+
+    /// Zoom to fit button and timecode format, right to left.
+    pub(super) fn zoom_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        if ui
+            .button("Zoom to fit")
+            .on_hover_text(format!(
+                "Show the whole sequence ({}), zoom with Ctrl and scroll",
+                ui.ctx().format_shortcut(&Self::ZOOM_TO_FIT_SHORTCUT)
+            ))
+            .clicked()
+        {
+            self.fit_requested = true;
+        }
+        self.timecode_format_ui(ui, state);
+    }
+}
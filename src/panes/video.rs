@@ -1,8 +1,193 @@
 use super::PaneBehavior;
+use crate::{
+    playback::Playback,
+    project::{File, FileType},
+    sequence::{FrameRate, TrackKind},
+    state::State,
+};
+
+/// Aspect ratio of the preview.
+const ASPECT_RATIO: f32 = 16. / 9.;
+const TRANSPORT_HEIGHT: f32 = 32.;
+
+#[derive(Default)]
+pub struct Video {
+    /// Uri of the image sequence frame shown last, forgotten once another frame is shown.
+    sequence_frame: Option<String>,
+}
+impl Video {
+    fn preview_ui(&mut self, ui: &mut egui::Ui, state: &State) {
+        let available = ui.available_size() - egui::vec2(0., TRANSPORT_HEIGHT);
+        if available.x <= 0. || available.y <= 0. {
+            return;
+        }
+
+        let size = if available.x / available.y > ASPECT_RATIO {
+            egui::vec2(available.y * ASPECT_RATIO, available.y)
+        } else {
+            egui::vec2(available.x, available.x / ASPECT_RATIO)
+        };
+        let (outer, _) = ui.allocate_exact_size(available, egui::Sense::hover());
+        let rect = egui::Rect::from_center_size(outer.center(), size);
+        ui.painter().rect_filled(rect, 0., egui::Color32::BLACK);
+
+        let sequence = &state.project.sequence;
+        let playhead = state.playback.playhead();
+        let Some(clip) = sequence.clip_at(TrackKind::Video, playhead) else {
+            return;
+        };
+        let Some(file) = state.project.file(clip.file) else {
+            return;
+        };
+
+        if file.is_offline() {
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                format!("Media offline\n{}", file.name),
+                egui::FontId::proportional(16.),
+                ui.visuals().error_fg_color,
+            );
+            return;
+        }
+
+        match file.r#type {
+            FileType::Image => {
+                // Mapped content is copied for egui, so it's only handed over once
+                let uri = file.id.uri(&file.name);
+                if ui.ctx().try_load_bytes(&uri).is_err() {
+                    ui.ctx()
+                        .include_bytes(uri.clone(), file.content.to_egui_bytes());
+                }
+                egui::Image::new(uri)
+                    .maintain_aspect_ratio(true)
+                    .paint_at(ui, rect);
+            }
+            FileType::Video if file.image_sequence.is_some() => {
+                let source_frame = clip.source_in + playhead - clip.start;
+                self.sequence_frame_ui(ui, rect, file, sequence.frame_rate, source_frame);
+            }
+            // TODO: Decode video frames
+            FileType::Video | FileType::Sound => {
+                let source_frame = clip.source_in + playhead - clip.start;
+                ui.painter().text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!(
+                        "{}\n{}",
+                        file.name,
+                        sequence
+                            .frame_rate
+                            .format(source_frame, state.project.settings.timecode_format)
+                    ),
+                    egui::FontId::proportional(16.),
+                    egui::Color32::GRAY,
+                );
+            }
+        }
+    }
+
+    /// Frame of the image sequence at the source frame, in frames of the sequence.
+    fn sequence_frame_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        rect: egui::Rect,
+        file: &File,
+        frame_rate: FrameRate,
+        source_frame: i64,
+    ) {
+        let Some(image_sequence) = &file.image_sequence else {
+            return;
+        };
+        let seconds = frame_rate.frames_to_seconds(source_frame);
+        let Some((index, content)) = image_sequence.content_at(seconds) else {
+            return;
+        };
+        let name = &image_sequence.frames[index];
+        if content.is_empty() {
+            ui.painter().text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                format!("Frame offline\n{name}"),
+                egui::FontId::proportional(16.),
+                ui.visuals().error_fg_color,
+            );
+            return;
+        }
+
+        // Decoded frames would pile up in egui's cache during playback
+        let uri = file.id.uri(name);
+        if self.sequence_frame.as_ref() != Some(&uri) {
+            if let Some(previous) = self.sequence_frame.replace(uri.clone()) {
+                ui.ctx().forget_image(&previous);
+            }
+            ui.ctx().include_bytes(uri.clone(), content.to_egui_bytes());
+        }
+        egui::Image::new(uri)
+            .maintain_aspect_ratio(true)
+            .paint_at(ui, rect);
+    }
+
+    fn transport_ui(ui: &mut egui::Ui, state: &mut State) {
+        let sequence = &state.project.sequence;
+        let playback = &mut state.playback;
+
+        let hint = |ui: &egui::Ui, text: &str, shortcut: &egui::KeyboardShortcut| {
+            format!("{text} ({})", ui.ctx().format_shortcut(shortcut))
+        };
+
+        ui.horizontal_centered(|ui| {
+            let text = hint(ui, "Go to start", &Playback::GO_TO_START_SHORTCUT);
+            if ui.button("⏮").on_hover_text(text).clicked() {
+                playback.go_to_start(ui.ctx());
+            }
+            let text = hint(ui, "Previous frame", &Playback::STEP_BACKWARD_SHORTCUT);
+            if ui.button("⏪").on_hover_text(text).clicked() {
+                playback.step(-1, sequence);
+            }
+            let text = hint(ui, "Play/Pause", &Playback::PLAY_PAUSE_SHORTCUT);
+            if playback.is_playing() {
+                if ui.button("⏸").on_hover_text(text).clicked() {
+                    playback.pause();
+                }
+            } else if ui.button("▶").on_hover_text(text).clicked() {
+                playback.play(ui.ctx(), sequence);
+            }
+            if ui.button("⏹").on_hover_text("Stop").clicked() {
+                playback.stop();
+            }
+            let text = hint(ui, "Next frame", &Playback::STEP_FORWARD_SHORTCUT);
+            if ui.button("⏩").on_hover_text(text).clicked() {
+                playback.step(1, sequence);
+            }
+            let text = hint(ui, "Go to end", &Playback::GO_TO_END_SHORTCUT);
+            if ui.button("⏭").on_hover_text(text).clicked() {
+                playback.go_to_end(sequence);
+            }
+            ui.toggle_value(&mut playback.looping, "🔁")
+                .on_hover_text("Loop");
+        });
+    }
+}
 
-pub struct Video {}
 impl PaneBehavior for Video {
-    fn ui(&mut self, ui: &mut egui::Ui) {
-        ui.label("Video");
+    fn ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        self.preview_ui(ui, state);
+        Self::transport_ui(ui, state);
+    }
+
+    fn top_bar_ui(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let sequence = &state.project.sequence;
+        let format = state.project.settings.timecode_format;
+        ui.label(
+            egui::RichText::new(format!(
+                "{} / {}",
+                sequence
+                    .frame_rate
+                    .format(state.playback.playhead(), format),
+                sequence.frame_rate.format(sequence.duration(), format)
+            ))
+            .monospace(),
+        );
     }
 }
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sequence::Sequence;
+
+/// Playback clock driving the playhead.
+///
+/// While playing, the playhead is derived from the time elapsed since playback started
+/// instead of being advanced frame by frame, so it never drifts from the sequence frame grid.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Playback {
+    /// Current frame of the playhead.
+    playhead: i64,
+    pub looping: bool,
+
+    /// Time (`egui::InputState::time`) and playhead when playback started.
+    #[serde(skip)]
+    anchor: Option<(f64, i64)>,
+    /// Frame where playback started, `stop` returns there.
+    #[serde(skip)]
+    start_frame: i64,
+}
+impl Playback {
+    pub const PLAY_PAUSE_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Space);
+    pub const STEP_BACKWARD_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::ArrowLeft);
+    pub const STEP_FORWARD_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::ArrowRight);
+    pub const GO_TO_START_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::Home);
+    pub const GO_TO_END_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::NONE, egui::Key::End);
+
+    pub fn playhead(&self) -> i64 {
+        self.playhead
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    pub fn play(&mut self, ctx: &egui::Context, sequence: &Sequence) {
+        if self.is_playing() {
+            return;
+        }
+
+        // Start over when at the end
+        if self.playhead >= sequence.duration() {
+            self.playhead = 0;
+        }
+        self.start_frame = self.playhead;
+        self.anchor = Some((ctx.input(|i| i.time), self.playhead));
+        ctx.request_repaint();
+    }
+
+    pub fn pause(&mut self) {
+        self.anchor = None;
+    }
+
+    pub fn toggle(&mut self, ctx: &egui::Context, sequence: &Sequence) {
+        if self.is_playing() {
+            self.pause();
+        } else {
+            self.play(ctx, sequence);
+        }
+    }
+
+    /// Pauses and returns to the frame where playback started.
+    pub fn stop(&mut self) {
+        self.pause();
+        self.playhead = self.start_frame;
+    }
+
+    /// Moves playhead, keeps playing from the new position.
+    pub fn seek(&mut self, ctx: &egui::Context, frame: i64) {
+        self.playhead = frame.max(0);
+        if self.is_playing() {
+            self.start_frame = self.playhead;
+            self.anchor = Some((ctx.input(|i| i.time), self.playhead));
+        }
+    }
+
+    /// Pauses and moves playhead by given amount of frames.
+    pub fn step(&mut self, frames: i64, sequence: &Sequence) {
+        self.pause();
+        self.playhead = (self.playhead + frames).clamp(0, sequence.duration());
+    }
+
+    pub fn go_to_start(&mut self, ctx: &egui::Context) {
+        self.seek(ctx, 0);
+    }
+
+    pub fn go_to_end(&mut self, sequence: &Sequence) {
+        self.pause();
+        self.playhead = sequence.duration();
+    }
+
+    /// Advances playhead, called once per frame.
+    pub fn update(&mut self, ctx: &egui::Context, sequence: &Sequence) {
+        let Some((anchor_time, anchor_frame)) = self.anchor else {
+            return;
+        };
+
+        let frame_rate = sequence.frame_rate;
+        let duration = sequence.duration();
+        let now = ctx.input(|i| i.time);
+
+        let elapsed =
+            ((now - anchor_time) * frame_rate.num as f64 / frame_rate.den as f64).floor() as i64;
+        let mut frame = anchor_frame + elapsed;
+
+        if frame >= duration {
+            if self.looping && duration > 0 {
+                // Re-anchor at the start of the sequence when the end was reached
+                let end_time = anchor_time + frame_rate.frames_to_seconds(duration - anchor_frame);
+                self.anchor = Some((end_time, 0));
+                frame = (frame - duration) % duration;
+            } else {
+                self.anchor = None;
+                self.playhead = duration;
+                return;
+            }
+        }
+        self.playhead = frame;
+
+        // Wake up exactly when the next frame is due
+        let (anchor_time, anchor_frame) = self.anchor.unwrap_or((anchor_time, anchor_frame));
+        let next = anchor_time + frame_rate.frames_to_seconds(frame + 1 - anchor_frame);
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64((next - now).max(0.)));
+    }
+
+    pub fn shortcuts(&mut self, ctx: &egui::Context, sequence: &Sequence) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (play_pause, step_backward, step_forward, go_to_start, go_to_end) =
+            ctx.input_mut(|i| {
+                (
+                    i.consume_shortcut(&Self::PLAY_PAUSE_SHORTCUT),
+                    i.consume_shortcut(&Self::STEP_BACKWARD_SHORTCUT),
+                    i.consume_shortcut(&Self::STEP_FORWARD_SHORTCUT),
+                    i.consume_shortcut(&Self::GO_TO_START_SHORTCUT),
+                    i.consume_shortcut(&Self::GO_TO_END_SHORTCUT),
+                )
+            });
+
+        if play_pause {
+            self.toggle(ctx, sequence);
+        }
+        if step_backward {
+            self.step(-1, sequence);
+        }
+        if step_forward {
+            self.step(1, sequence);
+        }
+        if go_to_start {
+            self.go_to_start(ctx);
+        }
+        if go_to_end {
+            self.go_to_end(sequence);
+        }
+    }
+}
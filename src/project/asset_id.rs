@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// Unique and persistent identifier of an imported file or a bin.
+///
+/// Files with the same name or content still get different ids.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AssetId(uuid::Uuid);
+impl AssetId {
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    /// Uri of an image in egui's cache, the name keeps the extension for image loaders.
+    pub fn uri(self, name: &str) -> String {
+        format!("bytes://{}/{name}", self.0)
+    }
+}
+impl Default for AssetId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::AssetId;
+
+/// Folder of the Files pane, holding files and other bins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bin {
+    pub id: AssetId,
+    pub name: String,
+    /// `None` for bins at the top level.
+    pub parent: Option<AssetId>,
+}
+
+/// Name and bin of a file.
+#[derive(Clone, Debug)]
+pub struct FilePlace {
+    pub id: AssetId,
+    pub name: String,
+    pub bin: Option<AssetId>,
+}
+
+/// Names, order and bins of all files together with all bins.
+///
+/// Unlike files it doesn't hold their content, so it's cheap to keep as an undo step.
+#[derive(Clone, Debug)]
+pub struct Organization {
+    pub bins: Vec<Bin>,
+    /// Places of files in the order they are shown.
+    pub files: Vec<FilePlace>,
+}
+impl Organization {
+    pub fn file_mut(&mut self, id: AssetId) -> Option<&mut FilePlace> {
+        self.files.iter_mut().find(|file| file.id == id)
+    }
+
+    pub fn bin_mut(&mut self, id: AssetId) -> Option<&mut Bin> {
+        self.bins.iter_mut().find(|bin| bin.id == id)
+    }
+
+    /// Moves file right before another one, or to the end when `before` is `None`.
+    pub fn move_file(&mut self, id: AssetId, before: Option<AssetId>) {
+        let Some(index) = self.files.iter().position(|file| file.id == id) else {
+            return;
+        };
+        let file = self.files.remove(index);
+        let index = before
+            .and_then(|before| self.files.iter().position(|file| file.id == before))
+            .unwrap_or(self.files.len());
+        self.files.insert(index, file);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, hash::Hasher, ops::Deref, sync::Arc};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Length of the parts hashed at the start, middle and end of the content.
+const FINGERPRINT_SAMPLE: usize = 1024 * 1024;
+
+#[derive(Clone)]
+enum Bytes {
+    Memory(Arc<[u8]>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Mapped(Arc<memmap2::Mmap>),
+}
+
+/// Content of an imported file, cheap to clone and share with background tasks.
+///
+/// On native files are memory mapped, so only the ranges being read are loaded and the
+/// system can drop them again. Files without a path, e.g. on the web, are kept in memory.
+#[derive(Clone, Default)]
+pub struct Content(Option<Bytes>);
+impl Content {
+    /// Maps the file, nothing is read until the content is used.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn map(path: &std::path::Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::default());
+        }
+        // SAFETY: Media is only read, changing it on disk while the project is open gives
+        // wrong content and truncating it can crash, which is what other editors do too
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(Self(Some(Bytes::Mapped(Arc::new(map)))))
+    }
+
+    /// Whether the content isn't saved in the project.
+    ///
+    /// Mapped content is read from its file again, empty content has nothing to save.
+    pub fn skip_embedding(&self) -> bool {
+        !matches!(self.0, Some(Bytes::Memory(_)))
+    }
+
+    /// Hash of the size and a few samples of the content, quick even for long footage.
+    ///
+    /// Stable between runs and platforms, so it can be saved and compared later.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = fnv::FnvHasher::default();
+        hasher.write(&(self.len() as u64).to_le_bytes());
+        if self.len() <= 3 * FINGERPRINT_SAMPLE {
+            hasher.write(self);
+        } else {
+            let middle = (self.len() - FINGERPRINT_SAMPLE) / 2;
+            for start in [0, middle, self.len() - FINGERPRINT_SAMPLE] {
+                hasher.write(&self[start..start + FINGERPRINT_SAMPLE]);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Bytes for egui image loaders, mapped content is copied.
+    pub fn to_egui_bytes(&self) -> egui::load::Bytes {
+        match &self.0 {
+            Some(Bytes::Memory(bytes)) => egui::load::Bytes::Shared(bytes.clone()),
+            _ => egui::load::Bytes::Shared(self.deref().into()),
+        }
+    }
+}
+
+impl Deref for Content {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.0 {
+            None => &[],
+            Some(Bytes::Memory(bytes)) => bytes,
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(Bytes::Mapped(map)) => map,
+        }
+    }
+}
+
+impl From<Arc<[u8]>> for Content {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        if bytes.is_empty() {
+            Self::default()
+        } else {
+            Self(Some(Bytes::Memory(bytes)))
+        }
+    }
+}
+
+impl From<Vec<u8>> for Content {
+    fn from(bytes: Vec<u8>) -> Self {
+        Arc::<[u8]>::from(bytes).into()
+    }
+}
+
+/// Content held in memory is embedded in the project, as base64 in RON.
+impl Serialize for Content {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+        impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(bytes.to_vec())
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(bytes)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Vec<u8>, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        Ok(deserializer.deserialize_byte_buf(BytesVisitor)?.into())
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ProjectError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+    /// Project was saved by a newer version of the editor.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Parse(err) => write!(f, "invalid project file: {err}"),
+            Self::Serialize(err) => write!(f, "can't serialize project: {err}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "project version {version} is not supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<std::io::Error> for ProjectError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for ProjectError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<ron::Error> for ProjectError {
+    fn from(err: ron::Error) -> Self {
+        Self::Serialize(err)
+    }
+}
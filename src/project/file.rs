@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::{AssetId, Content, ImageSequence};
+use crate::media::MediaInfo;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileType {
+    Image,
+    Video,
+    Sound,
+}
+impl FileType {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Image => "Image",
+            Self::Video => "Video",
+            Self::Sound => "Sound",
+        }
+    }
+}
+
+/// Imported media.
+#[derive(Serialize, Deserialize)]
+pub struct File {
+    #[serde(default)]
+    pub id: AssetId,
+    pub name: String,
+    /// Where the file was imported from, `None` on web where paths aren't available.
+    pub path: Option<PathBuf>,
+    /// Path relative to the project file, used when the media moved along with the project.
+    #[serde(default)]
+    pub relative_path: Option<PathBuf>,
+    /// Mapped from the path on native, only files without a path are held in memory.
+    ///
+    /// Content held in memory is saved in the project, there's nowhere else to read it from.
+    #[serde(default, skip_serializing_if = "Content::skip_embedding")]
+    pub content: Content,
+    pub r#type: FileType,
+    #[serde(skip)]
+    pub video_thumbnail: Option<Vec<u8>>,
+    #[serde(default)]
+    pub info: MediaInfo,
+    /// Bin the file is in, `None` for the top level.
+    #[serde(default)]
+    pub bin: Option<AssetId>,
+    /// Size of the content in bytes.
+    #[serde(default)]
+    pub size: u64,
+    /// When the file was imported, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub imported: Option<u64>,
+    /// Fingerprint of the content, see `Content::fingerprint`.
+    #[serde(default)]
+    pub hash: Option<u64>,
+    /// Frames of a video imported from numbered images, the path is of the first frame.
+    #[serde(default)]
+    pub image_sequence: Option<ImageSequence>,
+}
+impl File {
+    /// Whether the content couldn't be read from the path or wasn't kept, e.g. after a restart.
+    pub fn is_offline(&self) -> bool {
+        let lost_frames = self
+            .image_sequence
+            .as_ref()
+            .is_some_and(ImageSequence::is_offline);
+        lost_frames || self.content.is_empty() && (self.path.is_some() || self.size > 0)
+    }
+
+    /// Maps content from the path, the content is empty if it can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(&mut self) -> std::io::Result<()> {
+        self.content = Content::default();
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let content = Content::map(path)?;
+
+        if let Some(image_sequence) = &mut self.image_sequence {
+            image_sequence.load(path);
+            self.size = image_sequence.size();
+            self.hash
+                .get_or_insert_with(|| image_sequence.fingerprint());
+            self.content = content;
+            return Ok(());
+        }
+
+        // Projects saved before probing only had the duration
+        let only_duration = MediaInfo {
+            duration: self.info.duration,
+            ..Default::default()
+        };
+        if self.info == only_duration {
+            self.info = crate::media::probe::probe(&content).unwrap_or_default();
+            self.info.duration = self.info.duration.or(only_duration.duration);
+        }
+        self.size = content.len() as u64;
+        self.hash.get_or_insert_with(|| content.fingerprint());
+        self.content = content;
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use super::Content;
+use crate::{media::MediaInfo, sequence::FrameRate};
+
+/// Numbered images imported as one video, e.g. `shot_0001.png` to `shot_2000.png`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ImageSequence {
+    /// File names of the frames in order, all in the folder of the first frame.
+    pub frames: Vec<String>,
+    pub frame_rate: FrameRate,
+    /// Content of each frame, empty for frames which couldn't be read.
+    ///
+    /// Frames held in memory are saved in the project like the content of files.
+    #[serde(default, skip_serializing_if = "ImageSequence::skip_embedding")]
+    pub content: Vec<Content>,
+}
+impl ImageSequence {
+    fn skip_embedding(content: &[Content]) -> bool {
+        content.iter().all(Content::skip_embedding)
+    }
+
+    /// Whether content of some frames wasn't kept, e.g. in a project saved without it.
+    pub fn is_offline(&self) -> bool {
+        self.content.len() < self.frames.len()
+    }
+
+    /// Length in seconds.
+    pub fn duration(&self) -> f64 {
+        self.frame_rate.frames_to_seconds(self.frames.len() as i64)
+    }
+
+    /// Index of the frame shown at the time, the last frame is held past the end.
+    pub fn frame_at(&self, seconds: f64) -> usize {
+        // Tolerance, so times computed from the same frame rate don't fall to the previous frame
+        let frame = (seconds * self.frame_rate.fps() + 1e-6).floor().max(0.) as usize;
+        frame.min(self.frames.len().saturating_sub(1))
+    }
+
+    /// Content of the frame shown at the time, empty if it couldn't be read.
+    pub fn content_at(&self, seconds: f64) -> Option<(usize, &Content)> {
+        let index = self.frame_at(seconds);
+        Some((index, self.content.get(index)?))
+    }
+
+    /// Frame around 10% of the sequence, used for thumbnails like with videos.
+    pub fn thumbnail(&self) -> Option<&Content> {
+        self.content.get(self.content.len() / 10)
+    }
+
+    /// Size of all frames in bytes.
+    pub fn size(&self) -> u64 {
+        self.content.iter().map(|frame| frame.len() as u64).sum()
+    }
+
+    /// Hash of the frame count and the first, middle and last frames.
+    ///
+    /// Reading every frame would take long for big sequences, see `Content::fingerprint`.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = fnv::FnvHasher::default();
+        hasher.write(&(self.content.len() as u64).to_le_bytes());
+        let last = self.content.len().saturating_sub(1);
+        for index in [0, last / 2, last] {
+            if let Some(frame) = self.content.get(index) {
+                hasher.write(&frame.fingerprint().to_le_bytes());
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Properties of the first frame completed with those of the whole sequence.
+    pub fn info(&self, first_frame: MediaInfo) -> MediaInfo {
+        MediaInfo {
+            format: format!("{} sequence", first_frame.format).trim().to_owned(),
+            duration: Some(self.duration()),
+            frame_rate: Some(self.frame_rate.fps()),
+            video_codec: Some(first_frame.format.clone()).filter(|format| !format.is_empty()),
+            ..first_frame
+        }
+    }
+
+    /// Maps the frames next to the first one, frames which can't be read stay empty.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(&mut self, first_frame: &std::path::Path) {
+        let folder = first_frame.parent().unwrap_or(first_frame);
+        self.content = self
+            .frames
+            .iter()
+            .map(|name| {
+                Content::map(&folder.join(name))
+                    .map_err(|err| log::warn!("Frame {name} is offline: {err}"))
+                    .unwrap_or_default()
+            })
+            .collect();
+    }
+}
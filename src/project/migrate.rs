@@ -0,0 +1,167 @@
+//! Upgrades project files saved by older versions.
+
+use serde::Deserialize;
+
+use super::{File, Project, ProjectError, Settings};
+use crate::sequence::{self, FrameRate, TrackKind};
+
+/// Only the version, to pick how the rest is read.
+#[derive(Deserialize)]
+pub struct Versioned {
+    #[serde(default)]
+    pub version: u32,
+}
+
+/// Version 1 referenced files by their index instead of `AssetId`.
+mod v1 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    pub struct Project {
+        pub files: Vec<File>,
+        pub sequence: Sequence,
+        #[serde(default)]
+        pub settings: Settings,
+    }
+
+    /// Files had only the duration, read apart because the rest is read as `File`.
+    #[derive(Deserialize)]
+    pub struct Durations {
+        pub files: Vec<Duration>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Duration {
+        #[serde(default)]
+        pub duration: Option<f64>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Sequence {
+        pub name: String,
+        pub frame_rate: FrameRate,
+        pub tracks: Vec<Track>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Track {
+        pub name: String,
+        pub kind: TrackKind,
+        pub clips: Vec<Clip>,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Clip {
+        pub file: usize,
+        pub start: i64,
+        pub source_in: i64,
+        pub source_out: i64,
+    }
+}
+
+pub fn from_v1(text: &str) -> Result<Project, ProjectError> {
+    let mut old: v1::Project = ron::from_str(text)?;
+    let durations: v1::Durations = ron::from_str(text)?;
+    for (file, old) in old.files.iter_mut().zip(durations.files) {
+        file.info.duration = old.duration;
+    }
+
+    // Files got new ids while being read
+    let ids: Vec<_> = old.files.iter().map(|file| file.id).collect();
+    let tracks = old
+        .sequence
+        .tracks
+        .into_iter()
+        .map(|track| sequence::Track {
+            name: track.name,
+            kind: track.kind,
+            clips: track
+                .clips
+                .into_iter()
+                // Clips of missing files can't be restored
+                .filter_map(|clip| {
+                    Some(sequence::Clip {
+                        file: *ids.get(clip.file)?,
+                        start: clip.start,
+                        source_in: clip.source_in,
+                        source_out: clip.source_out,
+                        link: None,
+                        markers: Vec::new(),
+                    })
+                })
+                .collect(),
+            locked: false,
+        })
+        .collect();
+
+    Ok(Project {
+        files: old.files,
+        sequence: sequence::Sequence {
+            name: old.sequence.name,
+            frame_rate: old.sequence.frame_rate,
+            tracks,
+            ..Default::default()
+        },
+        settings: old.settings,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::PROJECT_VERSION, *};
+
+    const V1: &str = r#"(
+        version: 1,
+        files: [
+            (name: "music.wav", path: None, type: Sound, duration: Some(12.5)),
+            (name: "title.png", path: None, type: Image),
+        ],
+        sequence: (
+            name: "Edit",
+            frame_rate: (num: 25, den: 1),
+            tracks: [
+                (name: "V1", kind: Video, clips: [
+                    (file: 1, start: 0, source_in: 0, source_out: 50),
+                    (file: 7, start: 50, source_in: 0, source_out: 25),
+                ]),
+                (name: "A1", kind: Audio, clips: [
+                    (file: 0, start: 10, source_in: 5, source_out: 40),
+                ]),
+            ],
+        ),
+    )"#;
+
+    #[test]
+    fn clips_refer_to_file_ids() {
+        let project = from_v1(V1).unwrap();
+        let sequence = &project.sequence;
+        assert_eq!(sequence.name, "Edit");
+        assert_eq!(sequence.frame_rate, FrameRate::new(25, 1));
+        assert_eq!(sequence.tracks.len(), 2);
+
+        // Clip of the missing eighth file is dropped
+        let video = &sequence.tracks[0];
+        assert_eq!(video.clips.len(), 1);
+        assert_eq!(video.clips[0].file, project.files[1].id);
+        let sound = &sequence.tracks[1].clips[0];
+        assert_eq!(sound.file, project.files[0].id);
+        assert_eq!(project.files[0].info.duration, Some(12.5));
+        assert_eq!(project.files[1].info.duration, None);
+        assert_eq!(
+            (sound.start, sound.source_in, sound.source_out),
+            (10, 5, 40)
+        );
+    }
+
+    #[test]
+    fn old_versions_are_migrated_on_load() {
+        let project = Project::from_ron(V1).unwrap();
+        assert_eq!(project.version, PROJECT_VERSION);
+        assert_eq!(project.files.len(), 2);
+
+        // Files without a version are of version 1
+        let unversioned = V1.replacen("version: 1,", "", 1);
+        assert_eq!(Project::from_ron(&unversioned).unwrap().files.len(), 2);
+    }
+}
@@ -0,0 +1,273 @@
+mod asset_id;
+pub use asset_id::AssetId;
+mod bin;
+pub use bin::{Bin, FilePlace, Organization};
+mod content;
+pub use content::Content;
+mod error;
+pub use error::ProjectError;
+mod file;
+pub use file::{File, FileType};
+mod image_sequence;
+pub use image_sequence::ImageSequence;
+mod migrate;
+mod settings;
+pub use settings::Settings;
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sequence::Sequence;
+
+/// Extension of project files.
+pub const PROJECT_EXTENSION: &str = "vep";
+/// Version of the project file format, increased on breaking changes.
+pub const PROJECT_VERSION: u32 = 2;
+
+/// Everything the user edits, saved as a project file.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub version: u32,
+    pub files: Vec<File>,
+    pub bins: Vec<Bin>,
+    pub sequence: Sequence,
+    pub settings: Settings,
+}
+impl Default for Project {
+    fn default() -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            files: Vec::new(),
+            bins: Vec::new(),
+            sequence: Sequence::default(),
+            settings: Settings::default(),
+        }
+    }
+}
+impl Project {
+    pub fn to_ron(&self) -> Result<String, ProjectError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ProjectError> {
+        let migrate::Versioned { version } = ron::from_str(text)?;
+        if version > PROJECT_VERSION {
+            return Err(ProjectError::UnsupportedVersion(version));
+        }
+
+        let mut project = match version {
+            0 | 1 => migrate::from_v1(text)?,
+            _ => ron::from_str(text)?,
+        };
+
+        project.version = PROJECT_VERSION;
+        Ok(project)
+    }
+
+    pub fn file(&self, id: AssetId) -> Option<&File> {
+        self.files.iter().find(|file| file.id == id)
+    }
+
+    pub fn bin(&self, id: AssetId) -> Option<&Bin> {
+        self.bins.iter().find(|bin| bin.id == id)
+    }
+
+    /// Bin the file is shown in, files in bins which no longer exist are at the top level.
+    pub fn file_bin(&self, file: &File) -> Option<AssetId> {
+        file.bin.filter(|bin| self.bin(*bin).is_some())
+    }
+
+    /// Bins from the top level down to the given one, empty for the top level.
+    pub fn bin_path(&self, id: Option<AssetId>) -> Vec<&Bin> {
+        let mut path = Vec::new();
+        let mut next = id.and_then(|id| self.bin(id));
+        // Limit the depth in case a bin ended up inside itself
+        while let Some(bin) = next.filter(|_| path.len() <= self.bins.len()) {
+            path.push(bin);
+            next = bin.parent.and_then(|parent| self.bin(parent));
+        }
+        path.reverse();
+        path
+    }
+
+    /// Whether the bin is the ancestor or the same bin as `id`.
+    pub fn bin_contains(&self, ancestor: AssetId, id: Option<AssetId>) -> bool {
+        self.bin_path(id).iter().any(|bin| bin.id == ancestor)
+    }
+
+    pub fn organization(&self) -> Organization {
+        Organization {
+            bins: self.bins.clone(),
+            files: self
+                .files
+                .iter()
+                .map(|file| FilePlace {
+                    id: file.id,
+                    name: file.name.clone(),
+                    bin: file.bin,
+                })
+                .collect(),
+        }
+    }
+
+    /// Renames, reorders and moves files to bins as described by the organization.
+    pub fn set_organization(&mut self, organization: &Organization) {
+        self.bins = organization.bins.clone();
+
+        let mut files = std::mem::take(&mut self.files);
+        for place in &organization.files {
+            if let Some(index) = files.iter().position(|file| file.id == place.id) {
+                let mut file = files.remove(index);
+                file.name = place.name.clone();
+                file.bin = place.bin;
+                self.files.push(file);
+            }
+        }
+        // Files unknown to the organization stay at the end
+        self.files.append(&mut files);
+    }
+
+    /// Stores paths of media relative to the project file, which is about to be saved there.
+    pub fn set_location(&mut self, project_path: &Path) {
+        let directory = project_path.parent();
+        for file in &mut self.files {
+            file.relative_path = file
+                .path
+                .as_deref()
+                .zip(directory)
+                .and_then(|(path, directory)| relative_path(path, directory));
+        }
+    }
+
+    /// Files whose content couldn't be read from their path.
+    pub fn offline_files(&self) -> impl Iterator<Item = &File> {
+        self.files.iter().filter(|file| file.is_offline())
+    }
+
+    /// Maps content of files referenced by path, which isn't stored in the project.
+    ///
+    /// Media moved along with the project is found by its path relative to the project file.
+    pub fn load_files(
+        &mut self,
+        #[cfg_attr(target_arch = "wasm32", allow(unused_variables))] project_path: Option<&Path>,
+    ) {
+        #[cfg(not(target_arch = "wasm32"))]
+        for file in &mut self.files {
+            let Some(path) = &file.path else {
+                continue;
+            };
+            if !path.is_file() {
+                let moved = project_path
+                    .and_then(Path::parent)
+                    .zip(file.relative_path.as_ref())
+                    .map(|(directory, relative)| directory.join(relative))
+                    .filter(|path| path.is_file());
+                if let Some(moved) = moved {
+                    log::info!("Found {} at {}", file.name, moved.display());
+                    file.path = Some(moved);
+                }
+            }
+
+            if let Err(err) = file.load() {
+                log::warn!("{} is offline: {err}", file.name);
+            }
+        }
+    }
+}
+
+/// Path leading from the directory to the same place, `None` if they are on different drives.
+fn relative_path(path: &Path, directory: &Path) -> Option<PathBuf> {
+    let mut path = path.components().peekable();
+    let mut directory = directory.components().peekable();
+    if !matches!(path.peek(), Some(Component::Prefix(_) | Component::RootDir))
+        || path.peek() != directory.peek()
+    {
+        return None;
+    }
+
+    while path.peek().is_some() && path.peek() == directory.peek() {
+        path.next();
+        directory.next();
+    }
+    let mut relative: PathBuf = directory.map(|_| Component::ParentDir).collect();
+    relative.extend(path);
+    Some(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: Option<&str>, content: Content) -> File {
+        File {
+            id: AssetId::new(),
+            name: "clip.wav".to_owned(),
+            path: path.map(PathBuf::from),
+            relative_path: None,
+            size: content.len() as u64,
+            content,
+            r#type: FileType::Sound,
+            video_thumbnail: None,
+            info: Default::default(),
+            bin: None,
+            imported: None,
+            hash: None,
+            image_sequence: None,
+        }
+    }
+
+    #[test]
+    fn files_without_path_are_embedded() {
+        let project = Project {
+            files: vec![file(None, vec![0, 1, 2, 255].into())],
+            ..Default::default()
+        };
+
+        let project = Project::from_ron(&project.to_ron().unwrap()).unwrap();
+        assert_eq!(&*project.files[0].content, &[0, 1, 2, 255]);
+        assert!(!project.files[0].is_offline());
+    }
+
+    #[test]
+    fn files_with_lost_content_are_offline() {
+        let mut lost = file(None, vec![1, 2, 3].into());
+        lost.content = Content::default();
+        assert!(lost.is_offline());
+        assert!(file(Some("/missing/clip.wav"), Content::default()).is_offline());
+        assert!(!file(None, Content::default()).is_offline());
+    }
+
+    #[test]
+    fn image_sequences_without_path_are_embedded() {
+        let mut sequence = file(None, vec![1].into());
+        sequence.r#type = FileType::Video;
+        sequence.image_sequence = Some(ImageSequence {
+            frames: vec!["shot_1.png".to_owned(), "shot_2.png".to_owned()],
+            frame_rate: Default::default(),
+            content: vec![vec![1].into(), vec![2].into()],
+        });
+        let project = Project {
+            files: vec![sequence],
+            ..Default::default()
+        };
+
+        let mut project = Project::from_ron(&project.to_ron().unwrap()).unwrap();
+        let image_sequence = project.files[0].image_sequence.as_mut().unwrap();
+        assert_eq!(&*image_sequence.content[1], &[2]);
+        assert!(!project.files[0].is_offline());
+
+        // Frames lost e.g. with the stored app state
+        project.files[0]
+            .image_sequence
+            .as_mut()
+            .unwrap()
+            .content
+            .clear();
+        assert!(project.files[0].is_offline());
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sequence::TimecodeFormat;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Length of clips created from images, in seconds.
+    pub still_duration: f64,
+    pub timecode_format: TimecodeFormat,
+}
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            still_duration: 5.,
+            timecode_format: TimecodeFormat::default(),
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use std::{
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use crate::{
+    notifications::plural,
+    project::{Project, ProjectError, PROJECT_EXTENSION},
+    task, App,
+};
+
+/// Result of a project dialog.
+pub enum ProjectEvent {
+    Opened {
+        project: Project,
+        path: Option<PathBuf>,
+    },
+    Saved {
+        revision: Option<u64>,
+        path: Option<PathBuf>,
+    },
+    /// Location picked for saving the project.
+    #[cfg(not(target_arch = "wasm32"))]
+    SaveTo(PathBuf),
+    Failed(ProjectError),
+    Canceled,
+}
+
+/// Action waiting for the user to decide what to do with unsaved changes.
+#[derive(Clone, Copy)]
+pub enum PendingAction {
+    New,
+    Open,
+    Quit,
+}
+
+pub struct ProjectFile {
+    channel: (Sender<ProjectEvent>, Receiver<ProjectEvent>),
+    pending: Option<PendingAction>,
+    /// Project is being saved before running the pending action.
+    saving: bool,
+    /// Unsaved changes were discarded, window can be closed.
+    allow_close: bool,
+    title: String,
+}
+impl Default for ProjectFile {
+    fn default() -> Self {
+        Self {
+            channel: channel(),
+            pending: None,
+            saving: false,
+            allow_close: false,
+            title: String::new(),
+        }
+    }
+}
+
+impl ProjectFile {
+    pub const NEW_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::N);
+    pub const OPEN_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+        egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+        egui::Key::O,
+    );
+    pub const SAVE_SHORTCUT: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+    pub const SAVE_AS_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+        egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+        egui::Key::S,
+    );
+}
+
+impl App {
+    pub fn new_project(&mut self) {
+        if self.behavior.state.is_dirty() {
+            self.project_file.pending = Some(PendingAction::New);
+        } else {
+            self.behavior.state.set_project(Project::default(), None);
+        }
+    }
+
+    pub fn open_project(&mut self, ctx: &egui::Context) {
+        if self.behavior.state.is_dirty() {
+            self.project_file.pending = Some(PendingAction::Open);
+        } else {
+            self.open_project_dialog(ctx);
+        }
+    }
+
+    pub fn save_project(&mut self, ctx: &egui::Context) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = self.behavior.state.project_path.clone() {
+            self.write_project(path, false);
+            return;
+        }
+
+        self.save_project_as(ctx);
+    }
+
+    /// Asks where to save the project, on native it's written once the location is known.
+    pub fn save_project_as(&mut self, ctx: &egui::Context) {
+        let sender = self.project_file.channel.0.clone();
+        let ctx = ctx.clone();
+        let file_name = format!("{}.{PROJECT_EXTENSION}", self.project_name());
+        let dialog = rfd::AsyncFileDialog::new()
+            .add_filter("Project", &[PROJECT_EXTENSION])
+            .set_file_name(file_name);
+
+        // Paths of media are stored relative to the project, so it can't be written before
+        #[cfg(not(target_arch = "wasm32"))]
+        task::spawn(async move {
+            let event = match dialog.save_file().await {
+                Some(file) => ProjectEvent::SaveTo(file.path().to_path_buf()),
+                None => ProjectEvent::Canceled,
+            };
+            let _ = sender.send(event);
+            ctx.request_repaint();
+        });
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let state = &self.behavior.state;
+            let text = match state.project.to_ron() {
+                Ok(text) => text,
+                Err(err) => {
+                    let _ = sender.send(ProjectEvent::Failed(err));
+                    return;
+                }
+            };
+            let revision = state.history.revision();
+
+            task::spawn(async move {
+                let event = if let Some(file) = dialog.save_file().await {
+                    match file.write(text.as_bytes()).await {
+                        Ok(()) => ProjectEvent::Saved {
+                            revision,
+                            path: None,
+                        },
+                        Err(err) => ProjectEvent::Failed(err.into()),
+                    }
+                } else {
+                    ProjectEvent::Canceled
+                };
+
+                let _ = sender.send(event);
+                ctx.request_repaint();
+            });
+        }
+    }
+
+    /// Writes the project file, `new_path` tells whether it's saved to another location.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_project(&mut self, path: PathBuf, new_path: bool) {
+        let state = &mut self.behavior.state;
+        state.project.set_location(&path);
+        let event = match state
+            .project
+            .to_ron()
+            .and_then(|text| Ok(std::fs::write(&path, text)?))
+        {
+            Ok(()) => ProjectEvent::Saved {
+                revision: state.history.revision(),
+                path: new_path.then_some(path),
+            },
+            Err(err) => ProjectEvent::Failed(err),
+        };
+        let _ = self.project_file.channel.0.send(event);
+    }
+
+    /// Closes the app, asking about unsaved changes first.
+    pub fn quit(&mut self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    fn open_project_dialog(&mut self, ctx: &egui::Context) {
+        let sender = self.project_file.channel.0.clone();
+        let ctx = ctx.clone();
+
+        task::spawn(async move {
+            let event = if let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("Project", &[PROJECT_EXTENSION])
+                .pick_file()
+                .await
+            {
+                #[cfg(not(target_arch = "wasm32"))]
+                let path = Some(file.path().to_path_buf());
+                #[cfg(target_arch = "wasm32")]
+                let path = None;
+
+                let text = String::from_utf8_lossy(&file.read().await).into_owned();
+                match Project::from_ron(&text) {
+                    Ok(mut project) => {
+                        project.load_files(path.as_deref());
+                        ProjectEvent::Opened { project, path }
+                    }
+                    Err(err) => ProjectEvent::Failed(err),
+                }
+            } else {
+                ProjectEvent::Canceled
+            };
+
+            let _ = sender.send(event);
+            ctx.request_repaint();
+        });
+    }
+
+    fn project_name(&self) -> String {
+        self.behavior
+            .state
+            .project_path
+            .as_ref()
+            .and_then(|path| path.file_stem())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Untitled".to_owned())
+    }
+
+    /// Runs the action without asking, unsaved changes are saved or discarded at this point.
+    fn run_pending_action(&mut self, ctx: &egui::Context, action: PendingAction) {
+        match action {
+            PendingAction::New => self.behavior.state.set_project(Project::default(), None),
+            PendingAction::Open => self.open_project_dialog(ctx),
+            PendingAction::Quit => {
+                self.project_file.allow_close = true;
+                self.quit(ctx);
+            }
+        }
+    }
+
+    fn unsaved_changes_ui(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.project_file.pending else {
+            return;
+        };
+        if self.project_file.saving {
+            return;
+        }
+
+        let mut choice = None;
+        let modal = egui::Modal::new(egui::Id::new("unsaved_changes")).show(ctx, |ui| {
+            ui.heading("Unsaved changes");
+            ui.label(format!(
+                "Do you want to save changes to \"{}\"?",
+                self.project_name()
+            ));
+            ui.add_space(8.);
+            ui.horizontal(|ui| {
+                if ui.button("Save").clicked() {
+                    choice = Some(true);
+                }
+                if ui.button("Don't save").clicked() {
+                    choice = Some(false);
+                }
+                if ui.button("Cancel").clicked() {
+                    self.project_file.pending = None;
+                }
+            });
+        });
+        if modal.should_close() {
+            self.project_file.pending = None;
+        }
+
+        match choice {
+            Some(true) => {
+                self.project_file.saving = true;
+                self.save_project(ctx);
+            }
+            Some(false) => {
+                self.project_file.pending = None;
+                self.run_pending_action(ctx, action);
+            }
+            None => {}
+        }
+    }
+
+    /// Handles dialog results, shortcuts, closing and the window title.
+    pub fn project_file_ui(&mut self, ctx: &egui::Context) {
+        while let Ok(event) = self.project_file.channel.1.try_recv() {
+            let saving = std::mem::take(&mut self.project_file.saving);
+            match event {
+                ProjectEvent::Opened { project, path } => {
+                    let state = &mut self.behavior.state;
+                    state.set_project(project, path);
+                    let offline = state.project.offline_files().count();
+                    if offline > 0 {
+                        state.notifications.warning(format!(
+                            "{offline} media {} offline, relink {} in the Files pane",
+                            plural(offline, "file is", "files are"),
+                            plural(offline, "it", "them"),
+                        ));
+                    }
+                }
+                ProjectEvent::Saved { revision, path } => {
+                    self.behavior.state.mark_saved(revision, path);
+                    let message = format!("Saved {}", self.project_name());
+                    self.behavior.state.notifications.info(message);
+                    if saving {
+                        if let Some(action) = self.project_file.pending.take() {
+                            self.run_pending_action(ctx, action);
+                        }
+                    }
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                ProjectEvent::SaveTo(path) => {
+                    // Keep waiting for the save to finish
+                    self.project_file.saving = saving;
+                    self.write_project(path, true);
+                }
+                ProjectEvent::Failed(err) => {
+                    self.behavior
+                        .state
+                        .notifications
+                        .error(format!("Project error: {err}"));
+                    self.project_file.pending = None;
+                }
+                ProjectEvent::Canceled => {
+                    if saving {
+                        self.project_file.pending = None;
+                    }
+                }
+            }
+        }
+
+        // Closing the window
+        if ctx.input(|i| i.viewport().close_requested())
+            && self.behavior.state.is_dirty()
+            && !self.project_file.allow_close
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.project_file.pending = Some(PendingAction::Quit);
+        }
+
+        // Shortcuts, with shift first because they match without it too
+        let (save_as, save, open, new) = ctx.input_mut(|i| {
+            (
+                i.consume_shortcut(&ProjectFile::SAVE_AS_SHORTCUT),
+                i.consume_shortcut(&ProjectFile::SAVE_SHORTCUT),
+                i.consume_shortcut(&ProjectFile::OPEN_SHORTCUT),
+                i.consume_shortcut(&ProjectFile::NEW_SHORTCUT),
+            )
+        });
+        if save_as {
+            self.save_project_as(ctx);
+        } else if save {
+            self.save_project(ctx);
+        }
+        if open {
+            self.open_project(ctx);
+        }
+        if new {
+            self.new_project();
+        }
+
+        self.unsaved_changes_ui(ctx);
+
+        let title = format!(
+            "{}{} - video editor",
+            if self.behavior.state.is_dirty() {
+                "*"
+            } else {
+                ""
+            },
+            self.project_name()
+        );
+        if title != self.project_file.title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.project_file.title = title;
+        }
+    }
+}
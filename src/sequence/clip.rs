@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use super::Marker;
+use crate::project::AssetId;
+
+/// Shared by clips edited together, e.g. the video and the sound of one file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LinkId(uuid::Uuid);
+impl LinkId {
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+}
+impl Default for LinkId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A piece of imported media placed on a track.
+///
+/// All positions are in frames of the owning sequence.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Clip {
+    pub file: AssetId,
+    /// Position of the first frame on the timeline.
+    pub start: i64,
+    /// First used frame of the source media.
+    pub source_in: i64,
+    /// Frame after the last used frame of the source media.
+    pub source_out: i64,
+    #[serde(default)]
+    pub link: Option<LinkId>,
+    /// Frames are of the source media, so markers move with the clip.
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+}
+impl Clip {
+    pub fn duration(&self) -> i64 {
+        self.source_out - self.source_in
+    }
+
+    /// Frame right after the last frame of the clip on the timeline.
+    pub fn end(&self) -> i64 {
+        self.start + self.duration()
+    }
+
+    pub fn contains(&self, frame: i64) -> bool {
+        (self.start..self.end()).contains(&frame)
+    }
+}
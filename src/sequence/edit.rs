@@ -0,0 +1,884 @@
+//! Edits made with the timeline tools.
+//!
+//! Each edit moves something by an offset in frames, which is limited so clips don't overlap,
+//! keep at least one frame and stay within their source media. The offset actually applied
+//! is returned. Linked clips of unlocked tracks are edited along, so they stay in sync.
+
+use std::collections::HashMap;
+
+use super::{Clip, LinkId, Sequence, Track};
+
+/// Side of a clip.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edge {
+    Start,
+    End,
+}
+
+/// Frames of the source media used by the clip, `None` for stills which can be any length.
+pub type SourceLength<'a> = &'a dyn Fn(&Clip) -> Option<i64>;
+
+/// Allowed offsets of an edit, narrowed by each limit.
+struct Limits {
+    min: i64,
+    max: i64,
+}
+impl Limits {
+    fn new() -> Self {
+        Self {
+            min: i64::MIN,
+            max: i64::MAX,
+        }
+    }
+
+    /// Source in point of the clip moves by the offset, the end stays.
+    fn move_in(&mut self, clip: &Clip) {
+        self.min = self.min.max(-clip.source_in);
+        self.max = self.max.min(clip.duration() - 1);
+    }
+
+    /// Source out point of the clip moves by the offset, the start stays.
+    fn move_out(&mut self, clip: &Clip, source_length: SourceLength<'_>) {
+        self.min = self.min.max(1 - clip.duration());
+        if let Some(length) = source_length(clip) {
+            self.max = self.max.min(length - clip.source_out);
+        }
+    }
+
+    /// Source in and out points of the clip move by the offset.
+    fn move_source(&mut self, clip: &Clip, source_length: SourceLength<'_>) {
+        self.min = self.min.max(-clip.source_in);
+        if let Some(length) = source_length(clip) {
+            self.max = self.max.min(length - clip.source_out);
+        }
+    }
+
+    /// Clip start moves by the offset, it can't go before the frame.
+    fn start_after(&mut self, clip: &Clip, frame: i64) {
+        self.min = self.min.max(frame - clip.start);
+    }
+
+    /// Clip end moves by the offset, it can't go past the frame.
+    fn end_before(&mut self, clip: &Clip, frame: i64) {
+        self.max = self.max.min(frame - clip.end());
+    }
+
+    /// Clips of the track from the frame on move by the offset, up to the clips before them.
+    fn shift_from(&mut self, track: &Track, frame: i64) {
+        let first = track.clips.partition_point(|clip| clip.start < frame);
+        if let Some(clip) = track.clips.get(first) {
+            self.start_after(clip, track.previous_end(first));
+        }
+    }
+
+    /// Limits of the opposite offset.
+    fn negated(&self) -> Self {
+        Self {
+            min: self.max.saturating_neg(),
+            max: self.min.saturating_neg(),
+        }
+    }
+
+    fn intersect(&mut self, other: &Self) {
+        self.min = self.min.max(other.min);
+        self.max = self.max.min(other.max);
+    }
+
+    /// Offset closest to the requested one, no change if the clips are already out of limits.
+    fn clamp(&self, offset: i64) -> i64 {
+        offset.clamp(self.min.min(0), self.max.max(0))
+    }
+}
+
+impl Track {
+    /// Cuts the clip at the index into two at the frame, the second half is put after it.
+    fn split(&mut self, index: usize, frame: i64, link: Option<LinkId>) {
+        let clip = &mut self.clips[index];
+        let mut second = clip.clone();
+        clip.source_out = clip.source_in + (frame - clip.start);
+        second.source_in = clip.source_out;
+        second.start = frame;
+        second.link = link;
+        self.clips.insert(index + 1, second);
+    }
+
+    /// End of the clip before the one at the index, or the start of the track.
+    fn previous_end(&self, index: usize) -> i64 {
+        index
+            .checked_sub(1)
+            .map_or(0, |previous| self.clips[previous].end())
+    }
+
+    /// Neighbour touching the clip at the index on the side, edits move the cut between them.
+    fn adjacent(&self, index: usize, edge: Edge) -> Option<usize> {
+        let clip = &self.clips[index];
+        match edge {
+            Edge::Start => index
+                .checked_sub(1)
+                .filter(|&previous| self.clips[previous].end() == clip.start),
+            Edge::End => Some(index + 1).filter(|&next| {
+                self.clips
+                    .get(next)
+                    .is_some_and(|next| next.start == clip.end())
+            }),
+        }
+    }
+
+    fn limit_trim(
+        &self,
+        index: usize,
+        edge: Edge,
+        limits: &mut Limits,
+        source_length: SourceLength<'_>,
+    ) {
+        let clip = &self.clips[index];
+        match edge {
+            Edge::Start => {
+                limits.move_in(clip);
+                limits.start_after(clip, self.previous_end(index));
+            }
+            Edge::End => {
+                limits.move_out(clip, source_length);
+                if let Some(next) = self.clips.get(index + 1) {
+                    limits.end_before(clip, next.start);
+                }
+            }
+        }
+    }
+
+    fn apply_trim(&mut self, index: usize, edge: Edge, offset: i64) {
+        let clip = &mut self.clips[index];
+        match edge {
+            Edge::Start => {
+                clip.start += offset;
+                clip.source_in += offset;
+            }
+            Edge::End => clip.source_out += offset,
+        }
+    }
+
+    /// Clips before and after the cut at the edge of the clip, `None` without a neighbour.
+    fn cut(&self, index: usize, edge: Edge) -> Option<(usize, usize)> {
+        let neighbour = self.adjacent(index, edge)?;
+        Some(match edge {
+            Edge::Start => (neighbour, index),
+            Edge::End => (index, neighbour),
+        })
+    }
+
+    /// Rolls the cut at the edge, or trims the clip without a neighbour there.
+    fn limit_roll(
+        &self,
+        index: usize,
+        edge: Edge,
+        limits: &mut Limits,
+        source_length: SourceLength<'_>,
+    ) {
+        match self.cut(index, edge) {
+            Some((before, after)) => {
+                limits.move_out(&self.clips[before], source_length);
+                limits.move_in(&self.clips[after]);
+            }
+            None => self.limit_trim(index, edge, limits, source_length),
+        }
+    }
+
+    fn apply_roll(&mut self, index: usize, edge: Edge, offset: i64) {
+        match self.cut(index, edge) {
+            Some((before, after)) => {
+                self.clips[before].source_out += offset;
+                let after = &mut self.clips[after];
+                after.start += offset;
+                after.source_in += offset;
+            }
+            None => self.apply_trim(index, edge, offset),
+        }
+    }
+
+    /// Touching neighbours are trimmed, sides without one are limited by the gap there.
+    fn limit_slide(&self, index: usize, limits: &mut Limits, source_length: SourceLength<'_>) {
+        let clip = &self.clips[index];
+        match self.adjacent(index, Edge::Start) {
+            Some(previous) => limits.move_out(&self.clips[previous], source_length),
+            None => limits.start_after(clip, self.previous_end(index)),
+        }
+        match self.adjacent(index, Edge::End) {
+            Some(next) => limits.move_in(&self.clips[next]),
+            None => {
+                if let Some(next) = self.clips.get(index + 1) {
+                    limits.end_before(clip, next.start);
+                }
+            }
+        }
+    }
+
+    fn apply_slide(&mut self, index: usize, offset: i64) {
+        let previous = self.adjacent(index, Edge::Start);
+        let next = self.adjacent(index, Edge::End);
+        self.clips[index].start += offset;
+        if let Some(previous) = previous {
+            self.clips[previous].source_out += offset;
+        }
+        if let Some(next) = next {
+            let next = &mut self.clips[next];
+            next.start += offset;
+            next.source_in += offset;
+        }
+    }
+}
+
+impl Sequence {
+    /// The clip and clips linked to it on other unlocked tracks, which are edited along.
+    fn link_group(&self, track: usize, index: usize) -> Vec<(usize, usize)> {
+        let mut group = vec![(track, index)];
+        let Some(link) = self.tracks[track].clips[index].link else {
+            return group;
+        };
+        for (other_index, other) in self.tracks.iter().enumerate() {
+            if other_index == track || other.locked {
+                continue;
+            }
+            if let Some(linked) = other.clips.iter().position(|c| c.link == Some(link)) {
+                group.push((other_index, linked));
+            }
+        }
+        group
+    }
+
+    /// Cuts clips of unlocked tracks accepted by the filter which cross the frame.
+    ///
+    /// Second halves of linked clips are linked to each other. Returns the number of cuts.
+    fn split_where(&mut self, frame: i64, filter: impl Fn(usize, &Clip) -> bool) -> usize {
+        let mut links: HashMap<LinkId, LinkId> = HashMap::new();
+        let mut count = 0;
+        for (track_index, track) in self.tracks.iter_mut().enumerate() {
+            if track.locked {
+                continue;
+            }
+            let index = track.clips.iter().position(|clip| {
+                clip.start < frame && frame < clip.end() && filter(track_index, clip)
+            });
+            if let Some(index) = index {
+                let link = track.clips[index]
+                    .link
+                    .map(|link| *links.entry(link).or_default());
+                track.split(index, frame, link);
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Cuts the clip and clips linked to it at the frame.
+    pub fn split_clip(&mut self, track: usize, index: usize, frame: i64) -> usize {
+        let Some(clip) = self.tracks.get(track).and_then(|t| t.clips.get(index)) else {
+            return 0;
+        };
+        let (file, start, link) = (clip.file, clip.start, clip.link);
+        self.split_where(frame, |track_index, clip| {
+            let is_clip = track_index == track && clip.file == file && clip.start == start;
+            is_clip || (link.is_some() && clip.link == link)
+        })
+    }
+
+    /// Cuts clips of all unlocked tracks at the frame.
+    pub fn split_all(&mut self, frame: i64) -> usize {
+        self.split_where(frame, |_, _| true)
+    }
+
+    /// Removes the clip and its linked clips from their tracks, the clip comes first.
+    pub fn remove_linked(&mut self, track: usize, index: usize) -> Vec<(usize, Clip)> {
+        self.link_group(track, index)
+            .into_iter()
+            .map(|(track, index)| (track, self.tracks[track].clips.remove(index)))
+            .collect()
+    }
+
+    /// Closes the gap removed clips left in `start..end`, clips of unlocked tracks from the
+    /// start on move back.
+    ///
+    /// All tracks move by the same offset to stay in sync, as far as each of them allows, so
+    /// gaps which were there before stay.
+    pub fn close_gap(&mut self, start: i64, end: i64) {
+        let mut limits = Limits::new();
+        for track in self.tracks.iter().filter(|track| !track.locked) {
+            limits.shift_from(track, start);
+        }
+        let offset = limits.clamp(start - end);
+
+        for track in self.tracks.iter_mut().filter(|track| !track.locked) {
+            let later = track.clips.partition_point(|clip| clip.start < start);
+            for clip in &mut track.clips[later..] {
+                clip.start += offset;
+            }
+        }
+    }
+
+    /// Inserts the clips at their starts, clips of unlocked tracks from there on move later
+    /// to make room.
+    ///
+    /// All tracks move by the length the clips span to stay in sync. Clips crossing the start
+    /// of an inserted clip are cut, second halves of linked clips are linked to each other.
+    pub fn insert_clips(&mut self, clips: Vec<(usize, Clip)>) {
+        let Some(start) = clips.iter().map(|(_, clip)| clip.start).min() else {
+            return;
+        };
+        let end = clips
+            .iter()
+            .map(|(_, clip)| clip.end())
+            .max()
+            .unwrap_or(start);
+
+        let mut links: HashMap<LinkId, LinkId> = HashMap::new();
+        for (track_index, track) in self.tracks.iter_mut().enumerate() {
+            if track.locked {
+                continue;
+            }
+            let inserted = clips
+                .iter()
+                .find(|(track, _)| *track == track_index)
+                .map(|(_, clip)| clip.start);
+            if let Some(frame) = inserted {
+                let crossing = track
+                    .clips
+                    .iter()
+                    .position(|clip| clip.start < frame && frame < clip.end());
+                if let Some(index) = crossing {
+                    let link = track.clips[index]
+                        .link
+                        .map(|link| *links.entry(link).or_default());
+                    track.split(index, frame, link);
+                }
+            }
+            let frame = inserted.unwrap_or(start);
+            let later = track.clips.partition_point(|clip| clip.start < frame);
+            for clip in &mut track.clips[later..] {
+                clip.start += end - start;
+            }
+        }
+        for (track, clip) in clips {
+            self.tracks[track].insert_clip(clip);
+        }
+    }
+
+    /// Moves an edge of the clip and its linked clips, up to their neighbours.
+    pub fn trim(
+        &mut self,
+        track: usize,
+        index: usize,
+        edge: Edge,
+        offset: i64,
+        source_length: SourceLength<'_>,
+    ) -> i64 {
+        let group = self.link_group(track, index);
+        let mut limits = Limits::new();
+        for &(track, index) in &group {
+            self.tracks[track].limit_trim(index, edge, &mut limits, source_length);
+        }
+        let offset = limits.clamp(offset);
+
+        for (track, index) in group {
+            self.tracks[track].apply_trim(index, edge, offset);
+        }
+        offset
+    }
+
+    /// Trims the clip and its linked clips without leaving a gap, later clips move along.
+    ///
+    /// The start of the clip stays when trimming its start, the content moves instead.
+    /// Clips of all unlocked tracks after the edit point move, so they stay in sync.
+    pub fn ripple_trim(
+        &mut self,
+        track: usize,
+        index: usize,
+        edge: Edge,
+        offset: i64,
+        source_length: SourceLength<'_>,
+    ) -> i64 {
+        let group = self.link_group(track, index);
+        let clip = &self.tracks[track].clips[index];
+        // Clips starting with the clip stay like it when trimming its start
+        let edit_point = match edge {
+            Edge::Start => clip.start + 1,
+            Edge::End => clip.end(),
+        };
+
+        let mut limits = Limits::new();
+        let mut shift = Limits::new();
+        for (track_index, track) in self.tracks.iter().enumerate() {
+            match group
+                .iter()
+                .find(|(group_track, _)| *group_track == track_index)
+            {
+                Some(&(_, index)) => match edge {
+                    Edge::Start => limits.move_in(&track.clips[index]),
+                    Edge::End => limits.move_out(&track.clips[index], source_length),
+                },
+                None if !track.locked => shift.shift_from(track, edit_point),
+                None => {}
+            }
+        }
+        // Trimming the start by the offset moves later clips the other way
+        match edge {
+            Edge::Start => limits.intersect(&shift.negated()),
+            Edge::End => limits.intersect(&shift),
+        }
+        let offset = limits.clamp(offset);
+        let shift = match edge {
+            Edge::Start => -offset,
+            Edge::End => offset,
+        };
+
+        for (track_index, track) in self.tracks.iter_mut().enumerate() {
+            let later = match group
+                .iter()
+                .find(|(group_track, _)| *group_track == track_index)
+            {
+                Some(&(_, index)) => {
+                    let clip = &mut track.clips[index];
+                    match edge {
+                        Edge::Start => clip.source_in += offset,
+                        Edge::End => clip.source_out += offset,
+                    }
+                    index + 1
+                }
+                None if !track.locked => track.clips.partition_point(|c| c.start < edit_point),
+                None => continue,
+            };
+            for clip in &mut track.clips[later..] {
+                clip.start += shift;
+            }
+        }
+        offset
+    }
+
+    /// Moves the cut between the clip and its neighbour, trimming both.
+    ///
+    /// Cuts of linked clips roll along. Without a neighbour right at the edge it's a trim.
+    pub fn roll(
+        &mut self,
+        track: usize,
+        index: usize,
+        edge: Edge,
+        offset: i64,
+        source_length: SourceLength<'_>,
+    ) -> i64 {
+        let group = self.link_group(track, index);
+        let mut limits = Limits::new();
+        for &(track, index) in &group {
+            self.tracks[track].limit_roll(index, edge, &mut limits, source_length);
+        }
+        let offset = limits.clamp(offset);
+
+        for (track, index) in group {
+            self.tracks[track].apply_roll(index, edge, offset);
+        }
+        offset
+    }
+
+    /// Shows another part of the source media, the clip and its linked clips stay in place.
+    pub fn slip(
+        &mut self,
+        track: usize,
+        index: usize,
+        offset: i64,
+        source_length: SourceLength<'_>,
+    ) -> i64 {
+        let group = self.link_group(track, index);
+        let mut limits = Limits::new();
+        for &(track, index) in &group {
+            limits.move_source(&self.tracks[track].clips[index], source_length);
+        }
+        let offset = limits.clamp(offset);
+
+        for (track, index) in group {
+            let clip = &mut self.tracks[track].clips[index];
+            clip.source_in += offset;
+            clip.source_out += offset;
+        }
+        offset
+    }
+
+    /// Moves the clip and its linked clips between their neighbours, which are trimmed so no
+    /// gap opens.
+    ///
+    /// Sides without a touching neighbour are limited by the gap there.
+    pub fn slide(
+        &mut self,
+        track: usize,
+        index: usize,
+        offset: i64,
+        source_length: SourceLength<'_>,
+    ) -> i64 {
+        let group = self.link_group(track, index);
+        let mut limits = Limits::new();
+        for &(track, index) in &group {
+            self.tracks[track].limit_slide(index, &mut limits, source_length);
+        }
+        let offset = limits.clamp(offset);
+
+        for (track, index) in group {
+            self.tracks[track].apply_slide(index, offset);
+        }
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{project::AssetId, sequence::TrackKind};
+
+    /// Sources are 100 frames long.
+    fn source_length(_clip: &Clip) -> Option<i64> {
+        Some(100)
+    }
+
+    fn clip(start: i64, source_in: i64, source_out: i64) -> Clip {
+        Clip {
+            file: AssetId::new(),
+            start,
+            source_in,
+            source_out,
+            link: None,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Sequence of one track with clips as `(start, source_in, source_out)`.
+    fn sequence(clips: &[(i64, i64, i64)]) -> Sequence {
+        let mut track = Track::new("V1", TrackKind::Video);
+        track.clips = clips
+            .iter()
+            .map(|&(start, source_in, source_out)| clip(start, source_in, source_out))
+            .collect();
+        Sequence {
+            tracks: vec![track],
+            ..Default::default()
+        }
+    }
+
+    /// Video and sound tracks with the same clips, the clips at each index are linked.
+    fn linked_sequence(clips: &[(i64, i64, i64)]) -> Sequence {
+        let mut sequence = sequence(clips);
+        let mut sound = sequence.tracks[0].clone();
+        sound.name = "A1".to_owned();
+        sound.kind = TrackKind::Audio;
+        sequence.tracks.push(sound);
+        for index in 0..clips.len() {
+            let link = Some(LinkId::new());
+            sequence.tracks[0].clips[index].link = link;
+            sequence.tracks[1].clips[index].link = link;
+        }
+        sequence
+    }
+
+    fn clips(sequence: &Sequence, track: usize) -> Vec<(i64, i64, i64)> {
+        sequence.tracks[track]
+            .clips
+            .iter()
+            .map(|clip| (clip.start, clip.source_in, clip.source_out))
+            .collect()
+    }
+
+    #[test]
+    fn trim_stops_at_neighbours() {
+        let mut sequence = sequence(&[(0, 10, 20), (25, 5, 30)]);
+
+        assert_eq!(sequence.trim(0, 0, Edge::End, 20, &source_length), 15);
+        // Clip keeps one frame
+        assert_eq!(sequence.trim(0, 0, Edge::End, -30, &source_length), -24);
+        // Source starts 5 frames before the clip
+        assert_eq!(sequence.trim(0, 1, Edge::Start, -10, &source_length), -5);
+        assert_eq!(clips(&sequence, 0), [(0, 10, 11), (20, 0, 30)]);
+    }
+
+    #[test]
+    fn trim_within_source() {
+        let mut sequence = sequence(&[(0, 0, 50)]);
+
+        assert_eq!(sequence.trim(0, 0, Edge::End, 80, &source_length), 50);
+        // Stills can be any length
+        assert_eq!(sequence.trim(0, 0, Edge::End, 80, &|_| None), 80);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 180)]);
+    }
+
+    #[test]
+    fn ripple_trim_moves_later_clips() {
+        let mut sequence = sequence(&[(0, 0, 10), (10, 0, 10), (30, 0, 5)]);
+
+        assert_eq!(sequence.ripple_trim(0, 0, Edge::End, 5, &source_length), 5);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 15), (15, 0, 10), (35, 0, 5)]);
+
+        // Start stays, the content and later clips move
+        assert_eq!(
+            sequence.ripple_trim(0, 1, Edge::Start, 3, &source_length),
+            3
+        );
+        assert_eq!(clips(&sequence, 0), [(0, 0, 15), (15, 3, 10), (32, 0, 5)]);
+    }
+
+    #[test]
+    fn roll_moves_the_cut() {
+        let mut sequence = sequence(&[(0, 0, 10), (10, 5, 20)]);
+
+        assert_eq!(sequence.roll(0, 0, Edge::End, 3, &source_length), 3);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 13), (13, 8, 20)]);
+
+        // Second clip runs out of source first
+        assert_eq!(sequence.roll(0, 1, Edge::Start, -20, &source_length), -8);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 5), (5, 0, 20)]);
+    }
+
+    #[test]
+    fn roll_without_neighbour_trims() {
+        let mut sequence = sequence(&[(0, 0, 10), (15, 0, 10)]);
+
+        assert_eq!(sequence.roll(0, 0, Edge::End, 10, &source_length), 5);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 15), (15, 0, 10)]);
+    }
+
+    #[test]
+    fn slip_stays_within_source() {
+        let mut sequence = sequence(&[(10, 20, 30)]);
+        let length = |_: &Clip| Some(40);
+
+        assert_eq!(sequence.slip(0, 0, 50, &length), 10);
+        assert_eq!(clips(&sequence, 0), [(10, 30, 40)]);
+        assert_eq!(sequence.slip(0, 0, -100, &length), -30);
+        assert_eq!(clips(&sequence, 0), [(10, 0, 10)]);
+    }
+
+    #[test]
+    fn slide_trims_neighbours() {
+        let mut sequence = sequence(&[(0, 0, 10), (10, 0, 10), (20, 5, 15)]);
+
+        assert_eq!(sequence.slide(0, 1, 3, &source_length), 3);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 13), (13, 0, 10), (23, 8, 15)]);
+
+        // Next clip runs out of source first
+        assert_eq!(sequence.slide(0, 1, -100, &source_length), -8);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 5), (5, 0, 10), (15, 0, 15)]);
+    }
+
+    #[test]
+    fn slide_within_gaps() {
+        let mut sequence = sequence(&[(0, 0, 10), (15, 0, 10), (40, 0, 10)]);
+
+        assert_eq!(sequence.slide(0, 1, -100, &source_length), -5);
+        // Now touching the previous clip, which gets longer
+        assert_eq!(sequence.slide(0, 1, 100, &source_length), 20);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 30), (30, 0, 10), (40, 0, 10)]);
+    }
+
+    #[test]
+    fn split_clip_links_second_halves() {
+        let mut sequence = sequence(&[(0, 10, 30), (20, 0, 10)]);
+        let link = LinkId::new();
+        sequence.tracks[0].clips[0].link = Some(link);
+        let mut sound = Track::new("A1", TrackKind::Audio);
+        sound.clips.push(Clip {
+            link: Some(link),
+            ..clip(0, 10, 30)
+        });
+        sequence.tracks.push(sound);
+
+        assert_eq!(sequence.split_clip(0, 0, 5), 2);
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track)[..2], [(0, 10, 15), (5, 15, 30)]);
+        }
+        // Other clips stay whole
+        assert_eq!(clips(&sequence, 0)[2], (20, 0, 10));
+
+        let second = sequence.tracks[0].clips[1].link;
+        assert!(second.is_some() && second != Some(link));
+        assert_eq!(sequence.tracks[1].clips[1].link, second);
+        assert_eq!(sequence.tracks[1].clips[0].link, Some(link));
+    }
+
+    #[test]
+    fn split_all_skips_locked_tracks() {
+        let mut sequence = sequence(&[(0, 0, 10), (10, 0, 10)]);
+        let mut locked = Track::new("V2", TrackKind::Video);
+        locked.clips.push(clip(0, 0, 20));
+        locked.locked = true;
+        sequence.tracks.push(locked);
+
+        // Nothing to cut right at the edit point
+        assert_eq!(sequence.split_all(10), 0);
+        assert_eq!(sequence.split_all(15), 1);
+        assert_eq!(clips(&sequence, 0), [(0, 0, 10), (10, 0, 5), (15, 5, 10)]);
+        assert_eq!(clips(&sequence, 1), [(0, 0, 20)]);
+    }
+
+    #[test]
+    fn linked_clips_trim_along() {
+        let mut sequence = linked_sequence(&[(0, 0, 10), (20, 0, 10)]);
+        // Sound has no gap after the first clip
+        sequence.tracks[1].clips[1].start = 10;
+
+        assert_eq!(sequence.trim(0, 0, Edge::End, 5, &source_length), 0);
+        assert_eq!(sequence.trim(0, 0, Edge::End, -3, &source_length), -3);
+        assert_eq!(sequence.trim(0, 0, Edge::Start, 2, &source_length), 2);
+        assert_eq!(clips(&sequence, 0), [(2, 2, 7), (20, 0, 10)]);
+        assert_eq!(clips(&sequence, 1), [(2, 2, 7), (10, 0, 10)]);
+    }
+
+    #[test]
+    fn ripple_trim_keeps_tracks_in_sync() {
+        let mut sequence = linked_sequence(&[(0, 0, 10), (10, 0, 10)]);
+        let mut music = Track::new("A2", TrackKind::Audio);
+        music.clips = vec![clip(2, 0, 3), clip(15, 0, 20)];
+        sequence.tracks.push(music);
+        let mut locked = Track::new("A3", TrackKind::Audio);
+        locked.clips = vec![clip(30, 0, 5)];
+        locked.locked = true;
+        sequence.tracks.push(locked);
+
+        assert_eq!(
+            sequence.ripple_trim(0, 0, Edge::End, -4, &source_length),
+            -4
+        );
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track), [(0, 0, 6), (6, 0, 10)]);
+        }
+        // Clips after the edit point move, the clip before it and locked tracks stay
+        assert_eq!(clips(&sequence, 2), [(2, 0, 3), (11, 0, 20)]);
+        assert_eq!(clips(&sequence, 3), [(30, 0, 5)]);
+
+        assert_eq!(
+            sequence.ripple_trim(0, 1, Edge::Start, 2, &source_length),
+            2
+        );
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track), [(0, 0, 6), (6, 2, 10)]);
+        }
+        assert_eq!(clips(&sequence, 2), [(2, 0, 3), (9, 0, 20)]);
+    }
+
+    #[test]
+    fn ripple_trim_stops_at_other_tracks() {
+        let mut sequence = sequence(&[(0, 0, 10), (10, 0, 10)]);
+        let mut music = Track::new("A1", TrackKind::Audio);
+        music.clips = vec![clip(0, 0, 8), clip(12, 0, 5)];
+        sequence.tracks.push(music);
+
+        // Music after the edit point can move back until the clip before it
+        assert_eq!(
+            sequence.ripple_trim(0, 0, Edge::End, -5, &source_length),
+            -4
+        );
+        assert_eq!(clips(&sequence, 0), [(0, 0, 6), (6, 0, 10)]);
+        assert_eq!(clips(&sequence, 1), [(0, 0, 8), (8, 0, 5)]);
+    }
+
+    #[test]
+    fn linked_cuts_roll_along() {
+        let mut sequence = linked_sequence(&[(0, 0, 10), (10, 5, 20)]);
+
+        assert_eq!(sequence.roll(0, 0, Edge::End, 3, &source_length), 3);
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track), [(0, 0, 13), (13, 8, 20)]);
+        }
+    }
+
+    #[test]
+    fn linked_clips_slip_and_slide_along() {
+        let mut sequence = linked_sequence(&[(0, 0, 10), (10, 20, 30), (20, 5, 15)]);
+        // Sound uses other part of a shorter source
+        let sound = AssetId::new();
+        let sound_clip = &mut sequence.tracks[1].clips[1];
+        sound_clip.file = sound;
+        sound_clip.source_in = 25;
+        sound_clip.source_out = 35;
+        let length = |clip: &Clip| Some(if clip.file == sound { 45 } else { 100 });
+
+        assert_eq!(sequence.slip(0, 1, 20, &length), 10);
+        assert_eq!(clips(&sequence, 0)[1], (10, 30, 40));
+        assert_eq!(clips(&sequence, 1)[1], (10, 35, 45));
+
+        // Next clips run out of source first
+        assert_eq!(sequence.slide(0, 1, -100, &length), -5);
+        for track in 0..2 {
+            let clips = clips(&sequence, track);
+            assert_eq!(
+                (clips[0], clips[1].0, clips[2]),
+                ((0, 0, 5), 5, (15, 0, 15))
+            );
+        }
+    }
+
+    #[test]
+    fn close_gap_keeps_earlier_gaps() {
+        let mut sequence = linked_sequence(&[(5, 0, 10), (15, 0, 10), (30, 0, 10)]);
+        let mut locked = Track::new("A2", TrackKind::Audio);
+        locked.clips = vec![clip(40, 0, 5)];
+        locked.locked = true;
+        sequence.tracks.push(locked);
+
+        let removed = sequence.remove_linked(0, 1);
+        assert_eq!(
+            removed.iter().map(|(track, _)| *track).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        sequence.close_gap(15, 25);
+        // Only the gap of the removed clips closes
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track), [(5, 0, 10), (20, 0, 10)]);
+        }
+        assert_eq!(clips(&sequence, 2), [(40, 0, 5)]);
+
+        // Other tracks move by the same offset, as far as they can
+        let mut music = Track::new("A3", TrackKind::Audio);
+        music.clips = vec![clip(0, 0, 12), clip(14, 0, 5)];
+        sequence.tracks.push(music);
+        sequence.remove_linked(0, 0);
+        sequence.close_gap(5, 15);
+        for track in 0..2 {
+            assert_eq!(clips(&sequence, track), [(18, 0, 10)]);
+        }
+        assert_eq!(clips(&sequence, 3), [(0, 0, 12), (12, 0, 5)]);
+    }
+
+    #[test]
+    fn insert_clips_makes_room_on_all_tracks() {
+        let mut sequence = linked_sequence(&[(0, 0, 10), (10, 0, 10)]);
+        let mut music = Track::new("A2", TrackKind::Audio);
+        music.clips = vec![clip(5, 0, 20), clip(30, 0, 5)];
+        sequence.tracks.push(music);
+
+        let inserted = |start, source_in, source_out| {
+            let mut clip = clip(start, source_in, source_out);
+            clip.link = Some(LinkId::new());
+            vec![(0, clip.clone()), (1, clip)]
+        };
+        sequence.insert_clips(inserted(10, 50, 56));
+        for track in 0..2 {
+            assert_eq!(
+                clips(&sequence, track),
+                [(0, 0, 10), (10, 50, 56), (16, 0, 10)]
+            );
+        }
+        // Clips crossing the frame stay on tracks without inserted clips
+        assert_eq!(clips(&sequence, 2), [(5, 0, 20), (36, 0, 5)]);
+
+        // Linked clips crossing the frame are cut together
+        sequence.insert_clips(inserted(13, 0, 4));
+        for track in 0..2 {
+            assert_eq!(
+                clips(&sequence, track),
+                [
+                    (0, 0, 10),
+                    (10, 50, 53),
+                    (13, 0, 4),
+                    (17, 53, 56),
+                    (20, 0, 10)
+                ]
+            );
+        }
+        let [video, sound] = [0, 1].map(|track| &sequence.tracks[track].clips);
+        assert!(video[3].link.is_some());
+        assert_eq!(video[3].link, sound[3].link);
+        assert_ne!(video[3].link, video[1].link);
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Clip, Sequence};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkerColor {
+    #[default]
+    Blue,
+    Green,
+    Yellow,
+    Orange,
+    Red,
+    Purple,
+}
+impl MarkerColor {
+    pub const ALL: [Self; 6] = [
+        Self::Blue,
+        Self::Green,
+        Self::Yellow,
+        Self::Orange,
+        Self::Red,
+        Self::Purple,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Blue => "Blue",
+            Self::Green => "Green",
+            Self::Yellow => "Yellow",
+            Self::Orange => "Orange",
+            Self::Red => "Red",
+            Self::Purple => "Purple",
+        }
+    }
+}
+
+/// Named note at a frame, a region when it has a duration.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Marker {
+    pub name: String,
+    pub color: MarkerColor,
+    pub notes: String,
+    /// Frame of the sequence, or of the source media for markers of clips.
+    pub frame: i64,
+    /// Length of a region, 0 for a single frame.
+    pub duration: i64,
+}
+impl Marker {
+    pub fn is_region(&self) -> bool {
+        self.duration > 0
+    }
+
+    pub fn end(&self) -> i64 {
+        self.frame + self.duration
+    }
+}
+
+/// Where a marker is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarkerLocation {
+    Sequence(usize),
+    Clip {
+        track: usize,
+        clip: usize,
+        marker: usize,
+    },
+}
+
+impl Clip {
+    /// Sequence frame of the source frame.
+    pub fn to_sequence_frame(&self, source_frame: i64) -> i64 {
+        self.start + source_frame - self.source_in
+    }
+
+    /// Indices of markers within the used part of the source media.
+    pub fn visible_markers(&self) -> impl Iterator<Item = (usize, &Marker)> {
+        self.markers
+            .iter()
+            .enumerate()
+            .filter(|(_, marker)| (self.source_in..self.source_out).contains(&marker.frame))
+    }
+}
+
+impl Sequence {
+    /// Adds marker keeping markers sorted and returns its index.
+    pub fn add_marker(&mut self, marker: Marker) -> usize {
+        let index = self.markers.partition_point(|m| m.frame <= marker.frame);
+        self.markers.insert(index, marker);
+        index
+    }
+
+    /// Markers of the sequence and visible markers of clips, with their sequence frame.
+    ///
+    /// Sorted by the frame.
+    pub fn all_markers(&self) -> Vec<(MarkerLocation, i64, &Marker)> {
+        let mut markers: Vec<_> = self
+            .markers
+            .iter()
+            .enumerate()
+            .map(|(index, marker)| (MarkerLocation::Sequence(index), marker.frame, marker))
+            .collect();
+        for (track_index, track) in self.tracks.iter().enumerate() {
+            for (clip_index, clip) in track.clips.iter().enumerate() {
+                markers.extend(clip.visible_markers().map(|(index, marker)| {
+                    let location = MarkerLocation::Clip {
+                        track: track_index,
+                        clip: clip_index,
+                        marker: index,
+                    };
+                    (location, clip.to_sequence_frame(marker.frame), marker)
+                }));
+            }
+        }
+        markers.sort_by_key(|(_, frame, _)| *frame);
+        markers
+    }
+
+    pub fn marker_mut(&mut self, location: MarkerLocation) -> Option<&mut Marker> {
+        match location {
+            MarkerLocation::Sequence(index) => self.markers.get_mut(index),
+            MarkerLocation::Clip {
+                track,
+                clip,
+                marker,
+            } => self
+                .tracks
+                .get_mut(track)?
+                .clips
+                .get_mut(clip)?
+                .markers
+                .get_mut(marker),
+        }
+    }
+
+    pub fn remove_marker(&mut self, location: MarkerLocation) {
+        match location {
+            MarkerLocation::Sequence(index) => {
+                if index < self.markers.len() {
+                    self.markers.remove(index);
+                }
+            }
+            MarkerLocation::Clip {
+                track,
+                clip,
+                marker,
+            } => {
+                let clip = self
+                    .tracks
+                    .get_mut(track)
+                    .and_then(|t| t.clips.get_mut(clip));
+                if let Some(clip) = clip.filter(|clip| marker < clip.markers.len()) {
+                    clip.markers.remove(marker);
+                }
+            }
+        }
+    }
+
+    /// Closest marker after the frame, from the sequence or a clip.
+    pub fn next_marker(&self, frame: i64) -> Option<i64> {
+        self.all_markers()
+            .into_iter()
+            .map(|(_, marker_frame, _)| marker_frame)
+            .find(|marker_frame| *marker_frame > frame)
+    }
+
+    /// Closest marker before the frame, from the sequence or a clip.
+    pub fn previous_marker(&self, frame: i64) -> Option<i64> {
+        self.all_markers()
+            .into_iter()
+            .map(|(_, marker_frame, _)| marker_frame)
+            .rev()
+            .find(|marker_frame| *marker_frame < frame)
+    }
+
+    /// Frames from the in point to the out point, when both are set in order.
+    pub fn in_out_range(&self) -> Option<(i64, i64)> {
+        let (in_point, out_point) = (self.in_point?, self.out_point?);
+        (in_point < out_point).then_some((in_point, out_point))
+    }
+
+    /// Name for a new marker, e.g. `Marker 3` after `Marker 2`, numbers of removed markers
+    /// aren't reused.
+    pub fn next_marker_name(&self) -> String {
+        let clip_markers = self
+            .tracks
+            .iter()
+            .flat_map(|track| &track.clips)
+            .flat_map(|clip| &clip.markers);
+        let last = self
+            .markers
+            .iter()
+            .chain(clip_markers)
+            .filter_map(|marker| marker.name.strip_prefix("Marker ")?.parse::<u32>().ok())
+            .max()
+            .unwrap_or(0);
+        format!("Marker {}", last + 1)
+    }
+}
@@ -0,0 +1,152 @@
+mod clip;
+pub use clip::{Clip, LinkId};
+mod edit;
+pub use edit::Edge;
+mod marker;
+pub use marker::{Marker, MarkerColor, MarkerLocation};
+mod time;
+pub use time::{FrameRate, TimecodeFormat, FRAMES_PER_FOOT};
+mod track;
+pub use track::{Track, TrackKind};
+
+use serde::{Deserialize, Serialize};
+
+/// Ordered set of video and audio tracks.
+///
+/// Video tracks always come before audio tracks, top to bottom as drawn in the timeline.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sequence {
+    pub name: String,
+    pub frame_rate: FrameRate,
+    pub tracks: Vec<Track>,
+    /// Sorted by their frame.
+    pub markers: Vec<Marker>,
+    pub in_point: Option<i64>,
+    pub out_point: Option<i64>,
+}
+impl Default for Sequence {
+    fn default() -> Self {
+        Self {
+            name: "Sequence 1".to_owned(),
+            frame_rate: FrameRate::default(),
+            tracks: vec![
+                Track::new("V1", TrackKind::Video),
+                Track::new("A1", TrackKind::Audio),
+            ],
+            markers: Vec::new(),
+            in_point: None,
+            out_point: None,
+        }
+    }
+}
+impl Sequence {
+    /// Frame right after the last clip of all tracks.
+    pub fn duration(&self) -> i64 {
+        self.tracks.iter().map(Track::end).max().unwrap_or(0)
+    }
+
+    /// Topmost clip of given kind under the frame.
+    pub fn clip_at(&self, kind: TrackKind, frame: i64) -> Option<&Clip> {
+        self.tracks
+            .iter()
+            .filter(|track| track.kind == kind)
+            .find_map(|track| track.clips.iter().find(|clip| clip.contains(frame)))
+    }
+
+    /// Adds new track of given kind and returns its index.
+    ///
+    /// New video tracks are stacked above existing ones, new audio tracks below.
+    pub fn add_track(&mut self, kind: TrackKind) -> usize {
+        let count = self.tracks.iter().filter(|t| t.kind == kind).count();
+        let track = match kind {
+            TrackKind::Video => Track::new(format!("V{}", count + 1), kind),
+            TrackKind::Audio => Track::new(format!("A{}", count + 1), kind),
+        };
+
+        let index = match kind {
+            TrackKind::Video => 0,
+            TrackKind::Audio => self.tracks.len(),
+        };
+        self.tracks.insert(index, track);
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::AssetId;
+
+    fn clip(start: i64, duration: i64) -> Clip {
+        Clip {
+            file: AssetId::new(),
+            start,
+            source_in: 0,
+            source_out: duration,
+            link: None,
+            markers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn clips_stay_sorted() {
+        let mut track = Track::new("V1", TrackKind::Video);
+        assert_eq!(track.insert_clip(clip(20, 5)), 0);
+        assert_eq!(track.insert_clip(clip(0, 5)), 0);
+        assert_eq!(track.insert_clip(clip(10, 5)), 1);
+        // Clip at the same frame goes after the existing one
+        assert_eq!(track.insert_clip(clip(10, 3)), 2);
+
+        let starts: Vec<_> = track.clips.iter().map(|clip| clip.start).collect();
+        assert_eq!(starts, [0, 10, 10, 20]);
+        assert_eq!(track.end(), 25);
+    }
+
+    #[test]
+    fn duration_ends_with_last_clip() {
+        let mut sequence = Sequence::default();
+        assert_eq!(sequence.duration(), 0);
+
+        sequence.tracks[0].insert_clip(clip(10, 20));
+        sequence.tracks[1].insert_clip(clip(0, 50));
+        assert_eq!(sequence.duration(), 50);
+
+        let clip = &sequence.tracks[0].clips[0];
+        assert!(clip.contains(10) && clip.contains(29));
+        assert!(!clip.contains(9) && !clip.contains(30));
+    }
+
+    #[test]
+    fn video_tracks_above_audio() {
+        let mut sequence = Sequence::default();
+        assert_eq!(sequence.add_track(TrackKind::Video), 0);
+        assert_eq!(sequence.add_track(TrackKind::Audio), 3);
+
+        let names: Vec<_> = sequence.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["V2", "V1", "A1", "A2"]);
+    }
+
+    #[test]
+    fn marker_numbers_are_not_reused() {
+        let marker = |name: &str, frame| Marker {
+            name: name.to_owned(),
+            frame,
+            ..Default::default()
+        };
+        let mut sequence = Sequence::default();
+        assert_eq!(sequence.next_marker_name(), "Marker 1");
+
+        // Marker 2 was removed
+        sequence.add_marker(marker("Marker 1", 0));
+        sequence.add_marker(marker("Marker 3", 10));
+        sequence.add_marker(marker("Intro", 20));
+        assert_eq!(sequence.next_marker_name(), "Marker 4");
+
+        // Markers of clips count too, even outside the used part of the media
+        let mut clip = clip(0, 10);
+        clip.markers.push(marker("Marker 7", 50));
+        sequence.tracks[0].clips.push(clip);
+        assert_eq!(sequence.next_marker_name(), "Marker 8");
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+
+/// Rational frame rate, e.g. `30000/1001` for 29.97 fps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameRate {
+    pub num: u32,
+    pub den: u32,
+}
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self::new(30, 1)
+    }
+}
+impl FrameRate {
+    /// Frame rates offered in the UI.
+    pub const COMMON: [Self; 8] = [
+        Self::new(24000, 1001),
+        Self::new(24, 1),
+        Self::new(25, 1),
+        Self::new(30000, 1001),
+        Self::new(30, 1),
+        Self::new(50, 1),
+        Self::new(60000, 1001),
+        Self::new(60, 1),
+    ];
+
+    pub const fn new(num: u32, den: u32) -> Self {
+        Self { num, den }
+    }
+
+    /// E.g. `23.976 fps` or `25 fps`.
+    pub fn name(self) -> String {
+        let fps = format!("{:.3}", self.fps());
+        format!("{} fps", fps.trim_end_matches('0').trim_end_matches('.'))
+    }
+
+    pub fn fps(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+
+    /// Frames per second rounded to the nearest whole number, used for timecode.
+    pub fn timebase(self) -> i64 {
+        self.fps().round().max(1.) as i64
+    }
+
+    pub fn frames_to_seconds(self, frames: i64) -> f64 {
+        frames as f64 * self.den as f64 / self.num as f64
+    }
+
+    /// Converts seconds to the nearest whole frame.
+    pub fn seconds_to_frames(self, seconds: f64) -> i64 {
+        (seconds * self.num as f64 / self.den as f64).round() as i64
+    }
+
+    /// Whether timecode drops frame numbers to keep up with the clock, at 29.97 and 59.94 fps.
+    pub fn is_drop_frame(self) -> bool {
+        self.den == 1001 && self.timebase() % 30 == 0
+    }
+
+    /// Formats frames as `HH:MM:SS:FF` timecode, drop-frame `HH:MM:SS;FF` when the rate needs it.
+    pub fn timecode(self, frames: i64) -> String {
+        let sign = if frames < 0 { "-" } else { "" };
+        let mut frames = frames.abs();
+        let timebase = self.timebase();
+
+        // Frame numbers 0 and 1 (0 to 3 at 59.94) are skipped each minute except every tenth
+        let separator = if self.is_drop_frame() {
+            let dropped = timebase / 15;
+            let per_minute = timebase * 60 - dropped;
+            let per_ten_minutes = per_minute * 10 + dropped;
+            let tens = frames / per_ten_minutes;
+            let rest = frames % per_ten_minutes;
+            frames += dropped * 9 * tens;
+            if rest > dropped {
+                frames += dropped * ((rest - dropped) / per_minute);
+            }
+            ';'
+        } else {
+            ':'
+        };
+
+        let ff = frames % timebase;
+        let total_seconds = frames / timebase;
+        let ss = total_seconds % 60;
+        let mm = (total_seconds / 60) % 60;
+        let hh = total_seconds / 3600;
+
+        format!("{sign}{hh:02}:{mm:02}:{ss:02}{separator}{ff:02}")
+    }
+
+    /// Formats frames as a time in the format.
+    pub fn format(self, frames: i64, format: TimecodeFormat) -> String {
+        match format {
+            TimecodeFormat::Smpte => self.timecode(frames),
+            TimecodeFormat::Frames => frames.to_string(),
+            TimecodeFormat::Seconds => format!("{:.3} s", self.frames_to_seconds(frames)),
+            TimecodeFormat::FeetFrames => {
+                let sign = if frames < 0 { "-" } else { "" };
+                let frames = frames.abs();
+                format!(
+                    "{sign}{}+{:02}",
+                    frames / FRAMES_PER_FOOT,
+                    frames % FRAMES_PER_FOOT
+                )
+            }
+        }
+    }
+}
+
+/// Frames in a foot of 35 mm film.
+pub const FRAMES_PER_FOOT: i64 = 16;
+
+/// How times are shown in the timeline and the viewer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimecodeFormat {
+    /// `HH:MM:SS:FF`, drop-frame at 29.97 and 59.94 fps.
+    #[default]
+    Smpte,
+    Frames,
+    Seconds,
+    /// Length of 35 mm film, e.g. `12+08`.
+    FeetFrames,
+}
+impl TimecodeFormat {
+    pub const ALL: [Self; 4] = [Self::Smpte, Self::Frames, Self::Seconds, Self::FeetFrames];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Smpte => "Timecode",
+            Self::Frames => "Frames",
+            Self::Seconds => "Seconds",
+            Self::FeetFrames => "Feet+frames",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NTSC: FrameRate = FrameRate::new(30000, 1001);
+
+    #[test]
+    fn non_drop_frame_timecode() {
+        let rate = FrameRate::new(25, 1);
+        assert!(!rate.is_drop_frame());
+        assert_eq!(rate.timecode(0), "00:00:00:00");
+        assert_eq!(rate.timecode(25 * 3600 + 25 * 61 + 3), "01:01:01:03");
+        assert_eq!(rate.timecode(-26), "-00:00:01:01");
+        // 23.976 counts 24 frames a second without dropping
+        assert!(!FrameRate::new(24000, 1001).is_drop_frame());
+        assert_eq!(FrameRate::new(24000, 1001).timecode(24 * 60), "00:01:00:00");
+    }
+
+    #[test]
+    fn frame_conversion() {
+        let rate = FrameRate::new(30000, 1001);
+        assert_eq!(rate.timebase(), 30);
+        assert_eq!(rate.seconds_to_frames(1.001), 30);
+        assert_eq!(rate.seconds_to_frames(60.), 1798);
+        assert!((rate.frames_to_seconds(30) - 1.001).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drop_frame_timecode() {
+        assert!(NTSC.is_drop_frame());
+        assert_eq!(NTSC.timecode(1799), "00:00:59;29");
+        // Frames 0 and 1 of each minute are skipped
+        assert_eq!(NTSC.timecode(1800), "00:01:00;02");
+        assert_eq!(NTSC.timecode(3597), "00:01:59;29");
+        assert_eq!(NTSC.timecode(3598), "00:02:00;02");
+        // Except every tenth minute
+        assert_eq!(NTSC.timecode(17981), "00:09:59;29");
+        assert_eq!(NTSC.timecode(17982), "00:10:00;00");
+        assert_eq!(NTSC.timecode(17982 * 6), "01:00:00;00");
+        assert_eq!(NTSC.timecode(-1800), "-00:01:00;02");
+    }
+
+    #[test]
+    fn drop_frame_timecode_at_59_94() {
+        let rate = FrameRate::new(60000, 1001);
+        assert!(rate.is_drop_frame());
+        assert_eq!(rate.timecode(3599), "00:00:59;59");
+        assert_eq!(rate.timecode(3600), "00:01:00;04");
+        assert_eq!(rate.timecode(35964), "00:10:00;00");
+    }
+
+    #[test]
+    fn formats() {
+        let rate = FrameRate::new(24, 1);
+        assert_eq!(rate.format(200, TimecodeFormat::Frames), "200");
+        assert_eq!(rate.format(36, TimecodeFormat::Seconds), "1.500 s");
+        assert_eq!(rate.format(200, TimecodeFormat::FeetFrames), "12+08");
+        assert_eq!(rate.format(-17, TimecodeFormat::FeetFrames), "-1+01");
+        assert_eq!(NTSC.name(), "29.97 fps");
+        assert_eq!(FrameRate::new(25, 1).name(), "25 fps");
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use super::Clip;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrackKind {
+    Video,
+    Audio,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Track {
+    pub name: String,
+    pub kind: TrackKind,
+    /// Clips sorted by their start frame.
+    pub clips: Vec<Clip>,
+    /// Locked tracks can't be edited in the timeline.
+    #[serde(default)]
+    pub locked: bool,
+}
+impl Track {
+    pub fn new(name: impl Into<String>, kind: TrackKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            clips: Vec::new(),
+            locked: false,
+        }
+    }
+
+    /// Inserts clip keeping clips sorted and returns its index.
+    pub fn insert_clip(&mut self, clip: Clip) -> usize {
+        let index = self.clips.partition_point(|c| c.start <= clip.start);
+        self.clips.insert(index, clip);
+        index
+    }
+
+    /// Start or end of a clip closest to the frame, magnetic edits insert clips there.
+    ///
+    /// The start of the track counts as a cut too.
+    pub fn closest_cut(&self, frame: i64) -> i64 {
+        self.clips
+            .iter()
+            .flat_map(|clip| [clip.start, clip.end()])
+            .fold(0, |closest, cut| {
+                if cut.abs_diff(frame) < closest.abs_diff(frame) {
+                    cut
+                } else {
+                    closest
+                }
+            })
+    }
+
+    /// Removes everything in `start..end`, trimming or splitting clips crossing the range.
+    pub fn clear_range(&mut self, start: i64, end: i64) {
+        let mut clips = Vec::with_capacity(self.clips.len() + 1);
+        for clip in self.clips.drain(..) {
+            if clip.end() <= start || clip.start >= end {
+                clips.push(clip);
+                continue;
+            }
+
+            if clip.start < start {
+                let mut head = clip.clone();
+                head.source_out = clip.source_in + (start - clip.start);
+                clips.push(head);
+            }
+            if clip.end() > end {
+                let mut tail = clip.clone();
+                tail.source_in = clip.source_in + (end - clip.start);
+                tail.start = end;
+                clips.push(tail);
+            }
+        }
+        self.clips = clips;
+    }
+
+    /// Frame right after the last clip.
+    pub fn end(&self) -> i64 {
+        self.clips.iter().map(Clip::end).max().unwrap_or(0)
+    }
+}
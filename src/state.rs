@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    history::{Command, EditSequence, History, OrganizeFiles},
+    notifications::Notifications,
+    playback::Playback,
+    project::{Organization, Project},
+    sequence::Sequence,
+    thumbnails::Thumbnails,
+};
+
+/// State shared between all panes.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub project: Project,
+    /// Where the project was last opened from or saved to.
+    pub project_path: Option<PathBuf>,
+    pub playback: Playback,
+    pub thumbnails: Thumbnails,
+
+    #[serde(skip)]
+    pub history: History,
+    #[serde(skip)]
+    pub notifications: Notifications,
+    /// History revision at the time the project was saved.
+    #[serde(skip)]
+    saved_revision: Option<u64>,
+    /// Whether the project had unsaved changes when the app state was stored.
+    ///
+    /// Restored edits have no history, without it they'd count as saved.
+    unsaved_changes: bool,
+}
+impl State {
+    /// Applies undoable command to the project.
+    pub fn execute(&mut self, command: impl Command + 'static) {
+        self.history.execute(&mut self.project, command);
+    }
+
+    /// Edits copy of the sequence and applies it as one undoable step.
+    pub fn edit_sequence(&mut self, name: impl Into<String>, edit: impl FnOnce(&mut Sequence)) {
+        let before = self.project.sequence.clone();
+        let mut after = before.clone();
+        edit(&mut after);
+
+        self.execute(EditSequence::new(name, before, after));
+    }
+
+    /// Edits names, order and bins of files and applies it as one undoable step.
+    pub fn organize_files(
+        &mut self,
+        name: impl Into<String>,
+        edit: impl FnOnce(&mut Organization),
+    ) {
+        let before = self.project.organization();
+        let mut after = before.clone();
+        edit(&mut after);
+
+        self.execute(OrganizeFiles::new(name, before, after));
+    }
+
+    /// Replaces the whole project, e.g. when opening a file.
+    pub fn set_project(&mut self, project: Project, path: Option<PathBuf>) {
+        self.project = project;
+        self.project_path = path;
+        self.playback = Playback::default();
+        self.history.clear();
+        self.saved_revision = self.history.revision();
+    }
+
+    /// Whether the project changed since it was last saved.
+    pub fn is_dirty(&self) -> bool {
+        self.history.revision() != self.saved_revision
+    }
+
+    /// Remembers unsaved changes, called before the app state is stored.
+    pub fn store_unsaved_changes(&mut self) {
+        self.unsaved_changes = self.is_dirty();
+    }
+
+    /// Keeps restored unsaved changes dirty, called after the app state is restored.
+    pub fn restore_unsaved_changes(&mut self) {
+        if self.unsaved_changes {
+            // No command has revision 0, so the project stays dirty until it's saved
+            self.saved_revision = Some(0);
+        }
+    }
+
+    pub fn mark_saved(&mut self, revision: Option<u64>, path: Option<PathBuf>) {
+        self.saved_revision = revision;
+        if path.is_some() {
+            self.project_path = path;
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::future::Future;
+
+/// Runs future in the background, so it doesn't block the frame loop.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn(future: impl Future<Output = ()> + Send + 'static) {
+    async_std::task::spawn(future);
+}
+
+/// Runs future in the background, so it doesn't block the frame loop.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn(future: impl Future<Output = ()> + 'static) {
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+/// Runs CPU heavy work on a background thread.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_blocking(f: impl FnOnce() + Send + 'static) {
+    async_std::task::spawn_blocking(f);
+}
+
+/// Runs CPU heavy work right away, there are no threads on the web.
+#[cfg(target_arch = "wasm32")]
+pub fn spawn_blocking(f: impl FnOnce() + 'static) {
+    f();
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    media::waveform::Waveform,
+    project::{AssetId, Content, File, FileType},
+    task,
+};
+
+/// Longer side of thumbnails in pixels.
+const THUMBNAIL_SIZE: u32 = 256;
+/// Maximal number of files decoded at once.
+const MAX_PENDING: usize = 4;
+
+struct Thumbnail {
+    texture: egui::TextureHandle,
+    /// Size of the texture in bytes.
+    size: usize,
+    /// Pass in which the thumbnail was last shown.
+    last_used: u64,
+}
+
+/// Result sent back from a background thread, `None` if decoding failed.
+enum Decoded {
+    Image(AssetId, Option<egui::ColorImage>),
+    Waveform(AssetId, Option<Waveform>),
+}
+
+/// Downscaled textures of imported files and waveforms of sounds.
+///
+/// Each file is decoded once, least recently shown textures are freed when the cache
+/// gets over the memory budget. Waveforms are small and stay cached.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thumbnails {
+    /// Memory available for textures, in bytes.
+    pub memory_budget: usize,
+
+    #[serde(skip)]
+    cache: HashMap<AssetId, Thumbnail>,
+    #[serde(skip)]
+    memory_used: usize,
+    #[serde(skip)]
+    waveforms: HashMap<AssetId, Waveform>,
+    #[serde(skip)]
+    pending: HashSet<AssetId>,
+    /// Files which couldn't be decoded, not tried again.
+    #[serde(skip)]
+    failed: HashSet<AssetId>,
+    #[serde(skip)]
+    channel: (Sender<Decoded>, Receiver<Decoded>),
+}
+impl Default for Thumbnails {
+    fn default() -> Self {
+        Self {
+            memory_budget: 256 * 1024 * 1024,
+            cache: HashMap::new(),
+            memory_used: 0,
+            waveforms: HashMap::new(),
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            channel: channel(),
+        }
+    }
+}
+impl Thumbnails {
+    /// Thumbnail of the file, starts decoding it when not cached yet.
+    pub fn get(&mut self, ctx: &egui::Context, file: &File) -> Option<&egui::TextureHandle> {
+        if !self.cache.contains_key(&file.id)
+            && !self.pending.contains(&file.id)
+            && !self.failed.contains(&file.id)
+            && self.pending.len() < MAX_PENDING
+        {
+            self.decode(ctx, file);
+        }
+
+        let thumbnail = self.cache.get_mut(&file.id)?;
+        thumbnail.last_used = ctx.cumulative_pass_nr();
+        Some(&thumbnail.texture)
+    }
+
+    /// Waveform of the sound, starts decoding it when not cached yet.
+    pub fn waveform(&mut self, ctx: &egui::Context, file: &File) -> Option<&Waveform> {
+        if !self.waveforms.contains_key(&file.id)
+            && !self.pending.contains(&file.id)
+            && !self.failed.contains(&file.id)
+            && self.pending.len() < MAX_PENDING
+        {
+            self.decode_waveform(ctx, file);
+        }
+
+        self.waveforms.get(&file.id)
+    }
+
+    /// Whether the file is being decoded or waits for it.
+    pub fn is_loading(&self, file: &File) -> bool {
+        if self.failed.contains(&file.id) {
+            return false;
+        }
+
+        match file.r#type {
+            FileType::Sound => !self.waveforms.contains_key(&file.id) && !file.content.is_empty(),
+            FileType::Image | FileType::Video => {
+                !self.cache.contains_key(&file.id) && Self::source(file).is_some()
+            }
+        }
+    }
+
+    /// Uploads decoded thumbnails and frees memory, called once per frame.
+    pub fn update(&mut self, ctx: &egui::Context) {
+        while let Ok(decoded) = self.channel.1.try_recv() {
+            match decoded {
+                Decoded::Image(id, Some(image)) => {
+                    self.pending.remove(&id);
+                    self.insert_texture(ctx, id, image);
+                }
+                Decoded::Waveform(id, Some(waveform)) => {
+                    self.pending.remove(&id);
+                    self.waveforms.insert(id, waveform);
+                }
+                Decoded::Image(id, None) | Decoded::Waveform(id, None) => {
+                    self.pending.remove(&id);
+                    self.failed.insert(id);
+                }
+            }
+        }
+
+        self.evict(ctx.cumulative_pass_nr());
+    }
+
+    fn insert_texture(&mut self, ctx: &egui::Context, id: AssetId, image: egui::ColorImage) {
+        let size = image.pixels.len() * 4;
+        let texture = ctx.load_texture(format!("thumbnail-{id:?}"), image, Default::default());
+        self.memory_used += size;
+        let thumbnail = Thumbnail {
+            texture,
+            size,
+            last_used: ctx.cumulative_pass_nr(),
+        };
+        if let Some(old) = self.cache.insert(id, thumbnail) {
+            self.memory_used -= old.size;
+        }
+    }
+
+    /// Frees least recently shown textures until the cache fits into the budget.
+    ///
+    /// Textures shown in the last pass are kept even over the budget.
+    fn evict(&mut self, pass: u64) {
+        while self.memory_used > self.memory_budget {
+            let Some((&id, _)) = self
+                .cache
+                .iter()
+                .filter(|(_, thumbnail)| thumbnail.last_used + 1 < pass)
+                .min_by_key(|(_, thumbnail)| thumbnail.last_used)
+            else {
+                break;
+            };
+            if let Some(thumbnail) = self.cache.remove(&id) {
+                self.memory_used -= thumbnail.size;
+            }
+        }
+    }
+
+    /// Encoded image the thumbnail is made of.
+    fn source(file: &File) -> Option<Content> {
+        match file.r#type {
+            FileType::Image => Some(file.content.clone()),
+            FileType::Video => match &file.image_sequence {
+                Some(image_sequence) => image_sequence.thumbnail().cloned(),
+                None => file.video_thumbnail.clone().map(Content::from),
+            },
+            FileType::Sound => None,
+        }
+        .filter(|content| !content.is_empty())
+    }
+
+    fn decode(&mut self, ctx: &egui::Context, file: &File) {
+        let Some(bytes) = Self::source(file) else {
+            return;
+        };
+        let is_svg = file.name.to_lowercase().ends_with(".svg");
+        let id = file.id;
+        let sender = self.channel.0.clone();
+        let ctx = ctx.clone();
+
+        self.pending.insert(id);
+        task::spawn_blocking(move || {
+            let image = if is_svg {
+                egui_extras::image::load_svg_bytes_with_size(
+                    &bytes,
+                    Some(egui::SizeHint::Size(THUMBNAIL_SIZE, THUMBNAIL_SIZE)),
+                )
+                .map_err(|err| log::warn!("Failed to decode thumbnail: {err}"))
+                .ok()
+            } else {
+                image::load_from_memory(&bytes)
+                    .map_err(|err| log::warn!("Failed to decode thumbnail: {err}"))
+                    .ok()
+                    .map(|image| {
+                        let image = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgba8();
+                        egui::ColorImage::from_rgba_unmultiplied(
+                            [image.width() as usize, image.height() as usize],
+                            image.as_flat_samples().as_slice(),
+                        )
+                    })
+            };
+
+            let _ = sender.send(Decoded::Image(id, image));
+            ctx.request_repaint();
+        });
+    }
+
+    fn decode_waveform(&mut self, ctx: &egui::Context, file: &File) {
+        if file.content.is_empty() {
+            return;
+        }
+        let content = file.content.clone();
+        let id = file.id;
+        let sender = self.channel.0.clone();
+        let ctx = ctx.clone();
+
+        self.pending.insert(id);
+        task::spawn_blocking(move || {
+            let waveform = Waveform::decode(&content);
+            let _ = sender.send(Decoded::Waveform(id, waveform));
+            ctx.request_repaint();
+        });
+    }
+}
@@ -1,27 +1,30 @@
 use serde::{Deserialize, Serialize};
 
-use crate::panes::{Files, PaneBehavior as _, Timeline, Video};
+use crate::{
+    panes::{Files, Markers, PaneBehavior as _, Timeline, Video},
+    state::State,
+};
 
-#[derive(Serialize, Deserialize)]
+#[derive(PartialEq, Serialize, Deserialize)]
 pub enum Pane {
     Files,
     Timeline,
     Video,
+    Markers,
 }
 
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TreeBehavior {
     pub files: Files,
+    #[serde(skip)]
     timeline: Timeline,
+    #[serde(skip)]
     video: Video,
-}
-impl Default for TreeBehavior {
-    fn default() -> Self {
-        Self {
-            files: Files::default(),
-            timeline: Timeline {},
-            video: Video {},
-        }
-    }
+    #[serde(skip)]
+    markers: Markers,
+
+    pub state: State,
 }
 
 impl egui_tiles::Behavior<Pane> for TreeBehavior {
@@ -30,6 +33,7 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
             Pane::Files => "Files",
             Pane::Timeline => "Timeline",
             Pane::Video => "Video",
+            Pane::Markers => "Markers",
         }
         .into()
     }
@@ -41,9 +45,10 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
         pane: &mut Pane,
     ) -> egui_tiles::UiResponse {
         match pane {
-            Pane::Files => self.files.ui(ui),
-            Pane::Timeline => self.timeline.ui(ui),
-            Pane::Video => self.video.ui(ui),
+            Pane::Files => self.files.ui(ui, &mut self.state),
+            Pane::Timeline => self.timeline.ui(ui, &mut self.state),
+            Pane::Video => self.video.ui(ui, &mut self.state),
+            Pane::Markers => self.markers.ui(ui, &mut self.state),
         };
 
         Default::default()
@@ -51,18 +56,20 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
 
     fn top_bar_right_ui(
         &mut self,
-        _tiles: &egui_tiles::Tiles<Pane>,
+        tiles: &egui_tiles::Tiles<Pane>,
         ui: &mut egui::Ui,
-        tile_id: egui_tiles::TileId,
-        _tabs: &egui_tiles::Tabs,
+        _tile_id: egui_tiles::TileId,
+        tabs: &egui_tiles::Tabs,
         _scroll_offset: &mut f32,
     ) {
         ui.add_space(6.);
-        match tile_id.0 {
-            1 => self.files.top_bar_ui(ui),
-            2 => self.timeline.top_bar_ui(ui),
-            3 => self.video.top_bar_ui(ui),
-            _ => {}
+        // Tile ids change when panes are moved or added to stored layouts
+        match tabs.active.and_then(|active| tiles.get_pane(&active)) {
+            Some(Pane::Files) => self.files.top_bar_ui(ui, &mut self.state),
+            Some(Pane::Timeline) => self.timeline.top_bar_ui(ui, &mut self.state),
+            Some(Pane::Video) => self.video.top_bar_ui(ui, &mut self.state),
+            Some(Pane::Markers) => self.markers.top_bar_ui(ui, &mut self.state),
+            None => {}
         }
     }
 
@@ -80,13 +87,16 @@ pub fn create_tree() -> egui_tiles::Tree<Pane> {
     let files = tiles.insert_pane(Pane::Files);
     let timeline = tiles.insert_pane(Pane::Timeline);
     let video = tiles.insert_pane(Pane::Video);
+    let markers = tiles.insert_pane(Pane::Markers);
 
     let mut inner_top = egui_tiles::Linear {
-        children: vec![files, video],
+        children: vec![files, video, markers],
         dir: egui_tiles::LinearDir::Horizontal,
         ..Default::default()
     };
-    inner_top.shares.set_share(files, 0.5);
+    inner_top.shares.set_share(files, 0.35);
+    inner_top.shares.set_share(video, 0.4);
+    inner_top.shares.set_share(markers, 0.25);
 
     let top = tiles.insert_container(egui_tiles::Container::Linear(inner_top));
 
@@ -101,3 +111,54 @@ pub fn create_tree() -> egui_tiles::Tree<Pane> {
 
     egui_tiles::Tree::new("tree", root, tiles)
 }
+
+/// Adds panes introduced after the layout was stored, next to the video pane.
+pub fn migrate_tree(tree: &mut egui_tiles::Tree<Pane>) {
+    if tree.tiles.find_pane(&Pane::Markers).is_some() {
+        return;
+    }
+
+    // Video pane is wrapped in tabs, its neighbours are in the container above
+    let mut parent = tree.tiles.find_pane(&Pane::Video);
+    for _ in 0..2 {
+        parent = parent.and_then(|id| tree.tiles.parent_of(id));
+    }
+    let Some(parent) = parent.or(tree.root) else {
+        *tree = create_tree();
+        return;
+    };
+
+    let markers = tree.tiles.insert_pane(Pane::Markers);
+    match tree.tiles.get_mut(parent) {
+        Some(egui_tiles::Tile::Container(container)) => container.add_child(markers),
+        _ => *tree = create_tree(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_layout_gets_markers_pane() {
+        let mut tiles = egui_tiles::Tiles::default();
+        let files = tiles.insert_pane(Pane::Files);
+        let video = tiles.insert_pane(Pane::Video);
+        let timeline = tiles.insert_pane(Pane::Timeline);
+        let files = tiles.insert_tab_tile(vec![files]);
+        let video = tiles.insert_tab_tile(vec![video]);
+        let timeline = tiles.insert_tab_tile(vec![timeline]);
+        let top = tiles.insert_horizontal_tile(vec![files, video]);
+        let root = tiles.insert_vertical_tile(vec![top, timeline]);
+        let mut tree = egui_tiles::Tree::new("tree", root, tiles);
+
+        migrate_tree(&mut tree);
+        let markers = tree.tiles.find_pane(&Pane::Markers).unwrap();
+        assert_eq!(tree.tiles.parent_of(markers), Some(top));
+
+        // Already migrated layout stays as it is
+        let count = tree.tiles.len();
+        migrate_tree(&mut tree);
+        assert_eq!(tree.tiles.len(), count);
+    }
+}